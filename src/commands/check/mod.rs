@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context};
+use colored::Colorize;
+use log::info;
+use structopt::StructOpt;
+
+use crate::{git::GitRepository, utils::outro};
+
+use super::{
+    commit::{lint_message, strip_comments, strip_scissors},
+    config::AutocommitConfig,
+};
+
+#[derive(Debug, StructOpt)]
+pub struct CheckCommand {
+    #[structopt(
+        parse(from_os_str),
+        help = "File containing the commit message, e.g. the one a commit-msg hook receives"
+    )]
+    message_file: PathBuf,
+}
+
+impl CheckCommand {
+    pub async fn run(&self, config: &AutocommitConfig) -> anyhow::Result<()> {
+        let contents = tokio::fs::read_to_string(&self.message_file)
+            .await
+            .with_context(|| format!("Failed to read {}", self.message_file.display()))?;
+        let comment_char = GitRepository::get_comment_char().unwrap_or('#');
+        let message = strip_comments(strip_scissors(&contents, comment_char), comment_char);
+
+        let violations = lint_message(&message, config);
+        if violations.is_empty() {
+            outro(&format!(
+                "{} The commit message follows the rules",
+                "✔".green()
+            ));
+            info!("Commit message checked");
+            return Ok(());
+        }
+
+        let details = violations
+            .iter()
+            .map(|violation| format!("- {}", violation))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Err(anyhow!(
+            "The commit message doesn't follow the rules:\n{}",
+            details
+        ))
+    }
+}
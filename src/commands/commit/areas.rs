@@ -0,0 +1,163 @@
+use std::sync::{Mutex, PoisonError};
+
+use futures::future::try_join_all;
+use log::{debug, warn};
+
+use crate::{commands::config::AutocommitConfig, utils::MessageCache};
+
+use super::{
+    chat_context::{fit_diffs_to_tokens, Candidate, ChatContext},
+    CommitCommand,
+};
+
+/// The area of the files at the top level of the repository.
+const ROOT_AREA: &str = "(root)";
+
+/// The changed path named by a file header of the diff, if `line` is one.
+fn header_path(line: &str) -> Option<&str> {
+    if let Some(rest) = line.strip_prefix("diff --git a/") {
+        let header = rest.lines().next().unwrap_or_default();
+        return header.split(" b/").next();
+    }
+    line.strip_prefix("Binary file ")
+        .and_then(|rest| rest.rsplit_once(" changed ("))
+        .map(|(path, _)| path)
+}
+
+/// The top-level directory of `path`.
+fn area_of(path: &str) -> &str {
+    match path.split_once('/') {
+        Some((directory, _)) => directory,
+        None => ROOT_AREA,
+    }
+}
+
+/// Groups `files` by their top-level directory, in the order the areas
+/// first appear.
+pub fn group_files_by_area(files: &[String]) -> Vec<(String, Vec<String>)> {
+    let mut areas: Vec<(String, Vec<String>)> = Vec::new();
+    for file in files {
+        let area = area_of(file);
+        match areas.iter_mut().find(|(name, _)| name == area) {
+            Some((_, area_files)) => area_files.push(file.clone()),
+            None => areas.push((area.to_owned(), vec![file.clone()])),
+        }
+    }
+    areas
+}
+
+/// Groups the diff lines by the top-level directory of their file, in the
+/// order the areas first appear.
+pub fn group_diff_by_area(diff: &[String]) -> Vec<(String, Vec<String>)> {
+    let mut areas: Vec<(String, Vec<String>)> = Vec::new();
+    let mut current = None;
+    for line in diff {
+        if let Some(path) = header_path(line) {
+            let area = area_of(path);
+            current = Some(
+                areas
+                    .iter()
+                    .position(|(name, _)| name == area)
+                    .unwrap_or_else(|| {
+                        areas.push((area.to_owned(), Vec::new()));
+                        areas.len() - 1
+                    }),
+            );
+        }
+        match current {
+            Some(index) => areas[index].1.push(line.clone()),
+            None => {
+                if areas.is_empty() {
+                    areas.push((ROOT_AREA.to_owned(), Vec::new()));
+                }
+                areas[0].1.push(line.clone());
+            }
+        }
+    }
+    areas
+}
+
+/// Joins the sections generated for each area into combined candidates, the
+/// first sections forming the first candidate and so on.
+pub fn combine_sections(sections: Vec<Vec<Candidate>>) -> Vec<Candidate> {
+    let count = sections.iter().map(Vec::len).min().unwrap_or_default();
+    (0..count)
+        .map(|i| Candidate {
+            message: sections
+                .iter()
+                .map(|candidates| candidates[i].message.trim())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            temperature: sections[0][i].temperature,
+        })
+        .collect()
+}
+
+/// Generates `num_messages` messages for the diff of each area, all at once,
+/// and combines them into one message per candidate. Every area gets the
+/// context files and the draft, and its subject verbs are checked against
+/// its own diff. The message cache is loaded once and shared by all areas.
+pub async fn generate_area_candidates(
+    config: &AutocommitConfig,
+    areas: &[(String, Vec<String>)],
+    subject_only: bool,
+    context_files: &[(String, String)],
+    draft: Option<&str>,
+    num_messages: usize,
+    use_cache: bool,
+) -> anyhow::Result<Vec<Candidate>> {
+    let max_diff_lines = config
+        .config_data
+        .max_diff_lines
+        .get_value_ref()
+        .get_inner_value();
+    let max_diff_tokens = config
+        .config_data
+        .max_diff_tokens
+        .get_value_ref()
+        .get_inner_value();
+
+    let cache = if use_cache {
+        Some(Mutex::new(MessageCache::load().await?))
+    } else {
+        None
+    };
+    let shared_cache = cache.as_ref();
+
+    debug!("Generating messages for {} areas", areas.len());
+    let tasks = areas.iter().map(|(_, diff)| async move {
+        let diff = match max_diff_tokens {
+            Some(max_diff_tokens) => fit_diffs_to_tokens(diff, max_diff_tokens),
+            None => diff.concat(),
+        };
+        let mut chat_context = ChatContext::get_initial_context(config, subject_only);
+        chat_context.add_diff(&diff, max_diff_lines);
+        if !context_files.is_empty() {
+            chat_context.add_context_files(context_files);
+        }
+        if let Some(draft) = draft {
+            chat_context.add_draft(draft);
+        }
+        let candidates = match shared_cache {
+            Some(cache) => {
+                chat_context
+                    .generate_cached_candidates(config, num_messages, None, cache)
+                    .await?
+            }
+            None => {
+                chat_context
+                    .generate_candidates(config, num_messages, None)
+                    .await?
+            }
+        };
+        CommitCommand::correct_subject_verbs(config, &chat_context, candidates).await
+    });
+    let sections = try_join_all(tasks).await?;
+    if let Some(cache) = cache {
+        let cache = cache.into_inner().unwrap_or_else(PoisonError::into_inner);
+        if let Err(e) = cache.save().await {
+            warn!("Failed to save the message cache: {}", e);
+        }
+    }
+    Ok(combine_sections(sections))
+}
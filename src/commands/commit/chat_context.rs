@@ -1,13 +1,25 @@
-use crate::utils::generate_message;
+use crate::utils::{
+    generate_message, generate_message_streaming, CachedMessage, MessageCache, OnProgress,
+};
 use crate::{
-    commands::config::AutocommitConfig,
-    i18n::{self, language::Language},
+    commands::{
+        commit::{
+            lint::CONVENTIONAL_TYPES,
+            message::{limit_body_bullets, sanitize_message},
+            privacy::is_file_header,
+        },
+        config::{AutocommitConfig, CommitStyle},
+    },
+    git::StagedChange,
+    i18n,
     utils::{Message, MessageRole},
 };
 use anyhow::anyhow;
 use futures::future::try_join_all;
 use lazy_static::lazy_static;
 use log::{debug, info};
+use std::sync::{Mutex, PoisonError};
+#[derive(Clone)]
 pub struct ChatContext {
     messages: Vec<Message>,
 }
@@ -17,17 +29,145 @@ impl ChatContext {
         self.messages.push(Message::new(role, content));
     }
 
+    pub fn add_renames(&mut self, renames: &[StagedChange]) {
+        let rename_lines = renames
+            .iter()
+            .map(|rename| format!("- {} -> {}", rename.old_path, rename.new_path))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let content = format!(
+            "This commit only renames or moves the following files without changing their contents:\n{}\n\n\
+            Write a commit message that describes the renames, for example 'refactor: rename X to Y'.",
+            rename_lines
+        );
+        self.add_message(MessageRole::User, content);
+    }
+
+    pub fn add_binary_changes(&mut self, changes: &[StagedChange]) {
+        let change_lines = changes
+            .iter()
+            .map(|change| format!("- {}", change.describe()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let content = format!(
+            "This commit only changes binary files, so no diff is available:\n{}\n\n\
+            Write a commit message based on the file names, sizes and changes, for example 'chore(assets): add logo.png (24KB)'.",
+            change_lines
+        );
+        self.add_message(MessageRole::User, content);
+    }
+
+    pub fn add_mode_changes(&mut self, changes: &[StagedChange]) {
+        let change_lines = changes
+            .iter()
+            .map(|change| {
+                format!(
+                    "- {}: mode changed to {}",
+                    change.new_path,
+                    change.new_permissions()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let content = format!(
+            "This commit only changes file permissions without changing their contents:\n{}\n\n\
+            Write a commit message that describes the permission changes, for example 'chore: change file mode of script.sh to 755'.",
+            change_lines
+        );
+        self.add_message(MessageRole::User, content);
+    }
+
+    /// Adds the staged diff, keeping at most `max_lines` lines when set.
+    pub fn add_diff(&mut self, diff: &str, max_lines: Option<usize>) {
+        let diff = match max_lines {
+            Some(max_lines) => truncate_diff(diff, max_lines),
+            None => diff.to_owned(),
+        };
+        self.add_message(MessageRole::User, diff);
+    }
+
+    /// Adds the summaries of the parts of a diff too large to send at once,
+    /// in place of the diff itself.
+    pub fn add_partial_summaries(&mut self, summaries: &[String]) {
+        let parts = summaries
+            .iter()
+            .enumerate()
+            .map(|(i, summary)| format!("Part {}:\n{}", i + 1, summary.trim()))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let content = format!(
+            "The diff of this commit is too large to send at once, so it was split into parts and each part was summarized:\n\n{}\n\n\
+            Write a single commit message that covers all the parts.",
+            parts
+        );
+        self.add_message(MessageRole::User, content);
+    }
+
+    /// Adds `(name, contents)` files the changes depend on, marked as
+    /// unchanged so the model uses them without describing them.
+    pub fn add_context_files(&mut self, files: &[(String, String)]) {
+        let sections = files
+            .iter()
+            .map(|(name, contents)| {
+                format!(
+                    "--- {} ---\n{}",
+                    name,
+                    truncate_lines(contents, CONTEXT_FILE_MAX_LINES, "file")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let content = format!(
+            "The following files are unchanged context for the diff above. \
+            Use them to understand the changes, but do not describe them in the commit message.\n\n{}",
+            sections
+        );
+        self.add_message(MessageRole::User, content);
+    }
+
+    /// Asks the model to polish a message the user wrote instead of writing
+    /// one from scratch. The diff added before serves as context.
+    pub fn add_draft(&mut self, draft: &str) {
+        let content = format!(
+            "I wrote the following draft commit message:\n\n{}\n\n\
+            Improve it rather than writing a new one: keep its intent, fix grammar and wording, \
+            translate it into the language asked for above and follow the commit message style described above. \
+            Use the diff only as context to make it accurate.",
+            draft.trim()
+        );
+        self.add_message(MessageRole::User, content);
+    }
+
+    /// Adds a previous answer and asks the model to correct it.
+    pub fn add_correction(&mut self, answer: &str, instruction: String) {
+        self.add_message(MessageRole::Assistant, answer.to_owned());
+        self.add_message(MessageRole::User, instruction);
+    }
+
     pub fn get_messages(&self) -> &Vec<Message> {
         &self.messages
     }
 
-    pub fn get_initial_context(config: &AutocommitConfig) -> ChatContext {
-        let translation = i18n::get_translation(&Language::English).unwrap();
+    /// Builds the system prompt and few-shot example. `subject_only` turns
+    /// off the description for this run, whatever the config says.
+    pub fn get_initial_context(config: &AutocommitConfig, subject_only: bool) -> ChatContext {
         let config_data = &config.config_data;
-        let emoji_enabled = config_data.emoji_enabled.get_value_ref();
-        let description_enabled = config_data.description_enabled.get_value_ref();
+        let translation = i18n::get_translation(&config_data.language.get_value_ref().0).unwrap();
+        let conventional = *config_data.commit_style.get_value_ref() == CommitStyle::Conventional;
+        // A conventional type replaces the GitMoji emoji
+        let emoji_enabled = *config_data.emoji_enabled.get_value_ref() && !conventional;
+        let description_enabled = *config_data.description_enabled.get_value_ref() && !subject_only;
         let name = &config_data.name.get_value_ref();
         let email = &config_data.email.get_value_ref();
+        let max_body_bullets = config_data
+            .max_body_bullets
+            .get_value_ref()
+            .get_inner_value();
+        let allowed_verbs = config_data.allowed_verbs.get_value_ref().get_inner_value();
+        let max_subject_length = config_data
+            .max_subject_length
+            .get_value_ref()
+            .get_inner_value();
 
         let mut system_message = vec![
             "You are a software developer and need to create a commit message for a git repository.",
@@ -36,12 +176,32 @@ impl ChatContext {
             "Avoid using technical jargon or acronyms that may be unfamiliar to other developers.",
         ];
 
-        if *emoji_enabled {
+        let verbs_message;
+        if !allowed_verbs.is_empty() {
+            verbs_message = format!(
+                "Start the commit subject with one of these verbs only: {}.",
+                allowed_verbs.join(", ")
+            );
+            system_message.push(&verbs_message);
+        }
+
+        let conventional_message;
+        if conventional {
+            conventional_message = format!(
+                "Follow Conventional Commits: start the subject with 'type(scope): ', where type is one of {} and the optional scope names the part of the code that changed, e.g. 'fix(parser): handle empty input'.",
+                CONVENTIONAL_TYPES.join(", ")
+            );
+            system_message.push(&conventional_message);
+        }
+
+        if emoji_enabled {
             system_message.push("Use GitMoji convention to preface the commit.");
             system_message.push("Look up the GitMoji convention to choose an appropriate emoji for the type of changes being made (e.g. 🐛 for bug fixes, 🎉 for new features, etc.)");
         }
 
-        if *description_enabled {
+        let bullets_message;
+        let subject_length_message;
+        if description_enabled {
             system_message.push("You should also provide a detailed explanation in the commit description, including any relevant context or reasoning behind the change. Specifically, you should:");
             system_message.push(
                 "Include a brief, descriptive summary of the changes made in the commit message",
@@ -54,14 +214,32 @@ impl ChatContext {
             system_message.push("If there were any particular challenges or obstacles that needed to be overcome to make these changes, mention them in the commit description.");
             system_message.push("Use proper formatting, such as bullet points or numbered lists, to make your commit message easier to read and understand.");
             system_message.push("Keep your commit message concise and to the point. If the changes are significant, consider breaking them up into smaller, more manageable commits.");
-            system_message.push("The commit message should be under 72 characters and focused on a single change or set of related changes.");
+            match max_subject_length {
+                Some(max_subject_length) => {
+                    subject_length_message = format!("The commit message should be under {} characters and focused on a single change or set of related changes.", max_subject_length);
+                    system_message.push(&subject_length_message);
+                }
+                None => system_message.push("The commit message should be focused on a single change or set of related changes."),
+            }
+            if let Some(max_body_bullets) = max_body_bullets {
+                bullets_message = format!(
+                    "Limit the commit description to at most {} bullet points.",
+                    max_body_bullets
+                );
+                system_message.push(&bullets_message);
+            }
         } else {
             system_message.push("Don't add any descriptions to the commit, only commit message.")
         }
         system_message.push("Use the right keywords to help identify the type of change you made. For example, 'fix' for bug fixes, 'add' for new features, 'refactor' for code refactoring, etc.");
         system_message.push("Be consistent with your commit messages across your project. Use the same format and style to make it easier for others to read and understand your messages.");
 
-        let lang = format!("Use {} to answer.", translation.language);
+        let response_language = config_data
+            .response_language
+            .get_value_ref()
+            .get_inner_value()
+            .unwrap_or_else(|| translation.language.clone());
+        let lang = format!("Use {} to answer.", response_language);
         system_message.push(&lang);
         let signed_of_line = format!(
             "Include a 'Signed-off-by: {} <{}>' line indicating the author of the commit.",
@@ -71,11 +249,14 @@ impl ChatContext {
         system_message.push("Exclude anything unnecessary such as the original translation—your entire response will be passed directly into git commit.");
         system_message.push("Carefully heed the user's instructions.");
         let mut assistant_message = String::new();
-        if *emoji_enabled {
+        if conventional {
+            assistant_message.push_str(&format!("{}\n", translation.commit_fix));
+            assistant_message.push_str(&format!("{}\n", translation.commit_feat));
+        } else if emoji_enabled {
             assistant_message.push_str(&format!("🐛 {}\n", translation.commit_fix));
             assistant_message.push_str(&format!("✨ {}\n", translation.commit_feat));
         }
-        if *description_enabled {
+        if description_enabled {
             assistant_message.push_str(&translation.commit_description);
         }
 
@@ -83,49 +264,81 @@ impl ChatContext {
         assistant_message.push_str(&author_line);
         let mut context = ChatContext { messages: vec![] };
         context.add_message(MessageRole::System, system_message.join("\n\n"));
-        context.add_message(MessageRole::User, INITIAL_DIFF.to_owned());
-        context.add_message(MessageRole::Assistant, assistant_message);
+        if *config_data.few_shot.get_value_ref() {
+            debug!(
+                "Adding few-shot example (~{} tokens)",
+                (INITIAL_DIFF.len() + assistant_message.len()) / 4
+            );
+            context.add_message(MessageRole::User, INITIAL_DIFF.to_owned());
+            context.add_message(MessageRole::Assistant, assistant_message);
+        }
 
         context
     }
 
-    pub async fn generate_messages(
+    /// Generates `num_messages` candidates. A single candidate is streamed to
+    /// `on_progress` when it's given.
+    pub async fn generate_candidates(
         &mut self,
         config: &AutocommitConfig,
         num_messages: usize,
-    ) -> anyhow::Result<Vec<String>> {
-        let open_ai_api_key = config
+        on_progress: Option<OnProgress<'_>>,
+    ) -> anyhow::Result<Vec<Candidate>> {
+        let oai_config = config.get_oai_config()?;
+        let open_ai_model = &config
             .config_data
-            .open_ai_api_key
+            .open_ai_model
             .get_value_ref()
             .get_inner_value();
-
-        if open_ai_api_key.is_none() {
-            anyhow::bail!(
-                    "Please set your OpenAI API key in the autocommit config file or as an environment variable. \
-                    You can set it in the config file by running `autocommit config set open_ai_api_key=<your_api_key>`."
-                );
-        }
-
-        let open_ai_api_key = open_ai_api_key.unwrap();
-        let api_host = config.config_data.api_host.get_value_ref();
-        let open_ai_model = &config
+        let fallback_models = config
             .config_data
-            .open_ai_model
+            .fallback_models
             .get_value_ref()
             .get_inner_value();
+        let temperatures = candidate_temperatures(
+            num_messages,
+            *config.config_data.vary_temperature.get_value_ref(),
+        );
 
         debug!("Generating commit messages...");
+        if let (1, Some(on_progress)) = (num_messages, on_progress) {
+            let message = generate_message_streaming(
+                self.get_messages(),
+                oai_config,
+                open_ai_model,
+                &fallback_models,
+                on_progress,
+            )
+            .await
+            .map_err(|error| anyhow!("Failed to generate commit message 0: {}.", error))?;
+            let candidate = Candidate {
+                message,
+                temperature: temperatures[0],
+            };
+            return Ok(Self::finish_candidates(config, vec![candidate]));
+        }
+
         let mut tasks = Vec::new();
-        for i in 0..num_messages {
+        for (i, temperature) in temperatures.into_iter().enumerate() {
             let messages = self.get_messages().clone();
-            let open_ai_api_key = open_ai_api_key.clone();
-            let api_host = api_host.clone();
+            let oai_config = match temperature {
+                // Let the temperature alone control the sampling
+                Some(temperature) => oai_config
+                    .clone()
+                    .temperature(Some(temperature))
+                    .top_p(None),
+                None => oai_config.clone(),
+            };
             let open_ai_model = open_ai_model.clone();
+            let fallback_models = fallback_models.clone();
             tasks.push(tokio::spawn(async move {
-                match generate_message(&messages, &open_ai_api_key, &api_host, &open_ai_model).await
+                match generate_message(&messages, oai_config, &open_ai_model, &fallback_models)
+                    .await
                 {
-                    Ok(message) => Ok(message),
+                    Ok(message) => Ok(Candidate {
+                        message,
+                        temperature,
+                    }),
                     Err(error) => Err(anyhow!(
                         "Failed to generate commit message {}: {}.",
                         i,
@@ -135,15 +348,205 @@ impl ChatContext {
             }));
         }
 
-        let results: Vec<Result<String, anyhow::Error>> = try_join_all(tasks).await?;
-        let messages: Vec<String> = results.into_iter().filter_map(|r| r.ok()).collect();
-        if messages.is_empty() {
+        let results: Vec<Result<Candidate, anyhow::Error>> = try_join_all(tasks).await?;
+        let candidates: Vec<Candidate> = results.into_iter().filter_map(|r| r.ok()).collect();
+        if candidates.is_empty() {
             return Err(anyhow!("Failed to generate any commit messages."));
         }
+        Ok(Self::finish_candidates(config, candidates))
+    }
+
+    /// Like `generate_candidates`, but reuses the candidates generated before
+    /// for the same prompt and config, and adds new ones to `cache`. The
+    /// caller loads and saves the cache, so concurrent calls share one copy.
+    pub async fn generate_cached_candidates(
+        &mut self,
+        config: &AutocommitConfig,
+        num_messages: usize,
+        on_progress: Option<OnProgress<'_>>,
+        cache: &Mutex<MessageCache>,
+    ) -> anyhow::Result<Vec<Candidate>> {
+        let key = MessageCache::key(&[
+            &serde_json::to_string(self.get_messages())?,
+            &serde_json::to_string(&config.config_data)?,
+            &num_messages.to_string(),
+        ]);
+        let cached = cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key, MessageCache::now())
+            .map(<[CachedMessage]>::to_vec);
+        if let Some(messages) = cached {
+            info!("Reusing {} cached commit messages", messages.len());
+            return Ok(messages
+                .into_iter()
+                .map(|cached| Candidate {
+                    message: cached.message,
+                    temperature: cached.temperature,
+                })
+                .collect());
+        }
+
+        let candidates = self
+            .generate_candidates(config, num_messages, on_progress)
+            .await?;
+        let messages = candidates
+            .iter()
+            .map(|candidate| CachedMessage {
+                message: candidate.message.clone(),
+                temperature: candidate.temperature,
+            })
+            .collect();
+        cache.lock().unwrap_or_else(PoisonError::into_inner).insert(
+            key,
+            messages,
+            MessageCache::now(),
+        );
+        Ok(candidates)
+    }
+
+    /// Cleans up the generated messages.
+    fn finish_candidates(config: &AutocommitConfig, candidates: Vec<Candidate>) -> Vec<Candidate> {
+        let mut candidates: Vec<Candidate> = candidates
+            .into_iter()
+            .map(|candidate| Candidate {
+                message: sanitize_message(&candidate.message),
+                ..candidate
+            })
+            .collect();
+
+        let config_data = &config.config_data;
+        if *config_data.description_enabled.get_value_ref() {
+            if let Some(max_body_bullets) = config_data
+                .max_body_bullets
+                .get_value_ref()
+                .get_inner_value()
+            {
+                for candidate in candidates.iter_mut() {
+                    candidate.message = limit_body_bullets(&candidate.message, max_body_bullets);
+                }
+            }
+        }
 
         info!("Commit messages generated");
-        Ok(messages)
+        candidates
+    }
+}
+
+/// A generated commit message along with the temperature it was sampled
+/// with, if it differs from the default.
+pub struct Candidate {
+    pub message: String,
+    pub temperature: Option<f32>,
+}
+
+const MIN_CANDIDATE_TEMPERATURE: f32 = 0.2;
+const MAX_CANDIDATE_TEMPERATURE: f32 = 1.0;
+
+/// Spreads the temperatures of `n` candidates evenly from conservative to
+/// creative. Returns the default temperature for every candidate when
+/// `vary` is off or there is only one candidate.
+pub fn candidate_temperatures(n: usize, vary: bool) -> Vec<Option<f32>> {
+    if !vary || n < 2 {
+        return vec![None; n];
     }
+
+    let step = (MAX_CANDIDATE_TEMPERATURE - MIN_CANDIDATE_TEMPERATURE) / (n - 1) as f32;
+    (0..n)
+        .map(|i| Some(MIN_CANDIDATE_TEMPERATURE + step * i as f32))
+        .collect()
+}
+
+/// The number of lines kept from each `--context-file`.
+const CONTEXT_FILE_MAX_LINES: usize = 200;
+
+pub fn truncate_diff(diff: &str, max_lines: usize) -> String {
+    truncate_lines(diff, max_lines, "diff")
+}
+
+/// A rough token count, assuming about four characters per token.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Joins the diff lines of each file, as returned by
+/// `GitRepository::get_staged_file_diffs`, into one diff per file.
+pub fn group_diff_by_file(diff: &[String]) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for line in diff {
+        match files.last_mut() {
+            Some(file) if !is_file_header(line) => file.push_str(line),
+            _ => files.push(line.clone()),
+        }
+    }
+    files
+}
+
+/// Joins the diff lines, cutting each file's diff down in proportion to its
+/// size when together they're estimated at more than `max_tokens`. Files
+/// with no room left for a single line are omitted, and a note at the end
+/// says so.
+pub fn fit_diffs_to_tokens(diff: &[String], max_tokens: usize) -> String {
+    let diffs = group_diff_by_file(diff);
+    let total_chars = diffs.iter().map(|diff| diff.chars().count()).sum::<usize>();
+    let max_chars = max_tokens.saturating_mul(4);
+    if total_chars <= max_chars {
+        return diffs.concat();
+    }
+
+    debug!(
+        "Truncating the diff of {} files from about {} to {} tokens",
+        diffs.len(),
+        total_chars.div_ceil(4),
+        max_tokens
+    );
+    let mut fitted = String::new();
+    let mut omitted = 0;
+    for diff in &diffs {
+        let budget = diff.chars().count() * max_chars / total_chars;
+        let mut used = 0;
+        let mut kept = String::new();
+        for line in diff.split_inclusive('\n') {
+            used += line.chars().count();
+            if used > budget {
+                break;
+            }
+            kept.push_str(line);
+        }
+        if kept.is_empty() {
+            omitted += 1;
+            continue;
+        }
+        fitted.push_str(&kept);
+        if kept.len() < diff.len() {
+            fitted.push_str("... (file diff truncated) ...\n");
+        }
+    }
+    match omitted {
+        0 => fitted.push_str("[diff truncated]\n"),
+        omitted => fitted.push_str(&format!("[diff truncated: {} files omitted]\n", omitted)),
+    }
+    fitted
+}
+
+/// Keeps the first `max_lines` lines of `text`, ending with a marker naming
+/// what was truncated when lines were dropped.
+fn truncate_lines(text: &str, max_lines: usize, what: &str) -> String {
+    let line_count = text.lines().count();
+    if line_count <= max_lines {
+        return text.to_owned();
+    }
+
+    debug!(
+        "Truncating {} from {} to {} lines",
+        what, line_count, max_lines
+    );
+    let mut truncated = text.lines().take(max_lines).collect::<Vec<_>>().join("\n");
+    truncated.push_str(&format!(
+        "\n... ({} truncated at {} lines) ...\n",
+        what, max_lines
+    ));
+    truncated
 }
 
 lazy_static! {
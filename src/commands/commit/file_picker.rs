@@ -0,0 +1,155 @@
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+
+use crate::git::WorktreeChange;
+
+const HELP: &str =
+    "↑/↓ move  space toggle  a toggle all  PgUp/PgDn scroll diff  enter confirm  esc cancel";
+
+/// The state of the file picker, kept apart from drawing so it can be tested.
+pub struct FilePicker<'a> {
+    changes: &'a [WorktreeChange],
+    selected: Vec<bool>,
+    cursor: usize,
+    scroll: u16,
+}
+
+impl<'a> FilePicker<'a> {
+    pub fn new(changes: &'a [WorktreeChange]) -> Self {
+        Self {
+            changes,
+            selected: vec![false; changes.len()],
+            cursor: 0,
+            scroll: 0,
+        }
+    }
+
+    pub fn move_cursor(&mut self, offset: isize) {
+        if self.changes.is_empty() {
+            return;
+        }
+        let last = self.changes.len() - 1;
+        self.cursor = self.cursor.saturating_add_signed(offset).min(last);
+        self.scroll = 0;
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some(selected) = self.selected.get_mut(self.cursor) {
+            *selected = !*selected;
+        }
+    }
+
+    /// Selects every file, or none if all of them are already selected.
+    pub fn toggle_all(&mut self) {
+        let select = !self.selected.iter().all(|selected| *selected);
+        self.selected.fill(select);
+    }
+
+    fn scroll_preview(&mut self, offset: i16) {
+        self.scroll = self.scroll.saturating_add_signed(offset);
+    }
+
+    pub fn selected_files(&self) -> Vec<String> {
+        self.changes
+            .iter()
+            .zip(&self.selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(change, _)| change.path.clone())
+            .collect()
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [files, preview] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main);
+
+        let items = self
+            .changes
+            .iter()
+            .zip(&self.selected)
+            .map(|(change, selected)| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(if *selected { "[x] " } else { "[ ] " }),
+                    Span::styled(format!("+{} ", change.additions), Color::Green),
+                    Span::styled(format!("-{} ", change.deletions), Color::Red),
+                    Span::raw(change.path.as_str()),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(
+                " Files ({}/{} selected) ",
+                self.selected.iter().filter(|selected| **selected).count(),
+                self.changes.len()
+            )))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut list_state = ListState::default().with_selected(Some(self.cursor));
+        frame.render_stateful_widget(list, files, &mut list_state);
+
+        let patch = self
+            .changes
+            .get(self.cursor)
+            .map(|change| change.patch.as_str())
+            .unwrap_or_default();
+        let lines = patch
+            .lines()
+            .map(|line| {
+                let color = match line.chars().next() {
+                    _ if line.starts_with("+++") || line.starts_with("---") => Color::Yellow,
+                    Some('+') => Color::Green,
+                    Some('-') => Color::Red,
+                    Some('@') => Color::Cyan,
+                    _ => Color::Reset,
+                };
+                Line::styled(line, color)
+            })
+            .collect::<Vec<_>>();
+        let preview_widget = Paragraph::new(Text::from(lines))
+            .block(Block::bordered().title(" Diff "))
+            .scroll((self.scroll, 0));
+        frame.render_widget(preview_widget, preview);
+
+        frame.render_widget(Line::from(HELP).dim(), help);
+    }
+
+    /// Runs the picker until the user confirms or cancels. Returns `None`
+    /// when cancelled.
+    fn run(mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<Option<Vec<String>>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+                KeyCode::Char(' ') => self.toggle(),
+                KeyCode::Char('a') => self.toggle_all(),
+                KeyCode::PageUp => self.scroll_preview(-10),
+                KeyCode::PageDown => self.scroll_preview(10),
+                KeyCode::Enter => return Ok(Some(self.selected_files())),
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Lets the user pick files in a full-screen view with a diff preview.
+pub fn pick_files(changes: &[WorktreeChange]) -> anyhow::Result<Option<Vec<String>>> {
+    let mut terminal = ratatui::init();
+    let result = FilePicker::new(changes).run(&mut terminal);
+    ratatui::restore();
+    result
+}
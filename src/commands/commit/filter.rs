@@ -0,0 +1,52 @@
+use std::process::Stdio;
+
+use anyhow::{anyhow, Context};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+/// Pipes `message` through the shell `command` and returns what it prints.
+/// Fails if the command exits with an error or prints nothing.
+pub async fn filter_message(command: &str, message: &str) -> anyhow::Result<String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let mut child = shell
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run message filter '{}'", command))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Commands that don't read the message close stdin early
+        if let Err(e) = stdin.write_all(message.as_bytes()).await {
+            if e.kind() != std::io::ErrorKind::BrokenPipe {
+                return Err(e).context("Failed to write to message filter");
+            }
+        }
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Message filter '{}' exited with {}: {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let filtered = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+    if filtered.is_empty() {
+        return Err(anyhow!(
+            "Message filter '{}' returned an empty message",
+            command
+        ));
+    }
+    Ok(filtered)
+}
@@ -0,0 +1,203 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::commands::config::{AutocommitConfig, CommitStyle};
+
+use super::message::{exceeds_subject_limit, subject_length};
+
+/// The types a subject may start with when `commit_style` is `conventional`.
+pub const CONVENTIONAL_TYPES: [&str; 10] = [
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "chore", "build", "ci",
+];
+
+lazy_static! {
+    static ref CONVENTIONAL_PREFIX: Regex = Regex::new(r"^\w+(\([^)]*\))?!?:\s*").unwrap();
+    static ref CONVENTIONAL_SUBJECT: Regex = Regex::new(&format!(
+        r"^({})(\([\w./-]+\))?!?: \S",
+        CONVENTIONAL_TYPES.join("|")
+    ))
+    .unwrap();
+    /// A conventional prefix with any type, case or spacing.
+    static ref LOOSE_CONVENTIONAL_SUBJECT: Regex =
+        Regex::new(r"^(\w+)\s*(\([^)]*\))?\s*(!)?\s*:\s*(\S.*)$").unwrap();
+    static ref GENERATED_SUBJECT: Regex =
+        Regex::new(r#"^(Merge |Revert "|fixup! |squash! |amend! )"#).unwrap();
+}
+
+/// Words ending in "ed" or "ing" that are still imperative.
+const IMPERATIVE_EXCEPTIONS: [&str; 12] = [
+    "bed", "embed", "feed", "need", "proceed", "seed", "shed", "speed", "bring", "ping", "ring",
+    "string",
+];
+
+const THIRD_PERSON_VERBS: [&str; 18] = [
+    "adds",
+    "changes",
+    "cleans",
+    "creates",
+    "deletes",
+    "fixes",
+    "handles",
+    "implements",
+    "improves",
+    "makes",
+    "moves",
+    "refactors",
+    "removes",
+    "renames",
+    "replaces",
+    "supports",
+    "updates",
+    "uses",
+];
+
+/// Returns the first word of the subject without a conventional commit
+/// prefix or leading emoji.
+fn subject_verb(message: &str) -> Option<String> {
+    let subject = message.lines().next()?.trim();
+    let subject = CONVENTIONAL_PREFIX.replace(subject, "");
+    subject
+        .split_whitespace()
+        .find(|word| word.chars().next().is_some_and(char::is_alphabetic))
+        .map(|word| {
+            word.trim_end_matches(|c: char| !c.is_alphabetic())
+                .to_owned()
+        })
+}
+
+/// Detects subjects that don't use the imperative mood, such as "Added",
+/// "Fixes" or "Updating", and returns the offending word.
+pub fn non_imperative_verb(message: &str) -> Option<String> {
+    let verb = subject_verb(message)?;
+    let word = verb.to_lowercase();
+    let word = word.as_str();
+    if IMPERATIVE_EXCEPTIONS.contains(&word) {
+        return None;
+    }
+
+    let is_past_tense = word.len() > 3 && word.ends_with("ed") && !word.ends_with("eed");
+    let is_gerund = word.len() > 4 && word.ends_with("ing");
+    if is_past_tense || is_gerund || THIRD_PERSON_VERBS.contains(&word) {
+        Some(verb)
+    } else {
+        None
+    }
+}
+
+/// Returns the subject's first word when `allowed` is non-empty and doesn't
+/// contain it, ignoring case.
+pub fn disallowed_verb(message: &str, allowed: &[String]) -> Option<String> {
+    if allowed.is_empty() {
+        return None;
+    }
+    let verb = subject_verb(message)?;
+    if allowed
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(&verb))
+    {
+        None
+    } else {
+        Some(verb)
+    }
+}
+
+/// Whether the subject has the `type(scope): subject` form with one of the
+/// `CONVENTIONAL_TYPES`.
+pub fn is_conventional(message: &str) -> bool {
+    message
+        .lines()
+        .next()
+        .is_some_and(|subject| CONVENTIONAL_SUBJECT.is_match(subject))
+}
+
+/// Fixes small deviations from the conventional form, such as a type in
+/// upper case, spaces around the colon or a leading emoji. Returns `None`
+/// when the subject doesn't start with one of the `CONVENTIONAL_TYPES`.
+pub fn to_conventional(message: &str) -> Option<String> {
+    if is_conventional(message) {
+        return Some(message.to_owned());
+    }
+    let (subject, rest) = message.split_once('\n').unwrap_or((message, ""));
+    let subject = subject.trim_start_matches(|c: char| !c.is_alphanumeric());
+    let captures = LOOSE_CONVENTIONAL_SUBJECT.captures(subject.trim_end())?;
+    let commit_type = captures[1].to_lowercase();
+    if !CONVENTIONAL_TYPES.contains(&commit_type.as_str()) {
+        return None;
+    }
+    let scope = captures
+        .get(2)
+        .map(|scope| scope.as_str().replace(' ', ""))
+        .unwrap_or_default();
+    let breaking = captures.get(3).map_or("", |_| "!");
+    let subject = format!("{}{}{}: {}", commit_type, scope, breaking, &captures[4]);
+    let message = if rest.is_empty() {
+        subject
+    } else {
+        format!("{}\n{}", subject, rest)
+    };
+    is_conventional(&message).then_some(message)
+}
+
+/// Checks a commit message against the configured rules and returns a
+/// description of each violation. Messages generated by git, such as merges
+/// and fixups, are always accepted.
+pub fn lint_message(message: &str, config: &AutocommitConfig) -> Vec<String> {
+    let mut violations = Vec::new();
+    let Some(subject) = message
+        .lines()
+        .next()
+        .filter(|line| !line.trim().is_empty())
+    else {
+        violations.push(String::from("The message is empty"));
+        return violations;
+    };
+    if GENERATED_SUBJECT.is_match(subject) {
+        return violations;
+    }
+
+    let config_data = &config.config_data;
+    let max_subject_length = config_data
+        .max_subject_length
+        .get_value_ref()
+        .get_inner_value();
+    if exceeds_subject_limit(message, max_subject_length) {
+        violations.push(format!(
+            "The subject is {} characters long, more than {}",
+            subject_length(message),
+            max_subject_length.unwrap_or_default()
+        ));
+    }
+    if message
+        .lines()
+        .nth(1)
+        .is_some_and(|line| !line.trim().is_empty())
+    {
+        violations.push(String::from("The subject must be followed by a blank line"));
+    }
+
+    if *config_data.enforce_imperative.get_value_ref() {
+        if let Some(verb) = non_imperative_verb(message) {
+            violations.push(format!(
+                "The subject starts with '{}', which is not in the imperative mood",
+                verb
+            ));
+        }
+    }
+    if *config_data.commit_style.get_value_ref() == CommitStyle::Conventional
+        && !is_conventional(message)
+    {
+        violations.push(format!(
+            "The subject must look like 'type(scope): subject' with one of the types: {}",
+            CONVENTIONAL_TYPES.join(", ")
+        ));
+    }
+    let allowed_verbs = config_data.allowed_verbs.get_value_ref().get_inner_value();
+    if let Some(verb) = disallowed_verb(message, &allowed_verbs) {
+        violations.push(format!(
+            "The subject starts with '{}', which is not one of: {}",
+            verb,
+            allowed_verbs.join(", ")
+        ));
+    }
+    violations
+}
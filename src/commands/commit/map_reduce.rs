@@ -0,0 +1,81 @@
+use anyhow::anyhow;
+use futures::future::try_join_all;
+use log::debug;
+
+use crate::{
+    commands::config::AutocommitConfig,
+    utils::{generate_message, Message, MessageRole},
+};
+
+use super::chat_context::{group_diff_by_file, truncate_diff};
+
+/// The chunk size used when `max_diff_lines` isn't set.
+pub const DEFAULT_CHUNK_LINES: usize = 500;
+
+const SUMMARY_PROMPT: &str = "You are a software developer reviewing one part of a large commit. \
+    Summarize what the diff the user provides changes, as a short list of bullet points. \
+    Mention the files and the intent of the changes, not line-by-line details. \
+    Respond with the bullet points only.";
+
+/// Groups the diff lines into chunks of at most `max_lines` lines, keeping
+/// files whole. A file longer than `max_lines` gets a chunk of its own and
+/// is truncated.
+pub fn chunk_diffs(diff: &[String], max_lines: usize) -> Vec<String> {
+    let diffs = group_diff_by_file(diff);
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    let mut chunk_lines = 0;
+    for diff in &diffs {
+        let lines = diff.lines().count();
+        if chunk_lines > 0 && chunk_lines + lines > max_lines {
+            chunks.push(std::mem::take(&mut chunk));
+            chunk_lines = 0;
+        }
+        if lines > max_lines {
+            chunks.push(truncate_diff(diff, max_lines));
+            continue;
+        }
+        chunk.push_str(diff);
+        chunk_lines += lines;
+    }
+    if chunk_lines > 0 {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// Asks the model for a summary of each chunk, all at once.
+pub async fn summarize_chunks(
+    config: &AutocommitConfig,
+    chunks: Vec<String>,
+) -> anyhow::Result<Vec<String>> {
+    let oai_config = config.get_oai_config()?;
+    let model = config
+        .config_data
+        .open_ai_model
+        .get_value_ref()
+        .get_inner_value();
+    let fallback_models = config
+        .config_data
+        .fallback_models
+        .get_value_ref()
+        .get_inner_value();
+
+    debug!("Summarizing {} diff chunks", chunks.len());
+    let tasks = chunks.into_iter().enumerate().map(|(i, chunk)| {
+        let prompt = vec![
+            Message::new(MessageRole::System, String::from(SUMMARY_PROMPT)),
+            Message::new(MessageRole::User, chunk),
+        ];
+        let oai_config = oai_config.clone();
+        let model = model.clone();
+        let fallback_models = fallback_models.clone();
+        tokio::spawn(async move {
+            generate_message(&prompt, oai_config, &model, &fallback_models)
+                .await
+                .map_err(|error| anyhow!("Failed to summarize diff chunk {}: {}", i + 1, error))
+        })
+    });
+
+    try_join_all(tasks).await?.into_iter().collect()
+}
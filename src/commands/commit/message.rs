@@ -0,0 +1,246 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref MESSAGE_LABEL: Regex =
+        Regex::new(r"(?i)^(\*\*)?((suggested|git) )?commit message(\*\*)?\s*:(\*\*)?\s*").unwrap();
+}
+
+pub const SUBJECT_SOFT_LIMIT: usize = 50;
+
+pub fn subject_length(message: &str) -> usize {
+    message.lines().next().unwrap_or_default().chars().count()
+}
+
+/// Whether the subject is longer than `max_subject_length`, if one is set.
+pub fn exceeds_subject_limit(message: &str, max_subject_length: Option<usize>) -> bool {
+    max_subject_length.is_some_and(|max| subject_length(message) > max)
+}
+
+/// Puts a blank line between the subject and the body, which git and most
+/// tools rely on to tell them apart.
+pub fn separate_subject(message: &str) -> String {
+    match message.split_once('\n') {
+        Some((subject, body))
+            if body
+                .lines()
+                .next()
+                .is_some_and(|line| !line.trim().is_empty()) =>
+        {
+            format!("{}\n\n{}", subject, body)
+        }
+        _ => message.to_owned(),
+    }
+}
+
+fn is_bullet_line(line: &str) -> bool {
+    let line = line.trim_start();
+    if line.starts_with("- ") || line.starts_with("* ") || line.starts_with("+ ") {
+        return true;
+    }
+
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && (line[digits..].starts_with(". ") || line[digits..].starts_with(") "))
+}
+
+/// Keeps at most `max_bullets` bullet points in the body of a commit message.
+/// The subject line and non-bullet lines are left untouched, while lines that
+/// continue a dropped bullet point are dropped along with it.
+pub fn limit_body_bullets(message: &str, max_bullets: usize) -> String {
+    let mut lines = message.lines();
+    let mut result = lines
+        .next()
+        .map(|subject| vec![subject])
+        .unwrap_or_default();
+
+    let mut bullet_count = 0;
+    let mut dropping = false;
+    for line in lines {
+        if is_bullet_line(line) {
+            bullet_count += 1;
+            dropping = bullet_count > max_bullets;
+        } else if line.trim().is_empty() || !line.starts_with(char::is_whitespace) {
+            dropping = false;
+        }
+
+        if !dropping {
+            result.push(line);
+        }
+    }
+
+    result.join("\n")
+}
+
+fn strip_code_fence(message: &str) -> &str {
+    let inner = match message
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+    {
+        Some(inner) => inner,
+        None => return message,
+    };
+
+    // Drop the language tag of the opening fence, e.g. ```text
+    match inner.split_once('\n') {
+        Some((tag, body)) if !tag.trim().contains(' ') => body,
+        _ => inner,
+    }
+}
+
+/// Strips a pair of quotes around the whole message. Quotes that also
+/// appear inside it, as in "`foo` renamed to `bar`", are left alone.
+fn strip_surrounding_quotes(message: &str) -> &str {
+    for quote in ['"', '\'', '`'] {
+        if message.len() >= 2 && message.starts_with(quote) && message.ends_with(quote) {
+            let inner = &message[1..message.len() - 1];
+            if !inner.contains(quote) {
+                return inner;
+            }
+        }
+    }
+    message
+}
+
+/// Removes the wrapping that models tend to add around a commit message
+/// despite instructions: code fences, a "Commit message:" label and
+/// surrounding quotes.
+pub fn sanitize_message(message: &str) -> String {
+    let mut message = message.trim();
+    loop {
+        let label_end = MESSAGE_LABEL.find(message).map_or(0, |m| m.end());
+        let sanitized = message[label_end..].trim();
+        let sanitized = strip_code_fence(sanitized).trim();
+        let sanitized = strip_surrounding_quotes(sanitized).trim();
+
+        if sanitized == message {
+            return sanitized.to_owned();
+        }
+        message = sanitized;
+    }
+}
+
+const TRAILER_PREFIXES: [&str; 2] = ["Signed-off-by:", "Co-authored-by:"];
+
+/// Whether the message has a body besides its subject line and trailers.
+pub fn has_body(message: &str) -> bool {
+    message.trim().lines().skip(1).any(|line| {
+        let line = line.trim();
+        !line.is_empty()
+            && !TRAILER_PREFIXES
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+    })
+}
+
+/// Shortens the body of `message` so the whole message fits in `max_bytes`,
+/// keeping the subject and the trailing `Signed-off-by`/`Co-authored-by`
+/// lines. Whole body lines are dropped from the end and replaced by `[...]`.
+pub fn truncate_body(message: &str, max_bytes: usize) -> String {
+    let mut lines = message.trim().lines().collect::<Vec<_>>();
+    if lines.is_empty() {
+        return String::new();
+    }
+    let subject = lines.remove(0);
+    let trailer_start = lines
+        .iter()
+        .rposition(|line| {
+            let line = line.trim();
+            !line.is_empty()
+                && !TRAILER_PREFIXES
+                    .iter()
+                    .any(|prefix| line.starts_with(prefix))
+        })
+        .map_or(0, |last_body_line| last_body_line + 1);
+    let trailers = lines.split_off(trailer_start);
+    let trailers = trailers
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let compose = |body: &[&str]| {
+        [
+            subject.to_owned(),
+            body.join("\n").trim().to_owned(),
+            trailers.clone(),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+    };
+    let mut body = lines;
+    let mut message = compose(&body);
+    while message.len() > max_bytes && !body.is_empty() {
+        body.pop();
+        let mut truncated = body.clone();
+        truncated.push("[...]");
+        message = compose(&truncated);
+    }
+    message
+}
+
+/// Tidies a message before committing: converts CRLF line endings, trims
+/// trailing whitespace, collapses runs of blank lines into one and ends the
+/// message with a single newline.
+pub fn normalize_message(message: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in message.lines().map(str::trim_end) {
+        let previous_blank = lines.last().is_none_or(|previous| previous.is_empty());
+        if !(line.is_empty() && previous_blank) {
+            lines.push(line);
+        }
+    }
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    let mut normalized = lines.join("\n");
+    normalized.push('\n');
+    normalized
+}
+
+/// How many characters of a streamed message the spinner shows.
+const STREAM_PREVIEW_CHARS: usize = 60;
+
+/// The end of a message that's still being streamed, on a single line.
+pub fn stream_preview(message: &str) -> String {
+    let line = message.split_whitespace().collect::<Vec<_>>().join(" ");
+    let chars = line.chars().count();
+    if chars <= STREAM_PREVIEW_CHARS {
+        return line;
+    }
+    let tail = line
+        .chars()
+        .skip(chars - STREAM_PREVIEW_CHARS)
+        .collect::<String>();
+    format!("…{}", tail)
+}
+
+/// The line after which git ignores everything in a commit message, used
+/// to show the diff below the message like `git commit -v`.
+pub const SCISSORS_LINE: &str = "------------------------ >8 ------------------------";
+
+/// Drops the scissors line and everything below it.
+pub fn strip_scissors(message: &str, comment_char: char) -> &str {
+    let scissors = format!("{} {}", comment_char, SCISSORS_LINE);
+    let mut offset = 0;
+    for line in message.split_inclusive('\n') {
+        if line.trim_end() == scissors {
+            return &message[..offset];
+        }
+        offset += line.len();
+    }
+    message
+}
+
+pub fn strip_comments(message: &str, comment_char: char) -> String {
+    message
+        .lines()
+        .filter(|line| !line.starts_with(comment_char))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
@@ -1,31 +1,465 @@
 use crate::{
-    commands::commit::chat_context::ChatContext,
-    git::GitRepository,
-    utils::{outro, spinner, MessageRole},
+    commands::commit::chat_context::{
+        estimate_tokens, fit_diffs_to_tokens, Candidate, ChatContext,
+    },
+    git::{CommitSigning, DiffBase, DiffStyle, GitHubCli, GitRepository, StagedChange},
+    utils::{
+        assume_yes, edit_file, open_files, outro, send_notification, set_assume_yes,
+        set_ui_on_stderr, spinner, ui_on_stderr, CurrentDirGuard, MessageCache, State,
+    },
 };
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use clipboard::{ClipboardContext, ClipboardProvider};
 use colored::Colorize;
-use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect};
-use log::{debug, info};
-use prettytable::{color, format::Alignment, row, Attr, Cell, Row, Table};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, MultiSelect, Select};
+use log::{debug, info, warn};
+use prettytable::{cell, color, format::Alignment, row, Attr, Cell, Row, Table};
+use std::{
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
 use structopt::StructOpt;
 use textwrap::fill;
 
-use super::config::AutocommitConfig;
+use self::areas::{generate_area_candidates, group_diff_by_area, group_files_by_area};
+use self::file_picker::pick_files;
+use self::filter::filter_message;
+pub use self::lint::lint_message;
+use self::lint::{disallowed_verb, non_imperative_verb, to_conventional, CONVENTIONAL_TYPES};
+use self::map_reduce::{chunk_diffs, summarize_chunks, DEFAULT_CHUNK_LINES};
+use self::message::{
+    exceeds_subject_limit, has_body, normalize_message, separate_subject, stream_preview,
+    subject_length, truncate_body, SCISSORS_LINE, SUBJECT_SOFT_LIMIT,
+};
+pub use self::message::{strip_comments, strip_scissors};
+use self::privacy::{redact_changes, redact_diff};
+use self::staging::{assign_to_groups, suggest_staging_groups};
+use self::ticket::{append_footer, co_author_trailer, extract_ticket};
+use super::config::{
+    apply_directory_configs, confirm_send, AutocommitConfig, CommitStyle, DiffPrivacy,
+};
 
+mod areas;
 mod chat_context;
+mod file_picker;
+mod filter;
+mod lint;
+mod map_reduce;
+mod message;
+mod privacy;
+mod staging;
+#[cfg(test)]
+mod tests;
+mod ticket;
+
+#[derive(Debug, Default, Clone)]
+pub struct CommitOutcome {
+    pub committed: bool,
+    pub hash: Option<String>,
+    pub message: Option<String>,
+    pub pushed: bool,
+}
 
 #[derive(Debug, StructOpt)]
 pub struct CommitCommand {
     #[structopt(short, long)]
     stage_all: bool,
 
-    #[structopt(short, long, default_value = "1")]
-    n: usize,
+    #[structopt(short, long, help = "Number of messages to generate [default: 1]")]
+    n: Option<usize>,
+
+    #[structopt(long, help = "Open a pull request with the GitHub CLI after pushing")]
+    pr: bool,
+
+    #[structopt(
+        long,
+        help = "Allow committing files that contain merge conflict markers"
+    )]
+    allow_conflicts: bool,
+
+    #[structopt(
+        long,
+        help = "Review messages that have a body in your editor before committing"
+    )]
+    review: bool,
+
+    #[structopt(
+        long,
+        help = "Ask the model how to split unstaged changes into commits and stage one group"
+    )]
+    suggest_staging: bool,
+
+    #[structopt(
+        long,
+        help = "Abort the whole run if it takes longer than this many seconds"
+    )]
+    timeout: Option<u64>,
+
+    #[structopt(
+        long,
+        min_values = 1,
+        help = "Stage and commit only these files, skipping the file selection prompt"
+    )]
+    stage: Vec<String>,
+
+    #[structopt(
+        long,
+        possible_values = &DiffStyle::VARIANTS,
+        help = "How much context to include in the diff: minimal (changed lines only), unified (3 lines) or full (whole file). Defaults to diff_context_lines when set, otherwise unified"
+    )]
+    diff_style: Option<DiffStyle>,
+
+    #[structopt(
+        long,
+        help = "Prompt for the remote to push to instead of reusing the last one"
+    )]
+    choose_remote: bool,
+
+    #[structopt(
+        long,
+        help = "Skip the push prompt and print the git push command to run later"
+    )]
+    print_push_command: bool,
+
+    #[structopt(
+        long,
+        help = "Amend the last commit with the staged changes and a message describing the whole commit"
+    )]
+    amend: bool,
+
+    #[structopt(
+        short = "v",
+        long,
+        help = "Show the staged diff below the message when reviewing it in an editor"
+    )]
+    show_diff: bool,
+
+    #[structopt(
+        long,
+        conflicts_with_all = &["amend", "stage"],
+        help = "Commit the changes inside changed submodules first, then in this repository"
+    )]
+    recurse_submodules: bool,
+
+    #[structopt(
+        long,
+        help = "Generate only a one-line subject, even if description is enabled"
+    )]
+    subject_only: bool,
+
+    #[structopt(
+        long,
+        help = "Select the files to stage in a full-screen view with line counts and a diff preview"
+    )]
+    tui: bool,
+
+    #[structopt(
+        long,
+        help = "A rough message of your own for the model to polish and translate, using the diff as context"
+    )]
+    draft: Option<String>,
+
+    #[structopt(
+        long,
+        help = "Suggest the authors of the changed lines, from git blame, as co-authors"
+    )]
+    suggest_coauthors: bool,
+
+    #[structopt(
+        long,
+        help = "Summarize the diff in chunks and combine the summaries, for commits too large to send at once"
+    )]
+    map_reduce: bool,
+
+    #[structopt(
+        long,
+        conflicts_with_all = &["map-reduce", "message"],
+        help = "Generate a section of the message for each top-level directory and combine them, for commits touching unrelated areas"
+    )]
+    per_file: bool,
+
+    #[structopt(
+        long,
+        conflicts_with_all = &["amend", "message", "dry-run", "print-only", "recurse-submodules"],
+        help = "Split the staged changes into one commit per top-level directory, or per suggested group with --suggest-staging"
+    )]
+    split: bool,
+
+    #[structopt(
+        long,
+        help = "Generate new messages even if messages for the same diff are cached"
+    )]
+    no_cache: bool,
+
+    #[structopt(
+        long,
+        parse(from_os_str),
+        help = "A file the changes depend on, sent as unchanged context for the model (repeatable)"
+    )]
+    context_file: Vec<PathBuf>,
+
+    #[structopt(
+        short = "y",
+        long,
+        conflicts_with_all = &["choose-remote", "review", "tui"],
+        help = "Accept the default answer of every prompt and commit the first message, e.g. in CI"
+    )]
+    yes: bool,
+
+    #[structopt(
+        long,
+        conflicts_with = "recurse-submodules",
+        help = "Generate and print the message without committing, pulling or pushing"
+    )]
+    dry_run: bool,
+
+    #[structopt(
+        short,
+        long,
+        conflicts_with = "draft",
+        help = "Commit with this message instead of generating one"
+    )]
+    message: Option<String>,
+
+    #[structopt(
+        long,
+        conflicts_with = "recurse-submodules",
+        help = "Like --dry-run, but print only the message on stdout and everything else on stderr, e.g. for a git hook"
+    )]
+    print_only: bool,
+
+    #[structopt(
+        long = "co-author",
+        number_of_values = 1,
+        help = "Credit a pair as 'Name <email>' with a Co-authored-by trailer (repeatable)"
+    )]
+    co_authors: Vec<String>,
+
+    #[structopt(
+        long,
+        help = "Skip the pre-commit and commit-msg hooks when committing, like git commit --no-verify"
+    )]
+    no_verify: bool,
 }
 
+const MAX_COAUTHOR_SUGGESTIONS: usize = 5;
+
+const REVIEW_INSTRUCTIONS: [&str; 3] = [
+    "Please review the commit message above. The first line is the subject,",
+    "followed by a blank line and the body. Lines starting with '{}' will be",
+    "ignored, and an empty message aborts the commit.",
+];
+
+const SCISSORS_INSTRUCTIONS: &str =
+    "Do not modify or remove the line above. Everything below it will be ignored.";
+
 impl CommitCommand {
+    /// Whether the run needs the API, i.e. isn't given its message with
+    /// `--message`.
+    pub fn uses_api(&self) -> bool {
+        self.message.is_none() || self.suggest_staging
+    }
+
+    fn message_count(&self) -> usize {
+        if self.print_only {
+            1
+        } else {
+            self.n.unwrap_or(1)
+        }
+    }
+
+    /// Whether stdout is reserved for the message, with `--print-only`.
+    pub fn print_only(&self) -> bool {
+        self.print_only
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+
+    async fn review_message_in_editor(
+        message: &str,
+        diff: Option<&str>,
+    ) -> anyhow::Result<Option<String>> {
+        let editor = GitRepository::get_editor().await?;
+        let path = GitRepository::get_edit_message_path()?;
+        debug!("Opening {} in {}", path.display(), editor);
+
+        let comment_char = GitRepository::get_comment_char()?;
+        let instructions = REVIEW_INSTRUCTIONS
+            .iter()
+            .map(|line| {
+                format!(
+                    "{} {}",
+                    comment_char,
+                    line.replace("{}", &comment_char.to_string())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut content = format!("{}\n\n{}\n", message.trim(), instructions);
+        if let Some(diff) = diff {
+            content.push_str(&format!(
+                "{} {}\n{} {}\n{}",
+                comment_char, SCISSORS_LINE, comment_char, SCISSORS_INSTRUCTIONS, diff
+            ));
+        }
+        let edited = edit_file(&editor, &path, &content)?;
+        let reviewed = strip_comments(strip_scissors(&edited, comment_char), comment_char);
+        Ok(if reviewed.is_empty() {
+            None
+        } else {
+            Some(reviewed)
+        })
+    }
+
+    /// Returns the `--message` if given, otherwise generates messages and
+    /// lets the user pick one.
+    async fn get_message(
+        &self,
+        config: &AutocommitConfig,
+        diffs: &[String],
+        changes: &[StagedChange],
+    ) -> anyhow::Result<Option<String>> {
+        if let Some(message) = &self.message {
+            return Ok(Some(message.clone()));
+        }
+        let commit_messages = self
+            .generate_autocommit_messages(config, diffs, changes)
+            .await?;
+        self.choose_message(config, commit_messages, diffs).await
+    }
+
+    /// Lets the user pick one of the generated messages and review it.
+    /// Returns `None` when the resulting message is empty.
+    async fn choose_message(
+        &self,
+        config: &AutocommitConfig,
+        commit_messages: Vec<String>,
+        staged_diffs: &[String],
+    ) -> anyhow::Result<Option<String>> {
+        let commit_messages = Self::add_closing_footer(config, commit_messages)?;
+
+        // Prompt the user to confirm the commit message
+        let mut message = Self::prompt_for_selected_message(&commit_messages).await?;
+        let review = (self.review || *config.config_data.review_body.get_value_ref())
+            && has_body(&message)
+            && !assume_yes();
+        if review || Self::confirm("Do you want to edit the message before committing?", false)? {
+            let diff = self.show_diff.then(|| staged_diffs.join(""));
+            let Some(edited) = Self::edit_message(config, message, diff.as_deref()).await? else {
+                outro("Aborting commit due to empty commit message.");
+                return Ok(None);
+            };
+            message = edited;
+        }
+        // Drop comment lines like git does, e.g. scaffolding from templates
+        let message = strip_comments(&message, GitRepository::get_comment_char()?);
+        if message.is_empty() {
+            outro("Aborting commit due to empty commit message.");
+            return Ok(None);
+        }
+        Ok(Some(message))
+    }
+
+    /// Prints the message a dry run would have committed.
+    fn print_dry_run_message(message: String) -> CommitOutcome {
+        outro("Dry run, nothing was committed. The selected message:");
+        println!("{}", message);
+        CommitOutcome {
+            message: Some(message),
+            ..CommitOutcome::default()
+        }
+    }
+
+    /// Opens `message` in the editor until the user is done with it. Manual
+    /// edits are linted like generated messages, and the user can edit again
+    /// when they break a rule. Returns `None` when the message is emptied.
+    async fn edit_message(
+        config: &AutocommitConfig,
+        mut message: String,
+        diff: Option<&str>,
+    ) -> anyhow::Result<Option<String>> {
+        loop {
+            let Some(reviewed) = Self::review_message_in_editor(&message, diff).await? else {
+                return Ok(None);
+            };
+            let edited = reviewed != message;
+            message = reviewed;
+            if !edited || !Self::warn_about_violations(config, &message)? {
+                return Ok(Some(message));
+            }
+        }
+    }
+
+    /// Lints an edited message and lists the rules it breaks. Returns whether
+    /// the user wants to edit it again.
+    fn warn_about_violations(config: &AutocommitConfig, message: &str) -> anyhow::Result<bool> {
+        let violations = lint_message(message, config);
+        if violations.is_empty() {
+            return Ok(false);
+        }
+        outro(&format!(
+            "{} The edited message doesn't follow the rules:\n{}",
+            "⚠".yellow(),
+            violations
+                .iter()
+                .map(|violation| format!("  - {}", violation))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+        Self::confirm("Do you want to edit the message again?", true)
+    }
+
+    /// Rewrites the current commit with the staged changes and a message
+    /// describing the whole amended commit, not just the newly staged changes.
+    async fn run_amend(&self, config: &AutocommitConfig) -> anyhow::Result<CommitOutcome> {
+        let files = GitRepository::get_amend_files()?;
+        outro(&format!(
+            "{} files in the amended commit:\n{}",
+            files.len().to_string().green(),
+            files
+                .iter()
+                .map(|file| format!("  📄 {}", file))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+
+        // Refuse to commit unresolved merge conflicts
+        if !self.allow_conflicts {
+            Self::assert_no_conflict_markers(&GitRepository::get_staged_files()?)?;
+        }
+
+        let config = &apply_directory_configs(config, &files).await?;
+        let diff_files = self.get_diff_files(config, &files)?;
+        let diffs = GitRepository::get_staged_file_diffs(
+            &diff_files,
+            self.diff_style(config),
+            DiffBase::HeadParent,
+        )?;
+        let changes = GitRepository::get_staged_changes(&diff_files, DiffBase::HeadParent)?;
+        let Some(message) = self.get_message(config, &diffs, &changes).await? else {
+            return Ok(CommitOutcome::default());
+        };
+        if self.dry_run {
+            return Ok(Self::print_dry_run_message(message));
+        }
+
+        let hash = self.commit_changes(config, &message).await?;
+        outro(&format!(
+            "If the commit was already pushed, update the remote with {}",
+            "git push --force-with-lease".bold()
+        ));
+        Ok(CommitOutcome {
+            committed: true,
+            hash: Some(hash),
+            message: Some(message),
+            pushed: false,
+        })
+    }
+
     pub async fn stage_all_changed_files(changed_files: &[String]) -> anyhow::Result<()> {
         if !changed_files.is_empty() {
             GitRepository::git_add_all()?;
@@ -37,22 +471,250 @@ impl CommitCommand {
         Ok(())
     }
 
-    pub async fn run(&mut self, config: &AutocommitConfig) -> anyhow::Result<()> {
+    pub async fn run(&mut self, config: &AutocommitConfig) -> anyhow::Result<CommitOutcome> {
         info!("Starting autocommit process");
+        if self.print_only {
+            set_ui_on_stderr(true);
+            self.dry_run = true;
+        }
+        if self.yes {
+            set_assume_yes(true);
+        } else if !std::io::stdin().is_terminal() {
+            return Err(anyhow!(
+                "Standard input is not a terminal, pass --yes to commit without prompts"
+            ));
+        }
         GitRepository::assert_git_repo().await?;
+        // Fail before touching the index on invalid co-authors
+        self.co_author_trailers(config)?;
+        // Fail before touching the index if messages can't be generated
+        if self.uses_api() {
+            config.get_oai_config()?;
+        }
+        if self
+            .message
+            .as_ref()
+            .is_some_and(|message| message.trim().is_empty())
+        {
+            return Err(anyhow!("The message given with --message is empty"));
+        }
+        if self.message.is_some() && self.n.is_some() {
+            outro(&format!(
+                "{} -n is ignored when --message is given",
+                "⚠".yellow()
+            ));
+        } else if self.print_only && self.n.is_some_and(|n| n > 1) {
+            outro(&format!(
+                "{} -n is ignored with --print-only, which generates one message",
+                "⚠".yellow()
+            ));
+        }
+        if self.amend {
+            return self.run_amend(config).await;
+        }
+        if self.split {
+            return self.run_split(config).await;
+        }
+        if self.recurse_submodules {
+            self.run_submodules(config).await?;
+        }
+        if self.dry_run {
+            // Leave the index as it was, even if files were staged for the preview
+            let index_backup = GitRepository::backup_index()?;
+            let outcome = self.run_in_repo(config).await;
+            index_backup.restore()?;
+            return outcome;
+        }
+        self.run_in_repo(config).await
+    }
+
+    /// Commits the staged changes as several commits, one per group of files,
+    /// after showing the planned commits. The index is put back as it was in
+    /// the end, so the groups that weren't committed stay staged.
+    async fn run_split(&mut self, config: &AutocommitConfig) -> anyhow::Result<CommitOutcome> {
+        let changed_files = GitRepository::get_changed_files()?;
+        if !self.stage.is_empty() {
+            let files = std::mem::take(&mut self.stage);
+            Self::stage_given_files(&files, &changed_files).await?;
+        } else if self.stage_all {
+            Self::stage_all_changed_files(&changed_files).await?;
+        }
+        let staged_files = GitRepository::get_staged_files()?;
+        if staged_files.is_empty() {
+            return Err(anyhow!(
+                "No changes are staged, stage the changes to split first"
+            ));
+        }
+
+        let groups = if self.suggest_staging {
+            confirm_send(config, staged_files.iter().map(String::len).sum())?;
+            let mut suggestion_spinner = spinner();
+            suggestion_spinner.start("Suggesting how to split the changes...");
+            let suggestions = suggest_staging_groups(config, &staged_files).await?;
+            suggestion_spinner.stop(&format!(
+                "{} groups suggested",
+                suggestions.len().to_string().green()
+            ));
+            assign_to_groups(suggestions, &staged_files)?
+        } else {
+            group_files_by_area(&staged_files)
+        };
+        if groups.len() < 2 {
+            outro("The staged changes form a single group, committing them together");
+            return self.run_in_repo(config).await;
+        }
+
+        outro(&format!(
+            "{}\n{}",
+            "Planned commits:".green(),
+            groups
+                .iter()
+                .enumerate()
+                .map(|(i, (description, files))| format!(
+                    "  {}. {}\n{}",
+                    i + 1,
+                    description.bold(),
+                    files
+                        .iter()
+                        .map(|file| format!("     📄 {}", file))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+        if !Self::confirm(format!("Create these {} commits?", groups.len()), true)? {
+            outro(&format!("{}", "Exiting...".red()));
+            return Ok(CommitOutcome::default());
+        }
+
+        let index_backup = GitRepository::backup_index()?;
+        let tree = GitRepository::write_index_tree()?;
+        let mut outcome = CommitOutcome::default();
+        let result = self
+            .commit_split_groups(config, &groups, &tree, &mut outcome)
+            .await;
+        index_backup.restore()?;
+        result?;
+        Ok(outcome)
+    }
+
+    /// Stages and commits each group in turn, stopping when a message is
+    /// rejected.
+    async fn commit_split_groups(
+        &self,
+        config: &AutocommitConfig,
+        groups: &[(String, Vec<String>)],
+        tree: &str,
+        outcome: &mut CommitOutcome,
+    ) -> anyhow::Result<()> {
+        for (i, (description, files)) in groups.iter().enumerate() {
+            outro(&format!(
+                "Commit {}/{}: {}",
+                i + 1,
+                groups.len(),
+                description.bold()
+            ));
+            GitRepository::stage_from_tree(tree, files).await?;
+
+            let config = &apply_directory_configs(config, files).await?;
+            let diff_files = self.exclude_from_diff(config, files)?;
+            let message = if diff_files.is_empty() && self.message.is_none() {
+                // There's no diff to describe, but the group was shown and confirmed
+                outro(&format!(
+                    "{} Every file in this group matches diff_exclude_globs, using its description as the message",
+                    "⚠".yellow()
+                ));
+                description.clone()
+            } else {
+                let diffs = GitRepository::get_staged_file_diffs(
+                    &diff_files,
+                    self.diff_style(config),
+                    DiffBase::Head,
+                )?;
+                let changes = GitRepository::get_staged_changes(&diff_files, DiffBase::Head)?;
+                let Some(message) = self.get_message(config, &diffs, &changes).await? else {
+                    outro("Stopping the split, the remaining changes are left staged");
+                    return Ok(());
+                };
+                message
+            };
+            let hash = self.commit_changes(config, &message).await?;
+            *outcome = CommitOutcome {
+                committed: true,
+                hash: Some(hash),
+                message: Some(message),
+                pushed: false,
+            };
+        }
+        outro(&format!("{} Created {} commits", "✔".green(), groups.len()));
+        Ok(())
+    }
+
+    /// Commits the changes inside each submodule that has changes of its own,
+    /// then prints what happened per submodule. The parent repository is
+    /// committed afterwards, so the updated submodule pointers can be staged
+    /// there with their own message.
+    async fn run_submodules(&mut self, config: &AutocommitConfig) -> anyhow::Result<()> {
+        let submodules = GitRepository::get_changed_submodules()?;
+        if submodules.is_empty() {
+            return Ok(());
+        }
+
+        let root = GitRepository::get_repo_root()?;
+        let mut summary = Vec::new();
+        for path in submodules {
+            outro(&format!("📦 Submodule {}", path.bold()));
+            // The git helpers work on the repository in the current directory
+            let current_dir = CurrentDirGuard::change_to(&root.join(&path))?;
+            let result = self.run_in_repo(config).await;
+            drop(current_dir);
+            summary.push((path, result));
+        }
+
+        let lines = summary
+            .iter()
+            .map(|(path, result)| match result {
+                Ok(CommitOutcome {
+                    committed: true,
+                    hash,
+                    pushed,
+                    ..
+                }) => format!(
+                    "  {} {}: committed {}{}",
+                    "✔".green(),
+                    path,
+                    hash.as_deref()
+                        .map(|hash| &hash[..hash.len().min(7)])
+                        .unwrap_or_default(),
+                    if *pushed { " and pushed" } else { "" }
+                ),
+                Ok(_) => format!("  {} {}: nothing committed", "-".yellow(), path),
+                Err(e) => format!("  {} {}: {}", "✖".red(), path, e),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        outro(&format!("{}\n{}", "Submodules:".green(), lines));
+        Ok(())
+    }
+
+    async fn run_in_repo(&mut self, config: &AutocommitConfig) -> anyhow::Result<CommitOutcome> {
+        let mut outcome = CommitOutcome::default();
         loop {
             // Get the list of changed files
             let changed_files = GitRepository::get_changed_files()?;
 
-            if self.stage_all {
+            if !self.stage.is_empty() {
+                let files = std::mem::take(&mut self.stage);
+                Self::stage_given_files(&files, &changed_files).await?;
+            } else if self.stage_all {
                 Self::stage_all_changed_files(&changed_files).await?;
-            } else {
+            } else if !self.print_only {
                 // Prompt the user if they want to see the Git status
-                let should_show_status = Confirm::with_theme(&ColorfulTheme::default())
-                    .with_prompt("Do you want to see the Git status before committing?")
-                    .default(false)
-                    .interact_opt()?
-                    .unwrap_or(false);
+                let should_show_status = Self::confirm(
+                    "Do you want to see the Git status before committing?",
+                    *config.config_data.confirm_show_status.get_value_ref(),
+                )?;
 
                 // Show the Git status if the user wants to see it
                 if should_show_status {
@@ -67,7 +729,7 @@ impl CommitCommand {
             // If there are no changes, exit the loop
             if staged_files.is_empty() && changed_files.is_empty() {
                 outro(&format!("{}", "No changes detected, exiting...".red()));
-                return Ok(());
+                return Ok(outcome);
             }
 
             // Count the number of staged files and display them to the user
@@ -75,22 +737,43 @@ impl CommitCommand {
             staged_spinner.start("Counting staged files...");
             if staged_files.is_empty() {
                 staged_spinner.stop("No files are staged");
+                // Describe only what is already staged when printing
+                if self.print_only {
+                    return Err(anyhow!(
+                        "No changes are staged, stage them with git add first"
+                    ));
+                }
+
+                // Let the model suggest which files belong together
+                if self.suggest_staging
+                    && !changed_files.is_empty()
+                    && Self::stage_suggested_group(config, &changed_files).await?
+                {
+                    continue;
+                }
 
                 // Prompt the user if they want to stage all files and generate a commit message
-                let is_stage_all_and_commit_confirmed_by_user =
-                    Confirm::with_theme(&ColorfulTheme::default())
-                        .with_prompt("Do you want to stage all files and generate commit message?")
-                        .default(true)
-                        .interact_opt()?
-                        .unwrap_or(false);
+                let is_stage_all_and_commit_confirmed_by_user = Self::confirm(
+                    "Do you want to stage all files and generate commit message?",
+                    *config.config_data.confirm_stage_all.get_value_ref(),
+                )?;
 
                 // If the user confirms, stage all files and continue the loop
                 if is_stage_all_and_commit_confirmed_by_user {
                     self.stage_all = true;
                     continue;
                 } else if !changed_files.is_empty() {
+                    if assume_yes() {
+                        return Err(anyhow!(
+                            "No files are staged, pass --stage-all or --stage to pick them with --yes"
+                        ));
+                    }
                     // Prompt the user to select files to stage
-                    let files = Self::prompt_for_selected_files(&changed_files).await?;
+                    let files = if self.tui {
+                        Self::pick_files_in_tui(&changed_files)?
+                    } else {
+                        Self::prompt_for_selected_files(&changed_files).await?
+                    };
                     GitRepository::git_add(&files).await?;
                     self.stage_all = false;
                     continue;
@@ -100,7 +783,7 @@ impl CommitCommand {
                         "{}",
                         "No files selected for staging, exiting...".red()
                     ));
-                    return Ok(());
+                    return Ok(outcome);
                 }
             }
             staged_spinner.stop(&format!(
@@ -112,69 +795,487 @@ impl CommitCommand {
                     .collect::<Vec<_>>()
                     .join("\n")
             ));
+            let (files_changed, insertions, deletions) =
+                GitRepository::get_staged_short_stat(&staged_files, DiffBase::Head)?;
+            outro(&format!(
+                "{} files changed, {} insertions(+), {} deletions(-)",
+                files_changed,
+                insertions.to_string().green(),
+                deletions.to_string().red()
+            ));
+
+            // Refuse to commit unresolved merge conflicts
+            if !self.allow_conflicts {
+                Self::assert_no_conflict_markers(&staged_files)?;
+            }
+
+            // Let .autocommit.toml files in the changed directories adjust the config
+            let config = &apply_directory_configs(config, &staged_files).await?;
 
             // Get the diff of the staged files
-            let staged_diffs = GitRepository::get_staged_file_diffs(&staged_files)?;
-
-            // Generate a commit message
-            let commit_messages: Vec<String> = self
-                .generate_autocommit_messages(config, &staged_diffs)
-                .await?;
-
-            // Prompt the user to confirm the commit message
-            let message = Self::prompt_for_selected_message(&commit_messages).await?;
-            self.commit_changes(config, &message).await?;
-            // Prompt the user to confirm the push
-            if Self::prompt_for_push()? {
+            let diff_files = self.get_diff_files(config, &staged_files)?;
+            let staged_diffs = GitRepository::get_staged_file_diffs(
+                &diff_files,
+                self.diff_style(config),
+                DiffBase::Head,
+            )?;
+            let staged_changes = GitRepository::get_staged_changes(&diff_files, DiffBase::Head)?;
+
+            // Generate a commit message, unless one was given
+            let Some(message) = self
+                .get_message(config, &staged_diffs, &staged_changes)
+                .await?
+            else {
+                return Ok(outcome);
+            };
+            let message = if self.suggest_coauthors {
+                Self::prompt_for_coauthors(config, &staged_files, message)?
+            } else {
+                message
+            };
+            if self.dry_run {
+                return Ok(Self::print_dry_run_message(message));
+            }
+            let hash = self.commit_changes(config, &message).await?;
+            outcome = CommitOutcome {
+                committed: true,
+                hash: Some(hash),
+                message: Some(message.clone()),
+                pushed: false,
+            };
+            if self.print_push_command {
+                Self::print_push_command().await?;
+            } else if Self::prompt_for_push(*config.config_data.confirm_push.get_value_ref())? {
                 // Prompt the user to select a remote repository
-                if let Some(remote) = Self::prompt_for_remote().await? {
+                if let Some(remote) = Self::prompt_for_remote(self.choose_remote).await? {
                     // Pull changes from the remote repository if necessary
-                    if Self::prompt_for_pull(&remote)? {
+                    if Self::should_pull(config, &remote).await?
+                        && Self::prompt_for_pull(
+                            &remote,
+                            *config.config_data.confirm_pull.get_value_ref(),
+                        )?
+                    {
                         Self::pull_changes(&remote).await?;
                     }
                     // Push changes to the remote repository
                     Self::push_changes(&remote).await?;
+                    outcome.pushed = true;
+                    if self.pr {
+                        Self::create_pull_request(&message).await?;
+                    }
                     info!("Autocommit process completed successfully");
                 }
             }
 
-            // Prompt the user to continue or exit the loop
-            let should_continue = Self::prompt_to_continue().await?;
+            // Prompt the user to continue or exit the loop. A given message
+            // is only used for one commit
+            let should_continue = self.message.is_none()
+                && Self::prompt_to_continue(*config.config_data.confirm_continue.get_value_ref())
+                    .await?;
             if !should_continue {
                 outro(&format!("{}", "Exiting...".red()));
-                return Ok(());
+                return Ok(outcome);
             }
 
             self.stage_all = false;
         }
     }
 
+    /// Whether generated messages are cached and reused for the same diff.
+    fn use_cache(&self, config: &AutocommitConfig) -> bool {
+        !self.no_cache && *config.config_data.cache_enabled.get_value_ref()
+    }
+
+    /// The `--diff-style` flag wins over `diff_context_lines`.
+    fn diff_style(&self, config: &AutocommitConfig) -> DiffStyle {
+        self.diff_style.unwrap_or_else(|| {
+            config
+                .config_data
+                .diff_context_lines
+                .get_value_ref()
+                .get_inner_value()
+                .map_or(DiffStyle::default(), DiffStyle::Lines)
+        })
+    }
+
+    /// Like `exclude_from_diff`, but fails when every file is excluded and
+    /// there's no --message to use instead of a generated one.
+    fn get_diff_files(
+        &self,
+        config: &AutocommitConfig,
+        files: &[String],
+    ) -> anyhow::Result<Vec<String>> {
+        let diff_files = self.exclude_from_diff(config, files)?;
+        if diff_files.is_empty() && self.message.is_none() {
+            return Err(anyhow!(
+                "Every staged file matches diff_exclude_globs, so there is no diff to describe. Pass --message or change diff_exclude_globs"
+            ));
+        }
+        Ok(diff_files)
+    }
+
+    /// Leaves the files matching `diff_exclude_globs` out of the diffs and
+    /// lists them with the pattern that matched.
+    fn exclude_from_diff(
+        &self,
+        config: &AutocommitConfig,
+        files: &[String],
+    ) -> anyhow::Result<Vec<String>> {
+        let globs = config
+            .config_data
+            .diff_exclude_globs
+            .get_value_ref()
+            .get_inner_value();
+        let (diff_files, excluded_files) = GitRepository::exclude_diff_files(files, &globs)?;
+        if !excluded_files.is_empty() {
+            outro(&format!(
+                "{} files excluded from the diff:\n{}",
+                excluded_files.len().to_string().yellow(),
+                excluded_files
+                    .iter()
+                    .map(|file| format!("  🚫 {} ({})", file.path, file.pattern.dimmed()))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+        Ok(diff_files)
+    }
+
+    /// Stages exactly the given files. Every file must have changes, and no
+    /// other file may be staged already so the commit contains only them.
+    async fn stage_given_files(files: &[String], changed_files: &[String]) -> anyhow::Result<()> {
+        let mut repo_paths = Vec::with_capacity(files.len());
+        for file in files {
+            let repo_path = GitRepository::to_repo_path(file)?;
+            if !changed_files.contains(&repo_path) {
+                if Path::new(file).exists() {
+                    return Err(anyhow!("File '{}' has no changes to commit", file));
+                }
+                return Err(anyhow!("File '{}' does not exist", file));
+            }
+            repo_paths.push(repo_path);
+        }
+
+        let other_staged_files = GitRepository::get_staged_files()?
+            .into_iter()
+            .filter(|file| !repo_paths.contains(file))
+            .collect::<Vec<_>>();
+        if !other_staged_files.is_empty() {
+            return Err(anyhow!(
+                "Other files are already staged:\n{}\nUnstage them or run without --stage.",
+                other_staged_files
+                    .iter()
+                    .map(|file| format!("  📄 {}", file))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+
+        GitRepository::git_add(files).await
+    }
+
+    /// Stages one of the model's suggested groups. Returns `false` if the user
+    /// doesn't pick a group so the regular staging prompts can take over.
+    async fn stage_suggested_group(
+        config: &AutocommitConfig,
+        changed_files: &[String],
+    ) -> anyhow::Result<bool> {
+        confirm_send(config, changed_files.iter().map(String::len).sum())?;
+        let mut suggestion_spinner = spinner();
+        suggestion_spinner.start("Suggesting how to stage the changes...");
+        let groups = suggest_staging_groups(config, changed_files).await?;
+        if groups.is_empty() {
+            suggestion_spinner.stop("No staging suggestions available");
+            return Ok(false);
+        }
+        suggestion_spinner.stop(&format!(
+            "{} staging groups suggested",
+            groups.len().to_string().green()
+        ));
+
+        let items = groups
+            .iter()
+            .map(|group| format!("{} ({})", group.description, group.pathspecs.join(", ")))
+            .collect::<Vec<_>>();
+        let selection = if assume_yes() {
+            Some(0)
+        } else {
+            Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Select a group to stage")
+                .items(&items)
+                .default(0)
+                .interact_opt()?
+        };
+        let Some(index) = selection else {
+            return Ok(false);
+        };
+
+        let matches = GitRepository::match_pathspecs(&groups[index].pathspecs, changed_files)?;
+        let mut pathspecs = Vec::new();
+        for (pathspec, files) in matches {
+            if files.is_empty() {
+                outro(&format!(
+                    "{} '{}' does not match any changed file, skipping",
+                    "⚠".yellow(),
+                    pathspec
+                ));
+                continue;
+            }
+            outro(&format!(
+                "{} {}:\n{}",
+                "✔".green(),
+                pathspec,
+                files
+                    .iter()
+                    .map(|file| format!("  📄 {}", file))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+            pathspecs.push(pathspec);
+        }
+
+        if pathspecs.is_empty() || !Self::confirm("Stage these files?", true)? {
+            return Ok(false);
+        }
+        GitRepository::git_add_pathspecs(&pathspecs).await?;
+        Ok(true)
+    }
+
+    /// Offers the people who last changed the modified lines as co-authors
+    /// and adds the chosen ones as `Co-authored-by` trailers.
+    fn prompt_for_coauthors(
+        config: &AutocommitConfig,
+        staged_files: &[String],
+        message: String,
+    ) -> anyhow::Result<String> {
+        let own_email = config.config_data.email.get_value_ref().get_inner_value();
+        let authors = GitRepository::get_blame_authors(staged_files)?
+            .into_iter()
+            .filter(|author| !author.email.eq_ignore_ascii_case(own_email))
+            .take(MAX_COAUTHOR_SUGGESTIONS)
+            .collect::<Vec<_>>();
+        if authors.is_empty() {
+            outro("No other authors found for the changed lines");
+            return Ok(message);
+        }
+        if assume_yes() {
+            return Ok(message);
+        }
+
+        let items = authors
+            .iter()
+            .map(|author| {
+                format!(
+                    "{} <{}> ({} lines)",
+                    author.name, author.email, author.lines
+                )
+            })
+            .collect::<Vec<_>>();
+        let selected = MultiSelect::with_theme(&ColorfulTheme::default())
+            .with_prompt("Select the co-authors to credit:")
+            .items(&items)
+            .interact_opt()?
+            .unwrap_or_default();
+
+        // Footers are inserted at the top of the trailers, so add them in reverse
+        Ok(selected.iter().rev().fold(message, |message, &i| {
+            let author = &authors[i];
+            append_footer(
+                &message,
+                &format!("Co-authored-by: {} <{}>", author.name, author.email),
+            )
+        }))
+    }
+
+    /// Appends a closing footer such as `Closes #123` when the branch name
+    /// references an issue and `auto_close_issues` is enabled.
+    fn add_closing_footer(
+        config: &AutocommitConfig,
+        messages: Vec<String>,
+    ) -> anyhow::Result<Vec<String>> {
+        let config_data = &config.config_data;
+        if !*config_data.auto_close_issues.get_value_ref() {
+            return Ok(messages);
+        }
+
+        let Some(branch) = GitRepository::get_current_branch()? else {
+            return Ok(messages);
+        };
+        let keyword = config_data.close_keyword.get_value_ref().get_inner_value();
+        let Some(footer) =
+            extract_ticket(&branch).and_then(|ticket| ticket.closing_footer(keyword))
+        else {
+            debug!("No issue to close found in branch {}", branch);
+            return Ok(messages);
+        };
+
+        debug!("Adding '{}' from branch {}", footer, branch);
+        Ok(messages
+            .iter()
+            .map(|message| append_footer(message, &footer))
+            .collect())
+    }
+
+    fn assert_no_conflict_markers(staged_files: &[String]) -> anyhow::Result<()> {
+        let conflicted_files = GitRepository::get_files_with_conflict_markers(staged_files)?;
+        if conflicted_files.is_empty() {
+            return Ok(());
+        }
+
+        Err(anyhow!(
+            "Staged files contain merge conflict markers:\n{}\nResolve the conflicts or pass --allow-conflicts to commit anyway.",
+            conflicted_files
+                .iter()
+                .map(|file| format!("  📄 {}", file))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ))
+    }
+
+    /// The `Co-authored-by` trailers for the `co_authors` config key and the
+    /// `--co-author` options, in that order.
+    fn co_author_trailers(&self, config: &AutocommitConfig) -> anyhow::Result<Vec<String>> {
+        config
+            .config_data
+            .co_authors
+            .get_value_ref()
+            .get_inner_value()
+            .iter()
+            .chain(&self.co_authors)
+            .map(|entry| co_author_trailer(entry))
+            .collect()
+    }
+
+    /// Fixes small deviations from the `type(scope): subject` form and asks
+    /// before committing a message that can't be fixed.
+    fn ensure_conventional(message: String) -> anyhow::Result<String> {
+        if let Some(conventional) = to_conventional(&message) {
+            if conventional != message {
+                outro(&format!(
+                    "Reformatted the subject as a conventional commit: {}",
+                    conventional.lines().next().unwrap_or_default().green()
+                ));
+            }
+            return Ok(conventional);
+        }
+
+        outro(&format!(
+            "{} The subject doesn't start with one of the conventional commit types: {}",
+            "⚠".yellow(),
+            CONVENTIONAL_TYPES.join(", ")
+        ));
+        if Self::confirm("Do you want to commit it anyway?", false)? {
+            Ok(message)
+        } else {
+            Err(anyhow!(
+                "Commit cancelled, the message isn't a conventional commit"
+            ))
+        }
+    }
+
     pub async fn commit_changes(
         &self,
         config: &AutocommitConfig,
         commit_message: &str,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<String> {
         const COMMITTING_CHANGES: &str = "Committing changes...";
 
+        let mut commit_message = normalize_message(commit_message);
+        // Each trailer goes first in the trailer block, so keep the order by going backwards
+        for trailer in self.co_author_trailers(config)?.iter().rev() {
+            commit_message = normalize_message(&append_footer(&commit_message, trailer));
+        }
+        if *config.config_data.commit_style.get_value_ref() == CommitStyle::Conventional {
+            commit_message = Self::ensure_conventional(commit_message)?;
+        }
+        if let Some(max_message_bytes) = config
+            .config_data
+            .max_message_bytes
+            .get_value_ref()
+            .get_inner_value()
+        {
+            if commit_message.len() > max_message_bytes {
+                outro(&format!(
+                    "{} The commit message is {} bytes, more than max_message_bytes ({})",
+                    "⚠".yellow(),
+                    commit_message.len(),
+                    max_message_bytes
+                ));
+                if Self::confirm(
+                    "Do you want to truncate the body, keeping the subject and trailers?",
+                    true,
+                )? {
+                    // Leave room for the trailing newline
+                    commit_message = normalize_message(&truncate_body(
+                        &commit_message,
+                        max_message_bytes.saturating_sub(1),
+                    ));
+                }
+            }
+        }
+
         let mut commit_spinner = spinner();
         commit_spinner.start(COMMITTING_CHANGES);
 
-        let name = config.config_data.name.get_value_ref();
-        let email = config.config_data.email.get_value_ref();
+        let name = config.config_data.name.get_value_ref().get_inner_value();
+        let email = config.config_data.email.get_value_ref().get_inner_value();
+        let committer_name = config
+            .config_data
+            .committer_name
+            .get_value_ref()
+            .get_inner_value();
+        let committer_email = config
+            .config_data
+            .committer_email
+            .get_value_ref()
+            .get_inner_value();
+        let committer = (
+            committer_name.as_deref().unwrap_or(name),
+            committer_email.as_deref().unwrap_or(email),
+        );
 
-        let commit_output = GitRepository::git_commit(commit_message, name, email).await?;
-        let commit_table = GitRepository::get_commit_summary_table(name, email).await?;
+        let signing_key = config
+            .config_data
+            .signing_key
+            .get_value_ref()
+            .get_inner_value();
+        let signing = if *config.config_data.sign_commits.get_value_ref() {
+            CommitSigning::Sign(signing_key.as_deref())
+        } else {
+            CommitSigning::GitConfig
+        };
+        let commit_output = GitRepository::git_commit(
+            &commit_message,
+            name,
+            email,
+            committer,
+            self.amend,
+            signing,
+            self.no_verify,
+        )
+        .await?;
+        // The commit already landed, so a summary that can't be built only
+        // falls back to git's own output instead of failing the command.
+        let commit_table = GitRepository::get_commit_summary_table(name, email)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to build the commit summary: {}", e);
+                None
+            });
 
         commit_spinner.stop(&format!("{} Changes committed successfully", "✔".green()));
-        if GitRepository::get_commit_count()? == 1 {
-            outro(&commit_output);
-        } else {
-            commit_table.printstd();
+        match commit_table {
+            Some(commit_table)
+                if GitRepository::get_commit_count().is_ok_and(|count| count > 1) =>
+            {
+                commit_table.printstd()
+            }
+            _ => outro(&commit_output),
         }
 
         debug!("Changes committed successfully");
 
-        Ok(())
+        GitRepository::get_head_commit_hash()
     }
 
     pub async fn pull_changes(remote: &str) -> anyhow::Result<()> {
@@ -183,7 +1284,23 @@ impl CommitCommand {
             "Pulling changes from remote repository {}...",
             remote.green().bold()
         ));
-        GitRepository::git_pull(remote).await?;
+        if let Err(e) = GitRepository::git_pull(remote).await {
+            let conflicted_files = GitRepository::get_unmerged_files()?;
+            if conflicted_files.is_empty() {
+                return Err(e);
+            }
+            pull_spinner.stop(&format!(
+                "{} Pulling from {} left conflicts in:\n{}",
+                "✖".red(),
+                remote.green().bold(),
+                conflicted_files
+                    .iter()
+                    .map(|file| format!("  📄 {}", file))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+            return Self::resolve_pull_conflicts(&conflicted_files).await;
+        }
         pull_spinner.stop(&format!(
             "{} Changes pulled successfully from remote repository {}.",
             "✔".green(),
@@ -196,6 +1313,77 @@ impl CommitCommand {
         Ok(())
     }
 
+    /// Lets the user abort a conflicted pull, open the conflicted files or
+    /// resolve them on their own. The push is skipped in every case, since
+    /// the merge isn't finished yet.
+    async fn resolve_pull_conflicts(conflicted_files: &[String]) -> anyhow::Result<()> {
+        const ABORT: &str = "Abort the pull";
+        const OPEN: &str = "Open the conflicted files in the editor";
+        const MANUAL: &str = "Resolve the conflicts manually";
+        const FINISH_INSTRUCTIONS: &str = "Resolve the conflicts, stage the files and run `git commit` (or `git rebase --continue`), then push";
+
+        let options = [ABORT, OPEN, MANUAL];
+        let selection = if assume_yes() {
+            Some(0)
+        } else {
+            Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("How do you want to handle the conflicts?")
+                .items(&options)
+                .default(0)
+                .interact_opt()?
+        };
+
+        match selection.map(|index| options[index]) {
+            Some(ABORT) => {
+                GitRepository::git_abort_pull().await?;
+                Err(anyhow!(
+                    "Pull aborted, your commit is kept. Pull and resolve the conflicts before pushing"
+                ))
+            }
+            Some(OPEN) => {
+                let editor = GitRepository::get_editor().await?;
+                let root = GitRepository::get_repo_root()?;
+                let paths = conflicted_files
+                    .iter()
+                    .map(|file| root.join(file))
+                    .collect::<Vec<_>>();
+                open_files(
+                    &editor,
+                    &paths.iter().map(|path| path.as_path()).collect::<Vec<_>>(),
+                )?;
+                Err(anyhow!("Push skipped. {}", FINISH_INSTRUCTIONS))
+            }
+            _ => Err(anyhow!("Push skipped. {}", FINISH_INSTRUCTIONS)),
+        }
+    }
+
+    /// Prints the push command for the current branch without prompting,
+    /// preferring the last remote pushed to, then `origin`.
+    async fn print_push_command() -> anyhow::Result<()> {
+        let remotes = GitRepository::get_git_remotes()?;
+        let repo_path = GitRepository::get_repo_root()?.display().to_string();
+        let state = State::load().await?;
+        let remote = state
+            .get_last_remote(&repo_path)
+            .filter(|remote| remotes.iter().any(|r| r == remote))
+            .or_else(|| remotes.iter().find(|r| *r == "origin").map(|r| r.as_str()))
+            .or_else(|| remotes.first().map(|r| r.as_str()));
+
+        let Some(remote) = remote else {
+            outro(&format!(
+                "{}",
+                "No remote repository found, add one with `git remote add <name> <url>` before pushing".yellow()
+            ));
+            return Ok(());
+        };
+        let branch = GitRepository::get_current_branch()?.unwrap_or_else(|| String::from("HEAD"));
+        outro(&format!(
+            "Push the commit later with:\n  {}",
+            format!("git push {} {}", remote, branch).bold()
+        ));
+        Ok(())
+    }
+
     pub async fn push_changes(remote: &str) -> anyhow::Result<()> {
         let mut push_spinner = spinner();
         push_spinner.start(&format!(
@@ -215,55 +1403,427 @@ impl CommitCommand {
         Ok(())
     }
 
+    pub async fn create_pull_request(commit_message: &str) -> anyhow::Result<()> {
+        if !GitHubCli::is_available().await {
+            outro(&format!(
+                "{}",
+                "GitHub CLI (gh) not found, skipping pull request creation".yellow()
+            ));
+            return Ok(());
+        }
+
+        let (title, body) = commit_message
+            .trim()
+            .split_once('\n')
+            .map(|(title, body)| (title.trim(), body.trim()))
+            .unwrap_or((commit_message.trim(), ""));
+
+        let mut pr_spinner = spinner();
+        pr_spinner.start("Creating pull request...");
+        let pr_url = GitHubCli::create_pull_request(title, body).await?;
+        pr_spinner.stop(&format!(
+            "{} Pull request created: {}",
+            "✔".green(),
+            pr_url.green().bold()
+        ));
+        debug!("Pull request created {}", pr_url);
+        Ok(())
+    }
+
     pub async fn generate_autocommit_messages(
         &self,
         config: &AutocommitConfig,
         content: &[String],
+        staged_changes: &[StagedChange],
     ) -> anyhow::Result<Vec<String>> {
-        let mut commit_spinner = spinner();
+        let diff_privacy = *config.config_data.diff_privacy.get_value_ref();
+        if diff_privacy == DiffPrivacy::NoPaths {
+            outro(&format!(
+                "{} File paths are hidden from the model, so messages may be less specific",
+                "⚠".yellow()
+            ));
+        }
+        let content = redact_diff(content, staged_changes, diff_privacy);
+        let staged_changes = &redact_changes(staged_changes, diff_privacy);
+        confirm_send(config, content.iter().map(String::len).sum())?;
 
-        let mut chat_context = ChatContext::get_initial_context(config);
-        let content = content.join("");
-        chat_context.add_message(MessageRole::User, content.to_owned());
+        let mut chat_context = ChatContext::get_initial_context(config, self.subject_only);
+        let mut areas = Vec::new();
+        if !staged_changes.is_empty() && staged_changes.iter().all(StagedChange::is_pure_rename) {
+            chat_context.add_renames(staged_changes);
+        } else if !staged_changes.is_empty() && staged_changes.iter().all(|c| c.is_binary) {
+            chat_context.add_binary_changes(staged_changes);
+        } else if !staged_changes.is_empty()
+            && staged_changes.iter().all(StagedChange::is_mode_change)
+        {
+            chat_context.add_mode_changes(staged_changes);
+        } else {
+            let max_diff_lines = config
+                .config_data
+                .max_diff_lines
+                .get_value_ref()
+                .get_inner_value();
+            let diff = content.join("");
+            let exceeds_budget =
+                max_diff_lines.is_some_and(|max_diff_lines| diff.lines().count() > max_diff_lines);
+            if self.map_reduce || exceeds_budget {
+                let chunks = chunk_diffs(&content, max_diff_lines.unwrap_or(DEFAULT_CHUNK_LINES));
+                let mut summary_spinner = spinner();
+                summary_spinner.start(&format!(
+                    "Summarizing the diff in {} parts...",
+                    chunks.len()
+                ));
+                let summaries = summarize_chunks(config, chunks).await?;
+                summary_spinner.stop("📝 Diff summarized successfully");
+                chat_context.add_partial_summaries(&summaries);
+            } else {
+                let max_diff_tokens = config
+                    .config_data
+                    .max_diff_tokens
+                    .get_value_ref()
+                    .get_inner_value();
+                let diff = match max_diff_tokens {
+                    Some(max_diff_tokens) if estimate_tokens(&diff) > max_diff_tokens => {
+                        outro(&format!(
+                            "{} The diff is about {} tokens, more than max_diff_tokens ({}), so it was truncated",
+                            "⚠".yellow(),
+                            estimate_tokens(&diff),
+                            max_diff_tokens
+                        ));
+                        fit_diffs_to_tokens(&content, max_diff_tokens)
+                    }
+                    _ => diff,
+                };
+                chat_context.add_diff(&diff, max_diff_lines);
+                if self.per_file {
+                    areas = group_diff_by_area(&content);
+                }
+            }
+        }
+        let context_files = self.read_context_files(diff_privacy).await?;
+        if !context_files.is_empty() {
+            chat_context.add_context_files(&context_files);
+        }
+        if let Some(draft) = &self.draft {
+            chat_context.add_draft(draft);
+        }
 
-        commit_spinner.start("Generating the commit messages...");
-        let commit_messages = chat_context.generate_messages(config, self.n).await?;
+        let mut commit_spinner = spinner();
+        let mut candidates = if areas.len() > 1 {
+            commit_spinner.start(&format!(
+                "Generating the commit messages for {} areas...",
+                areas.len()
+            ));
+            generate_area_candidates(
+                config,
+                &areas,
+                self.subject_only,
+                &context_files,
+                self.draft.as_deref(),
+                self.message_count(),
+                self.use_cache(config),
+            )
+            .await?
+        } else {
+            commit_spinner.start("Generating the commit messages...");
+            let mut show_progress = |streamed: &str| {
+                commit_spinner.set_message(&format!(
+                    "Generating the commit messages... {}",
+                    stream_preview(streamed)
+                ));
+            };
+            let candidates = if self.use_cache(config) {
+                let cache = Mutex::new(MessageCache::load().await?);
+                let candidates = chat_context
+                    .generate_cached_candidates(
+                        config,
+                        self.message_count(),
+                        Some(&mut show_progress),
+                        &cache,
+                    )
+                    .await?;
+                let cache = cache.into_inner().unwrap_or_else(PoisonError::into_inner);
+                if let Err(e) = cache.save().await {
+                    warn!("Failed to save the message cache: {}", e);
+                }
+                candidates
+            } else {
+                chat_context
+                    .generate_candidates(config, self.message_count(), Some(&mut show_progress))
+                    .await?
+            };
+            Self::correct_subject_verbs(config, &chat_context, candidates).await?
+        };
         commit_spinner.stop("📝 Commit messages generated successfully");
 
+        let enforce_imperative = *config.config_data.enforce_imperative.get_value_ref();
+        let allowed_verbs = config
+            .config_data
+            .allowed_verbs
+            .get_value_ref()
+            .get_inner_value();
+        if let Some(command) = config
+            .config_data
+            .message_filter_command
+            .get_value_ref()
+            .get_inner_value()
+        {
+            for candidate in candidates.iter_mut() {
+                match filter_message(&command, &candidate.message).await {
+                    Ok(message) => candidate.message = message,
+                    Err(e) => outro(&format!(
+                        "{} {}, keeping the original message",
+                        "⚠".yellow(),
+                        e
+                    )),
+                }
+            }
+        }
+        for candidate in candidates.iter_mut() {
+            candidate.message = separate_subject(&candidate.message);
+        }
+        if *config.config_data.notify.get_value_ref() {
+            let subject = candidates[0].message.lines().next().unwrap_or_default();
+            send_notification("Commit messages are ready", subject).await;
+        }
+        let vary_temperature =
+            *config.config_data.vary_temperature.get_value_ref() && self.message_count() > 1;
+
+        let max_subject_length = config
+            .config_data
+            .max_subject_length
+            .get_value_ref()
+            .get_inner_value();
+
+        let mut header_row =
+            row![bFb->"Index", bFb->"Message", bFb->"Subject", bFb->"Lines", bFb->"Chars"];
+        if vary_temperature {
+            header_row.add_cell(cell!(bFb->"Temp"));
+        }
+        if enforce_imperative {
+            header_row.add_cell(cell!(bFb->"Mood"));
+        }
+        if !allowed_verbs.is_empty() {
+            header_row.add_cell(cell!(bFb->"Verb"));
+        }
+        if areas.len() > 1 {
+            header_row.add_cell(cell!(bFb->"Areas"));
+        }
+
         let mut table = Table::new();
         table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
         let title_row = Row::new(vec![Cell::new_align(
             "Autocommit Messages",
             Alignment::CENTER,
         )
-        .with_hspan(4)
+        .with_hspan(header_row.len())
         .with_style(Attr::ForegroundColor(color::GREEN))]);
         table.add_row(title_row);
-        table.add_row(row![bFb->"Index", bFb->"Message", bFb->"Lines", bFb->"Chars"]);
+        table.add_row(header_row);
 
-        for (i, commit_message) in commit_messages.iter().enumerate() {
+        for (i, candidate) in candidates.iter().enumerate() {
+            let commit_message = &candidate.message;
             let wrapped_message = fill(commit_message, 60);
 
-            let num_lines = wrapped_message.lines().count();
-            let num_chars = wrapped_message.chars().count();
-            table.add_row(row![i, wrapped_message, num_lines, num_chars]);
+            let num_lines = commit_message.lines().count();
+            let num_chars = commit_message.chars().count();
+            let mut row = Row::new(vec![
+                Cell::new(&i.to_string()),
+                Cell::new(&wrapped_message),
+                Self::subject_length_cell(commit_message, max_subject_length),
+                Cell::new(&num_lines.to_string()),
+                Cell::new(&num_chars.to_string()),
+            ]);
+            if vary_temperature {
+                row.add_cell(Cell::new(
+                    &candidate
+                        .temperature
+                        .map(|temperature| format!("{:.1}", temperature))
+                        .unwrap_or_else(|| String::from("default")),
+                ));
+            }
+            if enforce_imperative {
+                row.add_cell(match non_imperative_verb(commit_message) {
+                    Some(verb) => Cell::new(&format!("⚠ {}", verb))
+                        .with_style(Attr::ForegroundColor(color::YELLOW)),
+                    None => Cell::new("✔").with_style(Attr::ForegroundColor(color::GREEN)),
+                });
+            }
+            if !allowed_verbs.is_empty() {
+                row.add_cell(match disallowed_verb(commit_message, &allowed_verbs) {
+                    Some(verb) => Cell::new(&format!("⚠ {}", verb))
+                        .with_style(Attr::ForegroundColor(color::YELLOW)),
+                    None => Cell::new("✔").with_style(Attr::ForegroundColor(color::GREEN)),
+                });
+            }
+            if areas.len() > 1 {
+                row.add_cell(Cell::new(
+                    &areas
+                        .iter()
+                        .map(|(area, _)| area.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ));
+            }
+            table.add_row(row);
         }
 
-        table.printstd();
+        if ui_on_stderr() {
+            table.print(&mut std::io::stderr())?;
+        } else {
+            table.printstd();
+        }
 
         debug!("Commit messages generated successfully");
-        Ok(commit_messages)
+        Ok(candidates
+            .into_iter()
+            .map(|candidate| candidate.message)
+            .collect())
     }
 
-    pub async fn prompt_to_continue() -> anyhow::Result<bool> {
-        let should_continue = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Do you want to continue?")
-            .default(false)
-            .interact()?;
-        Ok(should_continue)
+    /// Reads the `--context-file` files, named by their path unless paths are
+    /// hidden from the model.
+    async fn read_context_files(
+        &self,
+        diff_privacy: DiffPrivacy,
+    ) -> anyhow::Result<Vec<(String, String)>> {
+        let mut context_files = Vec::with_capacity(self.context_file.len());
+        for (i, path) in self.context_file.iter().enumerate() {
+            let contents = tokio::fs::read_to_string(path)
+                .await
+                .with_context(|| format!("Failed to read context file {}", path.display()))?;
+            let name = match diff_privacy {
+                DiffPrivacy::Full => path.display().to_string(),
+                _ => format!("context file {}", i + 1),
+            };
+            context_files.push((name, contents));
+        }
+        Ok(context_files)
     }
 
-    pub async fn prompt_for_remote() -> anyhow::Result<Option<String>> {
+    /// Regenerates candidates whose subject verb `enforce_imperative` or
+    /// `allowed_verbs` rules out, in `chat_context`.
+    async fn correct_subject_verbs(
+        config: &AutocommitConfig,
+        chat_context: &ChatContext,
+        mut candidates: Vec<Candidate>,
+    ) -> anyhow::Result<Vec<Candidate>> {
+        let enforce_imperative = *config.config_data.enforce_imperative.get_value_ref();
+        if enforce_imperative {
+            candidates = Self::regenerate_bad_verbs(
+                config,
+                chat_context,
+                candidates,
+                non_imperative_verb,
+                "not in the imperative mood",
+                |verb| {
+                    format!(
+                        "The subject starts with '{}'. Rewrite the commit message so the subject uses the imperative mood, e.g. 'Add' instead of 'Added', 'Adds' or 'Adding'.",
+                        verb
+                    )
+                },
+            )
+            .await?;
+        }
+        let allowed_verbs = config
+            .config_data
+            .allowed_verbs
+            .get_value_ref()
+            .get_inner_value();
+        if !allowed_verbs.is_empty() {
+            candidates = Self::regenerate_bad_verbs(
+                config,
+                chat_context,
+                candidates,
+                |message| disallowed_verb(message, &allowed_verbs),
+                "not an allowed verb",
+                |verb| {
+                    format!(
+                        "The subject starts with '{}'. Rewrite the commit message so the subject starts with one of these verbs: {}.",
+                        verb,
+                        allowed_verbs.join(", ")
+                    )
+                },
+            )
+            .await?;
+        }
+        Ok(candidates)
+    }
+
+    /// Asks the model once to rewrite candidates whose subject starts with a
+    /// verb that `find_verb` returns, which is `problem`, with the correction
+    /// `instruction` gives for the verb. Candidates that fail to regenerate
+    /// are kept as they are.
+    async fn regenerate_bad_verbs(
+        config: &AutocommitConfig,
+        chat_context: &ChatContext,
+        candidates: Vec<Candidate>,
+        find_verb: impl Fn(&str) -> Option<String>,
+        problem: &str,
+        instruction: impl Fn(&str) -> String,
+    ) -> anyhow::Result<Vec<Candidate>> {
+        let mut checked_candidates = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            let Some(verb) = find_verb(&candidate.message) else {
+                checked_candidates.push(candidate);
+                continue;
+            };
+
+            warn!(
+                "Subject starts with '{}', which is {}, regenerating",
+                verb, problem
+            );
+            let mut correction_context = chat_context.clone();
+            correction_context.add_correction(&candidate.message, instruction(&verb));
+            match correction_context
+                .generate_candidates(config, 1, None)
+                .await
+            {
+                Ok(mut regenerated) if !regenerated.is_empty() => {
+                    checked_candidates.push(regenerated.remove(0))
+                }
+                _ => checked_candidates.push(candidate),
+            }
+        }
+        Ok(checked_candidates)
+    }
+
+    /// The subject length, marked red when it's over `max_subject_length`.
+    fn subject_length_cell(commit_message: &str, max_subject_length: Option<usize>) -> Cell {
+        let subject_length = subject_length(commit_message);
+        if exceeds_subject_limit(commit_message, max_subject_length) {
+            return Cell::new(&format!("✖ {}", subject_length))
+                .with_style(Attr::ForegroundColor(color::RED));
+        }
+        let subject_color = if subject_length <= SUBJECT_SOFT_LIMIT {
+            color::GREEN
+        } else {
+            color::YELLOW
+        };
+        Cell::new(&subject_length.to_string()).with_style(Attr::ForegroundColor(subject_color))
+    }
+
+    fn confirm(prompt: impl Into<String>, default: bool) -> anyhow::Result<bool> {
+        if assume_yes() {
+            return Ok(default);
+        }
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .default(default)
+            .interact_opt()?
+            .unwrap_or(false);
+        Ok(confirmed)
+    }
+
+    pub async fn prompt_to_continue(default: bool) -> anyhow::Result<bool> {
+        // Non-interactive runs make a single commit
+        if assume_yes() {
+            return Ok(false);
+        }
+        Self::confirm("Do you want to continue?", default)
+    }
+
+    pub async fn prompt_for_remote(choose_remote: bool) -> anyhow::Result<Option<String>> {
         let remotes = GitRepository::get_git_remotes()?;
         if remotes.is_empty() {
             eprintln!("  {}", "No remote repository found".yellow());
@@ -274,6 +1834,28 @@ impl CommitCommand {
             return Ok(Some(remotes[0].clone()));
         }
 
+        let repo_path = GitRepository::get_repo_root()?.display().to_string();
+        let mut state = State::load().await?;
+        if !choose_remote {
+            if let Some(remote) = state
+                .get_last_remote(&repo_path)
+                .filter(|remote| remotes.iter().any(|r| r == remote))
+            {
+                outro(&format!(
+                    "Using remote {} (pass --choose-remote to pick another)",
+                    remote.green()
+                ));
+                return Ok(Some(remote.to_owned()));
+            }
+        }
+        if assume_yes() {
+            outro(&format!(
+                "{} Several remotes found, skipping the push",
+                "⚠".yellow()
+            ));
+            return Ok(None);
+        }
+
         let remote_items = remotes.iter().map(|r| r.as_str()).collect::<Vec<_>>();
         let selected_remote = MultiSelect::with_theme(&ColorfulTheme::default())
             .with_prompt("Select the remote repository to push changes to:")
@@ -286,7 +1868,10 @@ impl CommitCommand {
                 outro("No remote repository selected, exiting...");
                 return Ok(None);
             }
-            Ok(Some(remotes[items[0]].clone()))
+            let remote = remotes[items[0]].clone();
+            state.set_last_remote(&repo_path, &remote);
+            state.save().await?;
+            Ok(Some(remote))
         } else {
             outro("No remote repository selected, exiting...");
             Ok(None)
@@ -294,6 +1879,12 @@ impl CommitCommand {
     }
 
     pub async fn prompt_for_selected_message(commit_messages: &[String]) -> anyhow::Result<String> {
+        if assume_yes() {
+            return commit_messages
+                .first()
+                .cloned()
+                .ok_or_else(|| anyhow!("No commit message was generated"));
+        }
         let index = Input::<usize>::with_theme(&ColorfulTheme::default())
             .with_prompt(format!(
                 "{}",
@@ -349,18 +1940,25 @@ impl CommitCommand {
 
             Ok(files)
         } else {
-            return Err(anyhow!("No files selected for staging"));
+            Err(anyhow!("No files selected for staging"))
         }
     }
 
-    pub fn prompt_for_push() -> anyhow::Result<bool> {
-        let push_confirmed_by_user = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!(
-                "Do you want to push these changes to remote repository?",
-            ))
-            .default(true)
-            .interact_opt()?
-            .unwrap_or(true);
+    /// Like `prompt_for_selected_files`, with line counts and a diff preview.
+    fn pick_files_in_tui(changed_files: &[String]) -> anyhow::Result<Vec<String>> {
+        let changes = GitRepository::get_worktree_changes(changed_files)?;
+        match pick_files(&changes)? {
+            Some(files) if files.is_empty() => Err(anyhow!("Please select at least one file")),
+            Some(files) => Ok(files),
+            None => Err(anyhow!("No files selected for staging")),
+        }
+    }
+
+    pub fn prompt_for_push(default: bool) -> anyhow::Result<bool> {
+        let push_confirmed_by_user = Self::confirm(
+            "Do you want to push these changes to remote repository?",
+            default,
+        )?;
 
         if push_confirmed_by_user {
             Ok(true)
@@ -370,20 +1968,39 @@ impl CommitCommand {
         }
     }
 
-    pub fn prompt_for_pull(remote: &str) -> anyhow::Result<bool> {
-        let pull_confirmed_by_user = Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!(
+    pub async fn should_pull(config: &AutocommitConfig, remote: &str) -> anyhow::Result<bool> {
+        if *config.config_data.pull_before_push.get_value_ref() {
+            return Ok(true);
+        }
+
+        // Whether to pull is only a hint, so a failed fetch shouldn't stop the push
+        if let Err(e) = GitRepository::git_fetch(remote).await {
+            outro(&format!(
+                "{} Couldn't fetch {} to check for new commits, pushing anyway: {}",
+                "⚠".yellow(),
+                remote,
+                e
+            ));
+            return Ok(false);
+        }
+        let commits_behind = GitRepository::get_commits_behind(remote)?;
+        debug!("Branch is {} commits behind {}", commits_behind, remote);
+        Ok(commits_behind > 0)
+    }
+
+    pub fn prompt_for_pull(remote: &str, default: bool) -> anyhow::Result<bool> {
+        let pull_confirmed_by_user = Self::confirm(
+            format!(
                 "Do you want to pull changes from the remote repository {} before pushing?",
                 remote.green()
-            ))
-            .default(true)
-            .interact_opt()?
-            .unwrap_or(true);
+            ),
+            default,
+        )?;
 
         if pull_confirmed_by_user {
             Ok(true)
         } else {
-            outro("Push cancelled, exiting...");
+            outro("Pull skipped");
             Ok(false)
         }
     }
@@ -0,0 +1,97 @@
+use crate::{commands::config::DiffPrivacy, git::StagedChange};
+
+/// Maps each changed path to `file1`, `file2`, ... in the order the paths
+/// first appear, longest paths first so that no path replaces part of another.
+fn path_aliases(changes: &[StagedChange]) -> Vec<(String, String)> {
+    let mut paths: Vec<&str> = Vec::new();
+    for change in changes {
+        for path in [&change.old_path, &change.new_path] {
+            if !path.is_empty() && !paths.contains(&path.as_str()) {
+                paths.push(path);
+            }
+        }
+    }
+
+    let mut aliases = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| (path.to_string(), format!("file{}", i + 1)))
+        .collect::<Vec<_>>();
+    aliases.sort_by_key(|(path, _)| std::cmp::Reverse(path.len()));
+    aliases
+}
+
+/// Replaces whole paths only, i.e. those that start after a `/` or a space
+/// and end at a space or the end of the line, as in `diff --git a/x b/x`.
+fn replace_paths(text: &str, aliases: &[(String, String)]) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut previous = None;
+    'outer: while let Some(c) = rest.chars().next() {
+        if previous.is_none_or(|p: char| p == '/' || p.is_whitespace()) {
+            for (path, alias) in aliases {
+                if let Some(after) = rest.strip_prefix(path.as_str()) {
+                    if after.chars().next().is_none_or(char::is_whitespace) {
+                        result.push_str(alias);
+                        rest = after;
+                        previous = path.chars().last();
+                        continue 'outer;
+                    }
+                }
+            }
+        }
+        result.push(c);
+        rest = &rest[c.len_utf8()..];
+        previous = Some(c);
+    }
+    result
+}
+
+/// Whether `line` starts the diff of a new file.
+pub fn is_file_header(line: &str) -> bool {
+    line.starts_with("diff --git ") || line.starts_with("Binary file")
+}
+
+/// Removes file paths from the diff lines sent to the model.
+pub fn redact_diff(diff: &[String], changes: &[StagedChange], privacy: DiffPrivacy) -> Vec<String> {
+    match privacy {
+        DiffPrivacy::Full => diff.to_vec(),
+        DiffPrivacy::NoPaths => {
+            let aliases = path_aliases(changes);
+            diff.iter()
+                .map(|line| {
+                    if is_file_header(line) {
+                        replace_paths(line, &aliases)
+                    } else {
+                        line.clone()
+                    }
+                })
+                .collect()
+        }
+        DiffPrivacy::HunksOnly => diff
+            .iter()
+            .filter(|line| !is_file_header(line))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Removes file paths from the staged change summaries. With `hunks-only`
+/// no summaries are kept, so the hunks are always sent instead.
+pub fn redact_changes(changes: &[StagedChange], privacy: DiffPrivacy) -> Vec<StagedChange> {
+    match privacy {
+        DiffPrivacy::Full => changes.to_vec(),
+        DiffPrivacy::NoPaths => {
+            let aliases = path_aliases(changes);
+            changes
+                .iter()
+                .map(|change| StagedChange {
+                    old_path: replace_paths(&change.old_path, &aliases),
+                    new_path: replace_paths(&change.new_path, &aliases),
+                    ..change.clone()
+                })
+                .collect()
+        }
+        DiffPrivacy::HunksOnly => Vec::new(),
+    }
+}
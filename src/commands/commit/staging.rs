@@ -0,0 +1,100 @@
+use log::debug;
+
+use crate::{
+    commands::config::AutocommitConfig,
+    git::GitRepository,
+    utils::{generate_message, Message, MessageRole},
+};
+
+/// A set of pathspecs the model suggests committing together.
+#[derive(Debug, PartialEq, Eq)]
+pub struct StagingGroup {
+    pub description: String,
+    pub pathspecs: Vec<String>,
+}
+
+/// Parses lines of the form `description | pathspec, pathspec` into staging
+/// groups. Lines that don't follow the format are ignored.
+pub fn parse_staging_groups(text: &str) -> Vec<StagingGroup> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim().trim_start_matches(['-', '*']).trim();
+            let (description, pathspecs) = line.rsplit_once('|')?;
+            let pathspecs = pathspecs
+                .split(',')
+                .map(|pathspec| pathspec.trim().trim_matches('`').to_owned())
+                .filter(|pathspec| !pathspec.is_empty())
+                .collect::<Vec<_>>();
+            let description = description.trim();
+            if description.is_empty() || pathspecs.is_empty() {
+                return None;
+            }
+
+            Some(StagingGroup {
+                description: description.to_owned(),
+                pathspecs,
+            })
+        })
+        .collect()
+}
+
+pub async fn suggest_staging_groups(
+    config: &AutocommitConfig,
+    changed_files: &[String],
+) -> anyhow::Result<Vec<StagingGroup>> {
+    let system_message = [
+        "You are a software developer splitting uncommitted changes into small, focused git commits.",
+        "Group the changed files into logical commits. Prefer directory pathspecs (e.g. 'src/git/') when every changed file in a directory belongs to the same group.",
+        "Answer with one group per line in the format: <short description> | <pathspec>, <pathspec>",
+        "Do not add any other text.",
+    ];
+    let prompt = vec![
+        Message::new(MessageRole::System, system_message.join("\n\n")),
+        Message::new(
+            MessageRole::User,
+            format!("Changed files:\n{}", changed_files.join("\n")),
+        ),
+    ];
+
+    let config_data = &config.config_data;
+    let response = generate_message(
+        &prompt,
+        config.get_oai_config()?,
+        &config_data.open_ai_model.get_value_ref().get_inner_value(),
+        &config_data
+            .fallback_models
+            .get_value_ref()
+            .get_inner_value(),
+    )
+    .await?;
+    debug!("Staging suggestion: {}", response);
+
+    Ok(parse_staging_groups(&response))
+}
+
+/// Assigns each of `files` to the first group with a pathspec matching it,
+/// for splitting them into commits. Files no group matches form a last group.
+pub fn assign_to_groups(
+    groups: Vec<StagingGroup>,
+    files: &[String],
+) -> anyhow::Result<Vec<(String, Vec<String>)>> {
+    let mut remaining = files.to_vec();
+    let mut assigned = Vec::new();
+    for group in groups {
+        let matches = GitRepository::match_pathspecs(&group.pathspecs, &remaining)?;
+        let group_files = remaining
+            .iter()
+            .filter(|file| matches.iter().any(|(_, matched)| matched.contains(file)))
+            .cloned()
+            .collect::<Vec<_>>();
+        if group_files.is_empty() {
+            continue;
+        }
+        remaining.retain(|file| !group_files.contains(file));
+        assigned.push((group.description, group_files));
+    }
+    if !remaining.is_empty() {
+        assigned.push((String::from("Other changes"), remaining));
+    }
+    Ok(assigned)
+}
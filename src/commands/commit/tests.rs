@@ -0,0 +1,981 @@
+#[test]
+fn test_sanitize_message_strips_code_fences() {
+    use super::message::sanitize_message;
+
+    assert_eq!(
+        sanitize_message("```\nfix: handle empty diff\n```"),
+        "fix: handle empty diff"
+    );
+    assert_eq!(
+        sanitize_message("```text\nfeat: add login\n\nAdd a login form.\n```"),
+        "feat: add login\n\nAdd a login form."
+    );
+    assert_eq!(sanitize_message("```fix: typo```"), "fix: typo");
+}
+
+#[test]
+fn test_sanitize_message_strips_labels() {
+    use super::message::sanitize_message;
+
+    assert_eq!(
+        sanitize_message("Commit message: Fix crash on startup"),
+        "Fix crash on startup"
+    );
+    assert_eq!(
+        sanitize_message("**Commit message:**\nRefactor config loading"),
+        "Refactor config loading"
+    );
+    assert_eq!(
+        sanitize_message("Suggested commit message:\n```\nAdd retry logic\n```"),
+        "Add retry logic"
+    );
+}
+
+#[test]
+fn test_sanitize_message_strips_quotes() {
+    use super::message::sanitize_message;
+
+    assert_eq!(sanitize_message("\"Update README\""), "Update README");
+    assert_eq!(sanitize_message("'Remove dead code'"), "Remove dead code");
+    assert_eq!(
+        sanitize_message("```\n\"Bump version\"\n```"),
+        "Bump version"
+    );
+}
+
+#[test]
+fn test_sanitize_message_keeps_inner_quotes() {
+    use super::message::sanitize_message;
+
+    for message in [
+        "`foo` renamed to `bar`",
+        "\"a\" vs \"b\"",
+        "'x' and 'y' swapped",
+    ] {
+        assert_eq!(sanitize_message(message), message);
+    }
+}
+
+#[test]
+fn test_sanitize_message_keeps_clean_messages() {
+    use super::message::sanitize_message;
+
+    let message = "fix(parser): handle \"quoted\" keys\n\n- Support `\"` in keys";
+    assert_eq!(sanitize_message(message), message);
+}
+
+#[test]
+fn test_has_body_ignores_trailers() {
+    use super::message::has_body;
+
+    assert!(!has_body("fix: typo"));
+    assert!(!has_body(
+        "fix: typo\n\nSigned-off-by: Jane <jane@example.com>"
+    ));
+    assert!(has_body(
+        "fix: typo\n\nCorrect the spelling in the Readme.\n\nSigned-off-by: Jane <jane@example.com>"
+    ));
+}
+
+#[test]
+fn test_strip_comments() {
+    use super::message::strip_comments;
+
+    assert_eq!(
+        strip_comments("feat: add login\n\nAdd a form.\n# comment\n#another\n", '#'),
+        "feat: add login\n\nAdd a form."
+    );
+    assert_eq!(strip_comments("# only comments\n\n", '#'), "");
+}
+
+#[test]
+fn test_truncate_body() {
+    use super::message::truncate_body;
+
+    let message = "Add parser\n\nFirst line\nSecond line\nThird line\n\nSigned-off-by: Jane <jane@example.com>\n";
+    assert_eq!(
+        truncate_body(message, 1000),
+        "Add parser\n\nFirst line\nSecond line\nThird line\n\nSigned-off-by: Jane <jane@example.com>"
+    );
+    assert_eq!(
+        truncate_body(message, 75),
+        "Add parser\n\nFirst line\n[...]\n\nSigned-off-by: Jane <jane@example.com>"
+    );
+    assert_eq!(
+        truncate_body(message, 10),
+        "Add parser\n\n[...]\n\nSigned-off-by: Jane <jane@example.com>"
+    );
+    assert_eq!(
+        truncate_body("Add parser\n\nA long body", 10),
+        "Add parser\n\n[...]"
+    );
+}
+
+#[test]
+fn test_stream_preview() {
+    use super::message::stream_preview;
+
+    assert_eq!(
+        stream_preview("Add parser\n\n- Parse  tokens"),
+        "Add parser - Parse tokens"
+    );
+    let preview = stream_preview(&format!("Add parser\n\n{}", "x".repeat(100)));
+    assert_eq!(preview, format!("…{}", "x".repeat(60)));
+}
+
+#[test]
+fn test_normalize_message() {
+    use super::message::normalize_message;
+
+    assert_eq!(
+        normalize_message("fix: typo\r\n\r\nFix the typo.\r\n"),
+        "fix: typo\n\nFix the typo.\n"
+    );
+    assert_eq!(
+        normalize_message("fix: typo  \n\nFix the typo.\t\n- One   \n"),
+        "fix: typo\n\nFix the typo.\n- One\n"
+    );
+    assert_eq!(
+        normalize_message("\n\nfix: typo\n\n\n\nFix the typo.\n\n \n\n"),
+        "fix: typo\n\nFix the typo.\n"
+    );
+    assert_eq!(normalize_message("fix: typo"), "fix: typo\n");
+}
+
+#[test]
+fn test_strip_scissors() {
+    use super::message::strip_scissors;
+
+    assert_eq!(
+        strip_scissors(
+            "feat: add login\n# ------------------------ >8 ------------------------\n+added line\n",
+            '#'
+        ),
+        "feat: add login\n"
+    );
+    assert_eq!(
+        strip_scissors(
+            "; ------------------------ >8 ------------------------\n",
+            '#'
+        ),
+        "; ------------------------ >8 ------------------------\n"
+    );
+    assert_eq!(strip_scissors("fix: typo", '#'), "fix: typo");
+}
+
+#[test]
+fn test_parse_staging_groups() {
+    use super::staging::{parse_staging_groups, StagingGroup};
+
+    let groups = parse_staging_groups(
+        "- Update git helpers | src/git/, `Cargo.toml`\nSome chatter\nDocs |\n* Document options | Readme.md",
+    );
+    assert_eq!(
+        groups,
+        vec![
+            StagingGroup {
+                description: "Update git helpers".to_owned(),
+                pathspecs: vec!["src/git/".to_owned(), "Cargo.toml".to_owned()],
+            },
+            StagingGroup {
+                description: "Document options".to_owned(),
+                pathspecs: vec!["Readme.md".to_owned()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_extract_ticket_from_branch() {
+    use super::ticket::{extract_ticket, Ticket};
+
+    assert_eq!(extract_ticket("123-fix-login"), Some(Ticket::Issue(123)));
+    assert_eq!(extract_ticket("fix/gh-42"), Some(Ticket::Issue(42)));
+    assert_eq!(
+        extract_ticket("feature/issue-7_search"),
+        Some(Ticket::Issue(7))
+    );
+    assert_eq!(
+        extract_ticket("feature/ABC-42-search"),
+        Some(Ticket::TrackerKey("ABC-42".to_owned()))
+    );
+    assert_eq!(extract_ticket("main"), None);
+    assert_eq!(extract_ticket("release/1.2"), None);
+    assert_eq!(extract_ticket("v2-migration"), None);
+}
+
+#[test]
+fn test_closing_footer() {
+    use super::ticket::{append_footer, Ticket};
+
+    assert_eq!(
+        Ticket::Issue(12).closing_footer("Fixes"),
+        Some("Fixes #12".to_owned())
+    );
+    assert_eq!(
+        Ticket::TrackerKey("ABC-1".to_owned()).closing_footer("Fixes"),
+        None
+    );
+
+    assert_eq!(
+        append_footer("fix: login", "Closes #12"),
+        "fix: login\n\nCloses #12"
+    );
+    assert_eq!(
+        append_footer(
+            "fix: login\n\nHandle empty passwords.\n\nSigned-off-by: Jane <jane@example.com>",
+            "Closes #12"
+        ),
+        "fix: login\n\nHandle empty passwords.\n\nCloses #12\nSigned-off-by: Jane <jane@example.com>"
+    );
+    assert_eq!(
+        append_footer("fix: login\n\nCloses #12", "Closes #12"),
+        "fix: login\n\nCloses #12"
+    );
+}
+
+#[test]
+fn test_non_imperative_verb() {
+    use super::lint::non_imperative_verb;
+
+    assert_eq!(
+        non_imperative_verb("Added login form"),
+        Some("Added".to_owned())
+    );
+    assert_eq!(
+        non_imperative_verb("fix(auth): fixes token refresh"),
+        Some("fixes".to_owned())
+    );
+    assert_eq!(
+        non_imperative_verb("✨ Updating dependencies"),
+        Some("Updating".to_owned())
+    );
+    assert_eq!(non_imperative_verb("Add login form"), None);
+    assert_eq!(non_imperative_verb("feat: embed fonts"), None);
+    assert_eq!(non_imperative_verb("Speed up diff parsing"), None);
+    assert_eq!(non_imperative_verb("Bring back the retry loop"), None);
+}
+
+#[test]
+fn test_disallowed_verb() {
+    use super::lint::disallowed_verb;
+
+    let allowed = vec!["Add".to_owned(), "Fix".to_owned(), "Remove".to_owned()];
+    assert_eq!(
+        disallowed_verb("Implement login form", &allowed),
+        Some("Implement".to_owned())
+    );
+    assert_eq!(
+        disallowed_verb("feat(auth): tweak token refresh", &allowed),
+        Some("tweak".to_owned())
+    );
+    assert_eq!(disallowed_verb("✨ Add login form", &allowed), None);
+    assert_eq!(disallowed_verb("fix: fix token refresh", &allowed), None);
+    assert_eq!(disallowed_verb("Implement login form", &[]), None);
+}
+
+#[test]
+fn test_lint_message() -> anyhow::Result<()> {
+    use super::lint_message;
+    use crate::commands::config::AutocommitConfig;
+
+    let config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = false
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "https://api.openai.com"
+enforce_imperative = true
+allowed_verbs = ["Add", "Fix"]
+"#,
+    )?;
+
+    assert!(lint_message("feat: add login form\n\nWith a remember me box.", &config).is_empty());
+    assert!(lint_message("Merge branch 'main' into feature", &config).is_empty());
+    assert_eq!(lint_message("", &config), vec!["The message is empty"]);
+    assert_eq!(
+        lint_message(&format!("Add {}\nBody", "x".repeat(70)), &config),
+        vec![
+            "The subject is 74 characters long, more than 72",
+            "The subject must be followed by a blank line",
+        ]
+    );
+    assert_eq!(
+        lint_message("Added login form", &config),
+        vec![
+            "The subject starts with 'Added', which is not in the imperative mood",
+            "The subject starts with 'Added', which is not one of: Add, Fix",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_lint_conventional_style() -> anyhow::Result<()> {
+    use super::lint_message;
+    use crate::commands::config::AutocommitConfig;
+
+    let mut config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = true
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "https://api.openai.com"
+"#,
+    )?;
+    assert_eq!(
+        config.config_data.commit_style.get_value_ref().to_string(),
+        "free"
+    );
+    assert!(lint_message("Add login form", &config).is_empty());
+
+    config.config_data.commit_style.update("conventional")?;
+    assert!(lint_message("feat(auth): add login form", &config).is_empty());
+    assert_eq!(
+        lint_message("Add login form", &config),
+        vec!["The subject must look like 'type(scope): subject' with one of the types: feat, fix, docs, style, refactor, perf, test, chore, build, ci"]
+    );
+    assert!(config.config_data.commit_style.update("angular").is_err());
+
+    let system_message = super::chat_context::ChatContext::get_initial_context(&config, false)
+        .get_messages()[0]
+        .content
+        .clone();
+    assert!(system_message.contains("Conventional Commits"));
+    assert!(!system_message.contains("GitMoji"));
+    Ok(())
+}
+
+#[test]
+fn test_response_language() -> anyhow::Result<()> {
+    use super::chat_context::ChatContext;
+    use crate::commands::config::AutocommitConfig;
+
+    let mut config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = false
+language = "english"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "https://api.openai.com"
+"#,
+    )?;
+    let system_message = |config: &AutocommitConfig| {
+        ChatContext::get_initial_context(config, false).get_messages()[0]
+            .content
+            .clone()
+    };
+
+    assert!(system_message(&config).contains("Use English to answer."));
+    config.config_data.response_language.update("German")?;
+    let message = system_message(&config);
+    assert!(message.contains("Use German to answer."));
+    assert!(message.starts_with("You are a software developer"));
+    Ok(())
+}
+
+#[test]
+fn test_add_context_files() {
+    use super::chat_context::ChatContext;
+    use crate::commands::config::AutocommitConfig;
+
+    let config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = false
+language = "english"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "https://api.openai.com"
+few_shot = false
+"#,
+    )
+    .unwrap();
+    let mut chat_context = ChatContext::get_initial_context(&config, false);
+    let long_file = (1..=300)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    chat_context.add_context_files(&[
+        (
+            String::from("src/lib.rs"),
+            String::from("pub trait Parser {}\n"),
+        ),
+        (String::from("src/long.rs"), long_file),
+    ]);
+
+    let content = &chat_context.get_messages().last().unwrap().content;
+    assert!(content.contains("do not describe them"));
+    assert!(content.contains("--- src/lib.rs ---\npub trait Parser {}"));
+    assert!(content.contains("line 200\n... (file truncated at 200 lines) ..."));
+    assert!(!content.contains("line 201"));
+}
+
+#[test]
+fn test_truncate_diff() {
+    use super::chat_context::truncate_diff;
+
+    let diff = "diff --git a/a b/a\n+one\n+two\n+three\n";
+    assert_eq!(truncate_diff(diff, 4), diff);
+    assert_eq!(
+        truncate_diff(diff, 2),
+        "diff --git a/a b/a\n+one\n... (diff truncated at 2 lines) ...\n"
+    );
+}
+
+#[tokio::test]
+async fn test_chunk_diffs() -> anyhow::Result<()> {
+    use super::{
+        chat_context::{group_diff_by_file, truncate_diff},
+        map_reduce::chunk_diffs,
+    };
+    use crate::git::{tests::TestRepo, DiffBase, DiffStyle, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    for file in ["a.txt", "b.txt", "c.txt"] {
+        repo.write_file(file, "")?;
+    }
+    repo.git(&["add", "."]).await?;
+    repo.git(&["commit", "-m", "Add files"]).await?;
+
+    repo.write_file("a.txt", "one\n")?;
+    repo.write_file("b.txt", "one\ntwo\n")?;
+    repo.write_file(
+        "c.txt",
+        (1..=20)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>(),
+    )?;
+    repo.git(&["add", "."]).await?;
+    let files = GitRepository::get_staged_files()?;
+    let diff = GitRepository::get_staged_file_diffs(&files, DiffStyle::Unified, DiffBase::Head)?;
+    let by_file = group_diff_by_file(&diff);
+    assert_eq!(
+        by_file
+            .iter()
+            .map(|file| file.lines().count())
+            .collect::<Vec<_>>(),
+        vec![6, 7, 25]
+    );
+
+    assert_eq!(
+        chunk_diffs(&diff, 14),
+        vec![
+            format!("{}{}", by_file[0], by_file[1]),
+            truncate_diff(&by_file[2], 14),
+        ]
+    );
+    assert_eq!(
+        chunk_diffs(&diff, 10),
+        vec![
+            by_file[0].clone(),
+            by_file[1].clone(),
+            truncate_diff(&by_file[2], 10),
+        ]
+    );
+    assert!(truncate_diff(&by_file[2], 10).ends_with("... (diff truncated at 10 lines) ...\n"));
+    assert_eq!(chunk_diffs(&diff, 100), vec![diff.concat()]);
+
+    Ok(())
+}
+
+#[test]
+fn test_candidate_temperatures() {
+    use super::chat_context::candidate_temperatures;
+
+    assert_eq!(candidate_temperatures(3, false), vec![None, None, None]);
+    assert_eq!(candidate_temperatures(1, true), vec![None]);
+
+    let temperatures = candidate_temperatures(5, true)
+        .into_iter()
+        .map(|temperature| (temperature.unwrap() * 10.0).round() / 10.0)
+        .collect::<Vec<_>>();
+    assert_eq!(temperatures, vec![0.2, 0.4, 0.6, 0.8, 1.0]);
+}
+
+#[test]
+fn test_redact_diff() {
+    use super::privacy::{redact_changes, redact_diff};
+    use crate::{commands::config::DiffPrivacy, git::StagedChange};
+
+    let change = |old_path: &str, new_path: &str| StagedChange {
+        status: git2::Delta::Modified,
+        old_path: old_path.to_owned(),
+        new_path: new_path.to_owned(),
+        content_changed: true,
+        is_binary: false,
+        old_size: 0,
+        new_size: 0,
+        old_mode: 0o100644,
+        new_mode: 0o100644,
+    };
+    let changes = vec![change("a", "a"), change("src/lib/a", "src/lib/a")];
+    let diff = vec![
+        "diff --git a/a b/a\nindex 1..2 100644\n--- a/a\n+++ b/a\n".to_owned(),
+        "@@ -1 +1 @@\n".to_owned(),
+        "-see src/lib/a\n".to_owned(),
+        "+see a\n".to_owned(),
+        "diff --git a/src/lib/a b/src/lib/a\n".to_owned(),
+        "Binary files a/src/lib/a and b/src/lib/a differ\n".to_owned(),
+    ];
+
+    assert_eq!(redact_diff(&diff, &changes, DiffPrivacy::Full), diff);
+    assert_eq!(
+        redact_diff(&diff, &changes, DiffPrivacy::NoPaths),
+        vec![
+            "diff --git a/file1 b/file1\nindex 1..2 100644\n--- a/file1\n+++ b/file1\n",
+            "@@ -1 +1 @@\n",
+            "-see src/lib/a\n",
+            "+see a\n",
+            "diff --git a/file2 b/file2\n",
+            "Binary files a/file2 and b/file2 differ\n",
+        ]
+    );
+    assert_eq!(
+        redact_diff(&diff, &changes, DiffPrivacy::HunksOnly),
+        vec!["@@ -1 +1 @@\n", "-see src/lib/a\n", "+see a\n"]
+    );
+
+    let redacted = redact_changes(&changes, DiffPrivacy::NoPaths);
+    assert_eq!(redacted[1].new_path, "file2");
+    assert!(redact_changes(&changes, DiffPrivacy::HunksOnly).is_empty());
+}
+
+#[tokio::test]
+async fn test_filter_message() -> anyhow::Result<()> {
+    use super::filter::filter_message;
+
+    assert_eq!(
+        filter_message("tr a-z A-Z", "fix: typo\n").await?,
+        "FIX: TYPO"
+    );
+    assert!(filter_message("cat >/dev/null; exit 1", "fix: typo")
+        .await
+        .is_err());
+    assert!(filter_message("cat >/dev/null", "fix: typo").await.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_file_picker_selection() {
+    use super::file_picker::FilePicker;
+    use crate::git::WorktreeChange;
+
+    let changes = ["a.rs", "b.rs", "c.rs"]
+        .iter()
+        .map(|path| WorktreeChange {
+            path: path.to_string(),
+            additions: 1,
+            deletions: 0,
+            patch: String::new(),
+        })
+        .collect::<Vec<_>>();
+    let mut picker = FilePicker::new(&changes);
+    assert!(picker.selected_files().is_empty());
+
+    picker.toggle();
+    picker.move_cursor(5);
+    picker.toggle();
+    assert_eq!(picker.selected_files(), vec!["a.rs", "c.rs"]);
+
+    picker.move_cursor(-1);
+    picker.toggle();
+    picker.toggle();
+    picker.toggle_all();
+    assert_eq!(picker.selected_files(), vec!["a.rs", "b.rs", "c.rs"]);
+    picker.toggle_all();
+    assert!(picker.selected_files().is_empty());
+}
+
+#[tokio::test]
+async fn test_assume_yes_takes_defaults() -> anyhow::Result<()> {
+    use super::CommitCommand;
+    use crate::utils::tests::AssumeYes;
+
+    let _assume_yes = AssumeYes::on();
+    assert!(CommitCommand::confirm("Push?", true)?);
+    assert!(!CommitCommand::confirm("Pull?", false)?);
+    // A non-interactive run stops after one commit
+    assert!(!CommitCommand::prompt_to_continue(true).await?);
+    let messages = vec!["feat: add login".to_string(), "Add login".to_string()];
+    assert_eq!(
+        CommitCommand::prompt_for_selected_message(&messages).await?,
+        "feat: add login"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_message_option_skips_the_api() {
+    use super::CommitCommand;
+    use structopt::StructOpt;
+
+    let commit = CommitCommand::from_iter(["commit", "-m", "fix: typo", "-n", "3"]);
+    assert_eq!(commit.message.as_deref(), Some("fix: typo"));
+    assert!(!commit.uses_api());
+
+    let commit = CommitCommand::from_iter(["commit"]);
+    assert!(commit.uses_api());
+    assert_eq!(commit.message_count(), 1);
+}
+
+#[test]
+fn test_print_only_generates_one_message() {
+    use super::CommitCommand;
+    use structopt::StructOpt;
+
+    let commit = CommitCommand::from_iter(["commit", "--print-only", "-n", "3"]);
+    assert!(commit.print_only());
+    assert_eq!(commit.message_count(), 1);
+
+    let commit = CommitCommand::from_iter(["commit", "-n", "3"]);
+    assert!(!commit.print_only());
+    assert_eq!(commit.message_count(), 3);
+}
+
+#[test]
+fn test_to_conventional() {
+    use super::lint::{is_conventional, to_conventional};
+
+    assert!(is_conventional("feat(api): add login\n\nBody"));
+    assert!(is_conventional("fix!: drop the old flag"));
+    assert!(!is_conventional("Add login"));
+    assert!(!is_conventional("feature: add login"));
+
+    assert_eq!(
+        to_conventional("Fix(Parser) : handle empty input").as_deref(),
+        Some("fix(Parser): handle empty input")
+    );
+    assert_eq!(
+        to_conventional("🐛 fix:handle empty input\n\nBody").as_deref(),
+        Some("fix: handle empty input\n\nBody")
+    );
+    assert_eq!(
+        to_conventional("FEAT (api)!: add login").as_deref(),
+        Some("feat(api)!: add login")
+    );
+    assert_eq!(to_conventional("Add login"), None);
+    assert_eq!(to_conventional("feature: add login"), None);
+}
+
+#[test]
+fn test_subject_formatting() {
+    use super::message::{exceeds_subject_limit, separate_subject};
+
+    assert_eq!(
+        separate_subject("Add login form\nWith a remember me box."),
+        "Add login form\n\nWith a remember me box."
+    );
+    assert_eq!(
+        separate_subject("Add login form\n\nWith a remember me box."),
+        "Add login form\n\nWith a remember me box."
+    );
+    assert_eq!(separate_subject("Add login form"), "Add login form");
+
+    let long_subject = format!("Add {}", "x".repeat(70));
+    assert!(exceeds_subject_limit(&long_subject, Some(72)));
+    assert!(!exceeds_subject_limit(&long_subject, Some(80)));
+    assert!(!exceeds_subject_limit(&long_subject, None));
+    assert!(!exceeds_subject_limit("Add login form\n\nBody", Some(14)));
+}
+
+#[test]
+fn test_co_author_trailer() -> anyhow::Result<()> {
+    use super::ticket::{append_footer, co_author_trailer};
+
+    assert_eq!(
+        co_author_trailer(" Jane Doe <jane@example.com> ")?,
+        "Co-authored-by: Jane Doe <jane@example.com>"
+    );
+    assert!(co_author_trailer("Jane Doe").is_err());
+    assert!(co_author_trailer("<jane@example.com>").is_err());
+    assert!(co_author_trailer("Jane Doe <jane>").is_err());
+
+    let message = append_footer(
+        "Add login form\n\nSigned-off-by: John Doe <john@example.com>",
+        &co_author_trailer("Jane Doe <jane@example.com>")?,
+    );
+    assert_eq!(
+        message,
+        "Add login form\n\nCo-authored-by: Jane Doe <jane@example.com>\nSigned-off-by: John Doe <john@example.com>"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_diff_style_falls_back_to_diff_context_lines() -> anyhow::Result<()> {
+    use super::CommitCommand;
+    use crate::{commands::config::AutocommitConfig, git::DiffStyle};
+    use structopt::StructOpt;
+
+    let mut config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = false
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "https://api.openai.com"
+"#,
+    )?;
+    let commit = CommitCommand::from_iter(["commit"]);
+    assert_eq!(commit.diff_style(&config), DiffStyle::Unified);
+
+    config.config_data.diff_context_lines.update("0")?;
+    assert_eq!(commit.diff_style(&config), DiffStyle::Lines(0));
+    assert_eq!(commit.diff_style(&config).context_lines(), 0);
+
+    let commit = CommitCommand::from_iter(["commit", "--diff-style", "full"]);
+    assert_eq!(commit.diff_style(&config), DiffStyle::Full);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fit_diffs_to_tokens() -> anyhow::Result<()> {
+    use super::chat_context::{estimate_tokens, fit_diffs_to_tokens, group_diff_by_file};
+    use crate::git::{tests::TestRepo, DiffBase, DiffStyle, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("large.txt", "")?;
+    repo.write_file("small.txt", "")?;
+    repo.git(&["add", "."]).await?;
+    repo.git(&["commit", "-m", "Add files"]).await?;
+
+    repo.write_file(
+        "large.txt",
+        (1..=3000)
+            .map(|i| format!("line {}\n", i))
+            .collect::<String>(),
+    )?;
+    repo.write_file("small.txt", "one\n")?;
+    repo.git(&["add", "."]).await?;
+    let files = GitRepository::get_staged_files()?;
+    let diff = GitRepository::get_staged_file_diffs(&files, DiffStyle::Unified, DiffBase::Head)?;
+    assert!(diff.len() > 3000);
+
+    let by_file = group_diff_by_file(&diff);
+    assert_eq!(by_file.len(), 2);
+    assert!(by_file[0].starts_with("diff --git a/large.txt b/large.txt\n"));
+    assert!(by_file[1].starts_with("diff --git a/small.txt b/small.txt\n"));
+    assert_eq!(by_file.concat(), diff.concat());
+
+    assert_eq!(estimate_tokens("abcde"), 2);
+    assert_eq!(fit_diffs_to_tokens(&diff, 100_000), diff.concat());
+
+    let fitted = fit_diffs_to_tokens(&diff, 1000);
+    assert!(estimate_tokens(&fitted) <= 1000 + 20);
+    assert!(fitted.starts_with("diff --git a/large.txt b/large.txt\n"));
+    assert!(fitted.contains("+line 1\n"));
+    assert!(fitted.contains("... (file diff truncated) ...\n"));
+    assert!(!fitted.contains("+line 3000\n"));
+    assert!(!fitted.contains("a/small.txt"));
+    assert!(fitted.ends_with("[diff truncated: 1 files omitted]\n"));
+
+    let fitted = fit_diffs_to_tokens(&diff, 3000);
+    assert!(fitted.contains("diff --git a/small.txt b/small.txt\n"));
+    assert!(fitted.ends_with("[diff truncated]\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_group_diff_by_area() {
+    use super::areas::group_diff_by_area;
+
+    let diff = [
+        "diff --git a/src/main.rs b/src/main.rs\nindex 1..2 100644\n",
+        "+fn main() {}\n",
+        "diff --git a/docs/guide.md b/docs/guide.md\nindex 1..2 100644\n",
+        "+# Guide\n",
+        "diff --git a/src/lib.rs b/src/lib.rs\nindex 1..2 100644\n",
+        "+pub mod git;\n",
+        "Binary file logo.png changed (29→33 bytes)\n",
+    ]
+    .map(String::from);
+
+    let areas = group_diff_by_area(&diff);
+    let names = areas
+        .iter()
+        .map(|(area, _)| area.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["src", "docs", "(root)"]);
+    assert_eq!(
+        areas[0].1,
+        vec![
+            diff[0].clone(),
+            diff[1].clone(),
+            diff[4].clone(),
+            diff[5].clone()
+        ]
+    );
+    assert_eq!(areas[2].1, vec![diff[6].clone()]);
+}
+
+#[test]
+fn test_combine_sections() {
+    use super::areas::combine_sections;
+    use super::chat_context::Candidate;
+
+    let candidate = |message: &str| Candidate {
+        message: message.to_owned(),
+        temperature: None,
+    };
+    let combined = combine_sections(vec![
+        vec![candidate("Add parser\n"), candidate("Parse input")],
+        vec![candidate("Document the parser")],
+    ]);
+    assert_eq!(combined.len(), 1);
+    assert_eq!(combined[0].message, "Add parser\n\nDocument the parser");
+}
+
+#[test]
+fn test_split_groups() -> anyhow::Result<()> {
+    use super::areas::group_files_by_area;
+    use super::staging::{assign_to_groups, StagingGroup};
+
+    let files = vec![
+        "Readme.md".to_owned(),
+        "src/git/mod.rs".to_owned(),
+        "docs/guide.md".to_owned(),
+        "src/main.rs".to_owned(),
+    ];
+    assert_eq!(
+        group_files_by_area(&files),
+        vec![
+            ("(root)".to_owned(), vec!["Readme.md".to_owned()]),
+            (
+                "src".to_owned(),
+                vec!["src/git/mod.rs".to_owned(), "src/main.rs".to_owned()]
+            ),
+            ("docs".to_owned(), vec!["docs/guide.md".to_owned()]),
+        ]
+    );
+
+    let suggestions = vec![
+        StagingGroup {
+            description: "Update the docs".to_owned(),
+            pathspecs: vec!["*.md".to_owned()],
+        },
+        StagingGroup {
+            description: "Refactor git".to_owned(),
+            pathspecs: vec!["src/git".to_owned(), "docs/guide.md".to_owned()],
+        },
+        StagingGroup {
+            description: "Nothing".to_owned(),
+            pathspecs: vec!["tests/".to_owned()],
+        },
+    ];
+    assert_eq!(
+        assign_to_groups(suggestions, &files)?,
+        vec![
+            (
+                "Update the docs".to_owned(),
+                vec!["Readme.md".to_owned(), "docs/guide.md".to_owned()]
+            ),
+            ("Refactor git".to_owned(), vec!["src/git/mod.rs".to_owned()]),
+            ("Other changes".to_owned(), vec!["src/main.rs".to_owned()]),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_should_pull_pushes_when_fetch_fails() -> anyhow::Result<()> {
+    use super::CommitCommand;
+    use crate::commands::config::AutocommitConfig;
+    use crate::git::tests::TestRepo;
+
+    let config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = false
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "https://api.openai.com"
+pull_before_push = false
+"#,
+    )?;
+    let _repo = TestRepo::new().await?;
+
+    assert!(!CommitCommand::should_pull(&config, "missing-remote").await?);
+    Ok(())
+}
+
+#[test]
+fn test_limit_body_bullets() {
+    use super::message::limit_body_bullets;
+
+    let message = "feat: add login\n\n- Add the form\n- Add the route";
+    assert_eq!(limit_body_bullets(message, 2), message);
+    assert_eq!(limit_body_bullets(message, 3), message);
+    assert_eq!(
+        limit_body_bullets(message, 1),
+        "feat: add login\n\n- Add the form"
+    );
+
+    assert_eq!(
+        limit_body_bullets(
+            "fix: parse dates\n\n1. Accept ISO dates\n2) Reject empty input\n3. Log failures",
+            2
+        ),
+        "fix: parse dates\n\n1. Accept ISO dates\n2) Reject empty input"
+    );
+
+    // A wrapped bullet is dropped with all of its lines, while the text after it stays
+    assert_eq!(
+        limit_body_bullets(
+            "refactor: split config\n\n- Move the keys\n  into their own module\n- Move the service\n  next to them\n\nNo behavior changes.",
+            1
+        ),
+        "refactor: split config\n\n- Move the keys\n  into their own module\n\nNo behavior changes."
+    );
+}
+
+#[tokio::test]
+async fn test_stage_given_files() -> anyhow::Result<()> {
+    use super::CommitCommand;
+    use crate::git::{tests::TestRepo, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("a.txt", "a\n")?;
+    repo.write_file("b.txt", "b\n")?;
+    repo.git(&["add", "a.txt", "b.txt"]).await?;
+    repo.git(&["commit", "-m", "Add files"]).await?;
+    repo.write_file("a.txt", "changed\n")?;
+    repo.write_file("c.txt", "new\n")?;
+    let changed_files = GitRepository::get_changed_files()?;
+
+    let error = CommitCommand::stage_given_files(&["b.txt".to_owned()], &changed_files)
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "File 'b.txt' has no changes to commit");
+    let error = CommitCommand::stage_given_files(&["d.txt".to_owned()], &changed_files)
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "File 'd.txt' does not exist");
+
+    // The commit must contain only the given files
+    repo.git(&["add", "c.txt"]).await?;
+    let error = CommitCommand::stage_given_files(&["a.txt".to_owned()], &changed_files)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("Other files are already staged"));
+    assert!(error.to_string().contains("c.txt"));
+
+    repo.git(&["reset", "-q", "c.txt"]).await?;
+    CommitCommand::stage_given_files(&["a.txt".to_owned()], &changed_files).await?;
+    assert_eq!(GitRepository::get_staged_files()?, vec!["a.txt"]);
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anyhow::anyhow;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref TRACKER_KEY: Regex = Regex::new(r"\b([A-Z][A-Z0-9]+-\d+)\b").unwrap();
+    static ref ISSUE_NUMBER: Regex =
+        Regex::new(r"(?i)(?:^|[/_-])(?:gh-|issue-|issues-|#)?(\d+)(?:[/_-]|$)").unwrap();
+    static ref CO_AUTHOR: Regex =
+        Regex::new(r"^([^<>]*[^<>\s])\s*<([^<>\s@]+@[^<>\s@]+)>$").unwrap();
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Ticket {
+    /// An issue number on the hosting platform, e.g. `123` in `fix/123-login`.
+    Issue(u64),
+    /// A key from an external tracker, e.g. `ABC-42`, which closing keywords
+    /// can't resolve.
+    TrackerKey(String),
+}
+
+impl Ticket {
+    pub fn closing_footer(&self, keyword: &str) -> Option<String> {
+        match self {
+            Ticket::Issue(number) => Some(format!("{} #{}", keyword, number)),
+            Ticket::TrackerKey(_) => None,
+        }
+    }
+}
+
+pub fn extract_ticket(branch: &str) -> Option<Ticket> {
+    if let Some(captures) = TRACKER_KEY.captures(branch) {
+        return Some(Ticket::TrackerKey(captures[1].to_owned()));
+    }
+
+    ISSUE_NUMBER
+        .captures(branch)
+        .and_then(|captures| captures[1].parse().ok())
+        .map(Ticket::Issue)
+}
+
+fn is_trailer_line(line: &str) -> bool {
+    line.split_once(": ")
+        .is_some_and(|(key, _)| !key.is_empty() && !key.contains(' '))
+}
+
+/// Adds `footer` to the trailer block at the end of the message, keeping it
+/// above a `Signed-off-by` line. Messages that already contain the footer
+/// are returned unchanged.
+pub fn append_footer(message: &str, footer: &str) -> String {
+    let message = message.trim_end();
+    if message.lines().any(|line| line.trim() == footer) {
+        return message.to_owned();
+    }
+
+    let paragraphs = message.split("\n\n").collect::<Vec<_>>();
+    match paragraphs.split_last() {
+        Some((trailers, rest)) if !rest.is_empty() && trailers.lines().all(is_trailer_line) => {
+            format!("{}\n\n{}\n{}", rest.join("\n\n"), footer, trailers)
+        }
+        _ => format!("{}\n\n{}", message, footer),
+    }
+}
+
+/// Turns a `Name <email>` entry into a `Co-authored-by` trailer.
+pub fn co_author_trailer(entry: &str) -> anyhow::Result<String> {
+    let captures = CO_AUTHOR
+        .captures(entry.trim())
+        .ok_or_else(|| anyhow!("Invalid co-author '{}', use the form 'Name <email>'", entry))?;
+    Ok(format!(
+        "Co-authored-by: {} <{}>",
+        &captures[1], &captures[2]
+    ))
+}
@@ -1,6 +1,7 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 use anyhow::{anyhow, Context};
+use log::warn;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use tokio::{
@@ -8,15 +9,49 @@ use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
 };
 
-use crate::{git::GitRepository, i18n::language::Language};
+use crate::{
+    git::GitRepository,
+    i18n::language::Language,
+    utils::{AzureDeployment, ChatBackend, OAIConfig},
+};
 
 use super::{
     config_data::ConfigData,
-    config_keys::{ConfigItem, ConfigKey, DefaultLanguage, OptionString},
+    config_keys::{
+        ApiProvider, AuthorEmail, AuthorName, CloseKeyword, CommitStyle, ConfigItem, ConfigKey,
+        DefaultLanguage, DiffPrivacy, OptionNumber, OptionString, StringList,
+    },
 };
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Large enough for any reasonable message, small enough to catch runaway
+/// descriptions before a git host truncates them.
+pub(super) const DEFAULT_MAX_MESSAGE_BYTES: usize = 8 * 1024;
+
+/// The subject length most git tools display without cutting it off.
+pub(super) const DEFAULT_MAX_SUBJECT_LENGTH: usize = 72;
+
+/// The Azure OpenAI API version used when `api_version` isn't set.
+const DEFAULT_AZURE_API_VERSION: &str = "2024-02-01";
+
+/// Low enough for focused messages while still varying between runs.
+pub(super) const DEFAULT_TEMPERATURE: f32 = 0.5;
+pub(super) const DEFAULT_TOP_P: f32 = 0.1;
+
+/// How long to wait for the API before giving up.
+pub(super) const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Leaves room for the prompt and the answer within a 16k token context.
+pub(super) const DEFAULT_MAX_DIFF_TOKENS: usize = 12_000;
+
+/// Lock files are generated and rarely say anything about a change.
+pub(super) const DEFAULT_DIFF_EXCLUDE_GLOBS: &[&str] = &["*.lock"];
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AutocommitConfig {
+    /// The keys the user set, which a remote config doesn't override. Config
+    /// files written before they were tracked don't list them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    set_keys: Option<Vec<String>>,
     #[serde(rename = "config")]
     pub config_data: ConfigData,
 }
@@ -25,6 +60,10 @@ impl AutocommitConfig {
     fn new() -> anyhow::Result<Self> {
         let name = GitRepository::get_git_user_name()?;
         let email = GitRepository::get_git_user_email()?;
+        Ok(Self::with_author(name.parse()?, email.parse()?))
+    }
+
+    fn with_author(name: AuthorName, email: AuthorEmail) -> Self {
         let config_data = ConfigData {
             description_enabled: ConfigItem::new(false),
             emoji_enabled: ConfigItem::new(false),
@@ -34,29 +73,82 @@ impl AutocommitConfig {
             open_ai_api_key: ConfigItem::new(OptionString::default()),
             api_host: ConfigItem::new(String::from("https://api.openai.com")),
             open_ai_model: ConfigItem::new(OptionString::default()),
+            max_body_bullets: ConfigItem::new(OptionNumber::default()),
+            fallback_models: ConfigItem::new(StringList::default()),
+            stop_sequences: ConfigItem::new(StringList::default()),
+            pull_before_push: ConfigItem::new(false),
+            few_shot: ConfigItem::new(true),
+            confirm_show_status: ConfigItem::new(false),
+            confirm_stage_all: ConfigItem::new(true),
+            confirm_push: ConfigItem::new(true),
+            confirm_pull: ConfigItem::new(true),
+            confirm_continue: ConfigItem::new(false),
+            review_body: ConfigItem::new(false),
+            close_keyword: ConfigItem::new(CloseKeyword::default()),
+            auto_close_issues: ConfigItem::new(false),
+            enforce_imperative: ConfigItem::new(false),
+            max_diff_lines: ConfigItem::new(OptionNumber::default()),
+            vary_temperature: ConfigItem::new(false),
+            remote_config_url: ConfigItem::new(OptionString::default()),
+            diff_privacy: ConfigItem::new(DiffPrivacy::default()),
+            message_filter_command: ConfigItem::new(OptionString::default()),
+            allowed_verbs: ConfigItem::new(StringList::default()),
+            notify: ConfigItem::new(false),
+            committer_name: ConfigItem::new(OptionString::default()),
+            committer_email: ConfigItem::new(OptionString::default()),
+            response_language: ConfigItem::new(OptionString::default()),
+            max_message_bytes: ConfigItem::new(OptionNumber::new(DEFAULT_MAX_MESSAGE_BYTES)),
+            confirm_before_send: ConfigItem::new(false),
+            request_timeout_secs: ConfigItem::new(OptionNumber::new(DEFAULT_REQUEST_TIMEOUT_SECS)),
+            api_provider: ConfigItem::new(ApiProvider::default()),
+            deployment: ConfigItem::new(OptionString::default()),
+            api_version: ConfigItem::new(OptionString::default()),
+            temperature: ConfigItem::new(OptionNumber::new(DEFAULT_TEMPERATURE)),
+            top_p: ConfigItem::new(OptionNumber::new(DEFAULT_TOP_P)),
+            max_tokens: ConfigItem::new(OptionNumber::default()),
+            commit_style: ConfigItem::new(CommitStyle::default()),
+            max_subject_length: ConfigItem::new(OptionNumber::new(DEFAULT_MAX_SUBJECT_LENGTH)),
+            co_authors: ConfigItem::new(StringList::default()),
+            sign_commits: ConfigItem::new(false),
+            signing_key: ConfigItem::new(OptionString::default()),
+            diff_exclude_globs: ConfigItem::new(StringList::new(DEFAULT_DIFF_EXCLUDE_GLOBS)),
+            diff_context_lines: ConfigItem::new(OptionNumber::default()),
+            max_diff_tokens: ConfigItem::new(OptionNumber::new(DEFAULT_MAX_DIFF_TOKENS)),
+            cache_enabled: ConfigItem::new(true),
+            proxy_url: ConfigItem::new(OptionString::default()),
         };
-        Ok(Self { config_data })
+        Self {
+            set_keys: Some(Vec::new()),
+            config_data,
+        }
     }
 
     fn update_config_from_env(config: &mut AutocommitConfig) -> anyhow::Result<()> {
-        let env_vars = ConfigKey::iter()
-            .map(|key| {
-                (
-                    format!("AUTOCOMMIT_{}", key.to_string().to_uppercase()),
-                    key,
-                )
-            })
-            .collect::<Vec<_>>();
-
-        for (var, key) in env_vars.iter() {
-            if let Ok(value) = std::env::var(var) {
-                config.update_config(key, &value)?;
+        for key in ConfigKey::iter() {
+            if let Some(value) = Self::env_value(&key) {
+                config.update_config(&key, &value)?;
             }
         }
 
         Ok(())
     }
 
+    /// The value the environment sets for `key`, if any.
+    fn env_value(key: &ConfigKey) -> Option<String> {
+        std::env::var(format!("AUTOCOMMIT_{}", key.to_string().to_uppercase()))
+            .ok()
+            .or_else(|| Self::standard_env_value(key))
+    }
+
+    fn standard_env_value(key: &ConfigKey) -> Option<String> {
+        let vars: &[&str] = match key {
+            ConfigKey::OpenAiApiKey => &["OPENAI_API_KEY"],
+            ConfigKey::ApiHost => &["OPENAI_API_BASE", "OPENAI_BASE_URL"],
+            _ => &[],
+        };
+        vars.iter().find_map(|var| std::env::var(var).ok())
+    }
+
     async fn from_file(path: &PathBuf) -> anyhow::Result<AutocommitConfig> {
         let mut file = File::open(path)
             .await
@@ -85,6 +177,7 @@ impl AutocommitConfig {
         let mut file = OpenOptions::new()
             .write(true)
             .create(true)
+            .truncate(true)
             .open(path)
             .await
             .with_context(|| format!("Failed to create config file: {}", path.display()))?;
@@ -122,9 +215,116 @@ impl AutocommitConfig {
         }
     }
 
+    /// Sets `key` to `value`, leaving the config unchanged if the result is
+    /// invalid.
     pub fn update_config(&mut self, key: &ConfigKey, value: &str) -> anyhow::Result<()> {
-        self.config_data.update_config(key, value)?;
-        self.config_data.validate()?;
+        let mut config_data = self.config_data.clone();
+        config_data.update_config(key, value)?;
+        config_data.validate()?;
+        self.config_data = config_data;
+        Ok(())
+    }
+
+    /// Sets `key` to `value` as the user's own choice, which a remote config
+    /// doesn't override even when it's the default.
+    pub fn set_config(&mut self, key: &ConfigKey, value: &str) -> anyhow::Result<()> {
+        let mut set_keys = match self.set_keys.take() {
+            Some(set_keys) => set_keys,
+            None => self.changed_keys()?,
+        };
+        let result = self.update_config(key, value);
+        if result.is_ok() && !set_keys.contains(&key.to_string()) {
+            set_keys.push(key.to_string());
+        }
+        self.set_keys = Some(set_keys);
+        result
+    }
+
+    /// The keys whose values differ from the defaults, taken as the ones the
+    /// user set in config files written before they were tracked.
+    fn changed_keys(&self) -> anyhow::Result<Vec<String>> {
+        let defaults = Self::with_author(
+            self.config_data.name.get_value().parse()?,
+            self.config_data.email.get_value().parse()?,
+        );
+        Ok(ConfigKey::iter()
+            .filter(|key| self.get_config_value(key) != defaults.get_config_value(key))
+            .map(|key| key.to_string())
+            .collect())
+    }
+
+    /// Whether the user set `key`, in the config file or the environment.
+    fn is_set_locally(&self, key: &ConfigKey) -> anyhow::Result<bool> {
+        if Self::env_value(key).is_some() {
+            return Ok(true);
+        }
+        Ok(match &self.set_keys {
+            Some(set_keys) => set_keys.contains(&key.to_string()),
+            None => self.changed_keys()?.contains(&key.to_string()),
+        })
+    }
+
+    /// Applies the `[config]` table of a remote config file in place of the
+    /// built-in defaults. Values set locally keep precedence, and the author
+    /// and committer identities and the keys only the user may set are never
+    /// taken from the remote config.
+    pub fn merge_remote(&mut self, contents: &str) -> anyhow::Result<()> {
+        let remote: toml::Table =
+            toml::from_str(contents).context("Failed to parse remote config")?;
+        let Some(toml::Value::Table(remote)) = remote.get("config") else {
+            return Err(anyhow!("Remote config has no [config] table"));
+        };
+
+        for (key, value) in remote {
+            let Ok(config_key) = ConfigKey::from_str(key) else {
+                warn!("Ignoring unknown remote config key: {}", key);
+                continue;
+            };
+            if config_key.is_user_only() {
+                warn!(
+                    "Ignoring remote config key {}: only your own config may set it",
+                    key
+                );
+                continue;
+            }
+            if matches!(
+                config_key,
+                ConfigKey::Name
+                    | ConfigKey::Email
+                    | ConfigKey::CommitterName
+                    | ConfigKey::CommitterEmail
+            ) || self.is_set_locally(&config_key)?
+            {
+                continue;
+            }
+
+            let value = config_value_string(value);
+            if let Err(e) = self.update_config(&config_key, &value) {
+                warn!("Ignoring remote config key {}: {}", key, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies every key in the `[config]` table of an override file, such
+    /// as a directory's `.autocommit.toml`. Keys that only the user's own
+    /// config may set are rejected.
+    pub fn apply_overrides(&mut self, contents: &str) -> anyhow::Result<()> {
+        let overrides: toml::Table = toml::from_str(contents)?;
+        let Some(toml::Value::Table(overrides)) = overrides.get("config") else {
+            return Err(anyhow!("Missing [config] table"));
+        };
+        for (key, value) in overrides {
+            let config_key =
+                ConfigKey::from_str(key).map_err(|_| anyhow!("Unsupported config key: {}", key))?;
+            if config_key.is_user_only() {
+                return Err(anyhow!(
+                    "{} can only be set in your own config, not in a repository",
+                    key
+                ));
+            }
+            self.update_config(&config_key, &config_value_string(value))?;
+        }
         Ok(())
     }
 
@@ -132,9 +332,97 @@ impl AutocommitConfig {
         self.config_data.get_value(key)
     }
 
+    pub fn get_oai_config(&self) -> anyhow::Result<OAIConfig> {
+        let api_provider = *self.config_data.api_provider.get_value_ref();
+        let open_ai_api_key = match self
+            .config_data
+            .open_ai_api_key
+            .get_value_ref()
+            .get_inner_value()
+        {
+            Some(open_ai_api_key) => open_ai_api_key,
+            // Ollama runs locally without authentication
+            None if api_provider == ApiProvider::Ollama => String::new(),
+            None => {
+                return Err(anyhow!(
+                    "Please set your {} API key in the autocommit config file or as an environment variable. \
+                    You can set it in the config file by running `autocommit config set open_ai_api_key=<your_api_key>`.",
+                    api_provider.display_name()
+                ))
+            }
+        };
+        let api_host = self.config_data.api_host.get_value_ref();
+        let stop_sequences = self
+            .config_data
+            .stop_sequences
+            .get_value_ref()
+            .get_inner_value();
+
+        let timeout = self
+            .config_data
+            .request_timeout_secs
+            .get_value_ref()
+            .get_inner_value()
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs);
+
+        let azure = match api_provider {
+            ApiProvider::OpenAI | ApiProvider::Anthropic | ApiProvider::Ollama => None,
+            ApiProvider::Azure => Some(AzureDeployment {
+                deployment: self
+                    .config_data
+                    .deployment
+                    .get_value_ref()
+                    .get_inner_value()
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Please set your Azure OpenAI deployment by running `autocommit config set deployment=<your_deployment>`."
+                        )
+                    })?,
+                api_version: self
+                    .config_data
+                    .api_version
+                    .get_value_ref()
+                    .get_inner_value()
+                    .unwrap_or_else(|| DEFAULT_AZURE_API_VERSION.to_owned()),
+            }),
+        };
+
+        let config_data = &self.config_data;
+        Ok(OAIConfig::new(api_host, open_ai_api_key)
+            .stop(stop_sequences)
+            .temperature(config_data.temperature.get_value_ref().get_inner_value())
+            .top_p(config_data.top_p.get_value_ref().get_inner_value())
+            .max_tokens(config_data.max_tokens.get_value_ref().get_inner_value())
+            .timeout(timeout)
+            .proxy(config_data.proxy_url.get_value_ref().get_inner_value())
+            .azure(azure)
+            .backend(match api_provider {
+                ApiProvider::OpenAI | ApiProvider::Azure => ChatBackend::OpenAI,
+                ApiProvider::Anthropic => ChatBackend::Anthropic,
+                ApiProvider::Ollama => ChatBackend::Ollama,
+            }))
+    }
+
     pub fn get_config_values(&self, keys: &[ConfigKey]) -> Vec<(String, String)> {
         keys.iter()
             .map(|key| (key.to_string(), self.get_config_value(key)))
             .collect()
     }
 }
+
+/// Converts a TOML value to the string form accepted by `update_config`.
+fn config_value_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(value) => value.clone(),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                toml::Value::String(item) => item.clone(),
+                item => item.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(","),
+        value => value.to_string(),
+    }
+}
@@ -1,10 +1,23 @@
+use std::{fmt, ops::RangeInclusive};
+
+use anyhow::anyhow;
 use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::commands::config::config_keys::{ConfigItem, DefaultLanguage, OptionString};
+use crate::commands::config::config_keys::{
+    ApiProvider, AuthorEmail, AuthorName, CloseKeyword, CommitStyle, ConfigItem, DefaultLanguage,
+    DiffPrivacy, OptionNumber, OptionString, StringList,
+};
 
-use super::config_keys::{ConfigKey, ConfigValue};
+use super::{
+    autocommit_config::{
+        DEFAULT_DIFF_EXCLUDE_GLOBS, DEFAULT_MAX_DIFF_TOKENS, DEFAULT_MAX_MESSAGE_BYTES,
+        DEFAULT_MAX_SUBJECT_LENGTH, DEFAULT_REQUEST_TIMEOUT_SECS, DEFAULT_TEMPERATURE,
+        DEFAULT_TOP_P,
+    },
+    config_keys::{ConfigKey, ConfigValue},
+};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct ConfigData {
     #[serde(rename = "description")]
@@ -12,11 +25,54 @@ pub struct ConfigData {
     #[serde(rename = "emoji")]
     pub emoji_enabled: ConfigItem<bool>,
     pub language: ConfigItem<DefaultLanguage>,
-    pub name: ConfigItem<String>,
-    pub email: ConfigItem<String>,
+    pub name: ConfigItem<AuthorName>,
+    pub email: ConfigItem<AuthorEmail>,
     pub open_ai_api_key: ConfigItem<OptionString>,
     pub api_host: ConfigItem<String>,
     pub open_ai_model: ConfigItem<OptionString>,
+    pub max_body_bullets: ConfigItem<OptionNumber<usize>>,
+    pub fallback_models: ConfigItem<StringList>,
+    pub stop_sequences: ConfigItem<StringList>,
+    pub pull_before_push: ConfigItem<bool>,
+    pub few_shot: ConfigItem<bool>,
+    pub confirm_show_status: ConfigItem<bool>,
+    pub confirm_stage_all: ConfigItem<bool>,
+    pub confirm_push: ConfigItem<bool>,
+    pub confirm_pull: ConfigItem<bool>,
+    pub confirm_continue: ConfigItem<bool>,
+    pub review_body: ConfigItem<bool>,
+    pub close_keyword: ConfigItem<CloseKeyword>,
+    pub auto_close_issues: ConfigItem<bool>,
+    pub enforce_imperative: ConfigItem<bool>,
+    pub max_diff_lines: ConfigItem<OptionNumber<usize>>,
+    pub vary_temperature: ConfigItem<bool>,
+    pub remote_config_url: ConfigItem<OptionString>,
+    pub diff_privacy: ConfigItem<DiffPrivacy>,
+    pub message_filter_command: ConfigItem<OptionString>,
+    pub allowed_verbs: ConfigItem<StringList>,
+    pub notify: ConfigItem<bool>,
+    pub committer_name: ConfigItem<OptionString>,
+    pub committer_email: ConfigItem<OptionString>,
+    pub response_language: ConfigItem<OptionString>,
+    pub max_message_bytes: ConfigItem<OptionNumber<usize>>,
+    pub confirm_before_send: ConfigItem<bool>,
+    pub request_timeout_secs: ConfigItem<OptionNumber<u64>>,
+    pub api_provider: ConfigItem<ApiProvider>,
+    pub deployment: ConfigItem<OptionString>,
+    pub api_version: ConfigItem<OptionString>,
+    pub temperature: ConfigItem<OptionNumber<f32>>,
+    pub top_p: ConfigItem<OptionNumber<f32>>,
+    pub max_tokens: ConfigItem<OptionNumber<u64>>,
+    pub commit_style: ConfigItem<CommitStyle>,
+    pub max_subject_length: ConfigItem<OptionNumber<usize>>,
+    pub co_authors: ConfigItem<StringList>,
+    pub sign_commits: ConfigItem<bool>,
+    pub signing_key: ConfigItem<OptionString>,
+    pub diff_exclude_globs: ConfigItem<StringList>,
+    pub diff_context_lines: ConfigItem<OptionNumber<u32>>,
+    pub max_diff_tokens: ConfigItem<OptionNumber<usize>>,
+    pub cache_enabled: ConfigItem<bool>,
+    pub proxy_url: ConfigItem<OptionString>,
 }
 
 impl<'de> Deserialize<'de> for ConfigData {
@@ -31,11 +87,97 @@ impl<'de> Deserialize<'de> for ConfigData {
             #[serde(rename = "emoji")]
             emoji_enabled: ConfigItem<bool>,
             language: ConfigItem<DefaultLanguage>,
-            name: ConfigItem<String>,
-            email: ConfigItem<String>,
+            name: ConfigItem<AuthorName>,
+            email: ConfigItem<AuthorEmail>,
             open_ai_api_key: ConfigItem<OptionString>,
             api_host: ConfigItem<String>,
             open_ai_model: ConfigItem<OptionString>,
+            #[serde(default)]
+            max_body_bullets: ConfigItem<OptionNumber<usize>>,
+            #[serde(default)]
+            fallback_models: ConfigItem<StringList>,
+            #[serde(default)]
+            stop_sequences: ConfigItem<StringList>,
+            #[serde(default)]
+            pull_before_push: ConfigItem<bool>,
+            #[serde(default = "default_true")]
+            few_shot: ConfigItem<bool>,
+            #[serde(default)]
+            confirm_show_status: ConfigItem<bool>,
+            #[serde(default = "default_true")]
+            confirm_stage_all: ConfigItem<bool>,
+            #[serde(default = "default_true")]
+            confirm_push: ConfigItem<bool>,
+            #[serde(default = "default_true")]
+            confirm_pull: ConfigItem<bool>,
+            #[serde(default)]
+            confirm_continue: ConfigItem<bool>,
+            #[serde(default)]
+            review_body: ConfigItem<bool>,
+            #[serde(default)]
+            close_keyword: ConfigItem<CloseKeyword>,
+            #[serde(default)]
+            auto_close_issues: ConfigItem<bool>,
+            #[serde(default)]
+            enforce_imperative: ConfigItem<bool>,
+            #[serde(default)]
+            max_diff_lines: ConfigItem<OptionNumber<usize>>,
+            #[serde(default)]
+            vary_temperature: ConfigItem<bool>,
+            #[serde(default)]
+            remote_config_url: ConfigItem<OptionString>,
+            #[serde(default)]
+            diff_privacy: ConfigItem<DiffPrivacy>,
+            #[serde(default)]
+            message_filter_command: ConfigItem<OptionString>,
+            #[serde(default)]
+            allowed_verbs: ConfigItem<StringList>,
+            #[serde(default)]
+            notify: ConfigItem<bool>,
+            #[serde(default)]
+            committer_name: ConfigItem<OptionString>,
+            #[serde(default)]
+            committer_email: ConfigItem<OptionString>,
+            #[serde(default)]
+            response_language: ConfigItem<OptionString>,
+            #[serde(default = "default_max_message_bytes")]
+            max_message_bytes: ConfigItem<OptionNumber<usize>>,
+            #[serde(default)]
+            confirm_before_send: ConfigItem<bool>,
+            #[serde(default = "default_request_timeout_secs")]
+            request_timeout_secs: ConfigItem<OptionNumber<u64>>,
+            #[serde(default, alias = "provider")]
+            api_provider: ConfigItem<ApiProvider>,
+            #[serde(default)]
+            deployment: ConfigItem<OptionString>,
+            #[serde(default)]
+            api_version: ConfigItem<OptionString>,
+            #[serde(default = "default_temperature")]
+            temperature: ConfigItem<OptionNumber<f32>>,
+            #[serde(default = "default_top_p")]
+            top_p: ConfigItem<OptionNumber<f32>>,
+            #[serde(default)]
+            max_tokens: ConfigItem<OptionNumber<u64>>,
+            #[serde(default)]
+            commit_style: ConfigItem<CommitStyle>,
+            #[serde(default = "default_max_subject_length")]
+            max_subject_length: ConfigItem<OptionNumber<usize>>,
+            #[serde(default)]
+            co_authors: ConfigItem<StringList>,
+            #[serde(default)]
+            sign_commits: ConfigItem<bool>,
+            #[serde(default)]
+            signing_key: ConfigItem<OptionString>,
+            #[serde(default = "default_diff_exclude_globs")]
+            diff_exclude_globs: ConfigItem<StringList>,
+            #[serde(default)]
+            diff_context_lines: ConfigItem<OptionNumber<u32>>,
+            #[serde(default = "default_max_diff_tokens")]
+            max_diff_tokens: ConfigItem<OptionNumber<usize>>,
+            #[serde(default = "default_true")]
+            cache_enabled: ConfigItem<bool>,
+            #[serde(default)]
+            proxy_url: ConfigItem<OptionString>,
         }
 
         let inner = InnerConfigData::deserialize(deserializer)?;
@@ -48,10 +190,88 @@ impl<'de> Deserialize<'de> for ConfigData {
             open_ai_api_key: inner.open_ai_api_key,
             api_host: inner.api_host,
             open_ai_model: inner.open_ai_model,
+            max_body_bullets: inner.max_body_bullets,
+            fallback_models: inner.fallback_models,
+            stop_sequences: inner.stop_sequences,
+            pull_before_push: inner.pull_before_push,
+            few_shot: inner.few_shot,
+            confirm_show_status: inner.confirm_show_status,
+            confirm_stage_all: inner.confirm_stage_all,
+            confirm_push: inner.confirm_push,
+            confirm_pull: inner.confirm_pull,
+            confirm_continue: inner.confirm_continue,
+            review_body: inner.review_body,
+            close_keyword: inner.close_keyword,
+            auto_close_issues: inner.auto_close_issues,
+            enforce_imperative: inner.enforce_imperative,
+            max_diff_lines: inner.max_diff_lines,
+            vary_temperature: inner.vary_temperature,
+            remote_config_url: inner.remote_config_url,
+            diff_privacy: inner.diff_privacy,
+            message_filter_command: inner.message_filter_command,
+            allowed_verbs: inner.allowed_verbs,
+            notify: inner.notify,
+            committer_name: inner.committer_name,
+            committer_email: inner.committer_email,
+            response_language: inner.response_language,
+            max_message_bytes: inner.max_message_bytes,
+            confirm_before_send: inner.confirm_before_send,
+            request_timeout_secs: inner.request_timeout_secs,
+            api_provider: inner.api_provider,
+            deployment: inner.deployment,
+            api_version: inner.api_version,
+            temperature: inner.temperature,
+            top_p: inner.top_p,
+            max_tokens: inner.max_tokens,
+            commit_style: inner.commit_style,
+            max_subject_length: inner.max_subject_length,
+            co_authors: inner.co_authors,
+            sign_commits: inner.sign_commits,
+            signing_key: inner.signing_key,
+            diff_exclude_globs: inner.diff_exclude_globs,
+            diff_context_lines: inner.diff_context_lines,
+            max_diff_tokens: inner.max_diff_tokens,
+            cache_enabled: inner.cache_enabled,
+            proxy_url: inner.proxy_url,
         })
     }
 }
 
+/// More context than this is better sent with `--diff-style full`.
+const MAX_DIFF_CONTEXT_LINES: u32 = 1000;
+
+fn default_true() -> ConfigItem<bool> {
+    ConfigItem::new(true)
+}
+
+fn default_max_diff_tokens() -> ConfigItem<OptionNumber<usize>> {
+    ConfigItem::new(OptionNumber::new(DEFAULT_MAX_DIFF_TOKENS))
+}
+
+fn default_max_message_bytes() -> ConfigItem<OptionNumber<usize>> {
+    ConfigItem::new(OptionNumber::new(DEFAULT_MAX_MESSAGE_BYTES))
+}
+
+fn default_max_subject_length() -> ConfigItem<OptionNumber<usize>> {
+    ConfigItem::new(OptionNumber::new(DEFAULT_MAX_SUBJECT_LENGTH))
+}
+
+fn default_diff_exclude_globs() -> ConfigItem<StringList> {
+    ConfigItem::new(StringList::new(DEFAULT_DIFF_EXCLUDE_GLOBS))
+}
+
+fn default_request_timeout_secs() -> ConfigItem<OptionNumber<u64>> {
+    ConfigItem::new(OptionNumber::new(DEFAULT_REQUEST_TIMEOUT_SECS))
+}
+
+fn default_temperature() -> ConfigItem<OptionNumber<f32>> {
+    ConfigItem::new(OptionNumber::new(DEFAULT_TEMPERATURE))
+}
+
+fn default_top_p() -> ConfigItem<OptionNumber<f32>> {
+    ConfigItem::new(OptionNumber::new(DEFAULT_TOP_P))
+}
+
 impl ConfigData {
     pub fn validate(&self) -> anyhow::Result<()> {
         self.description_enabled.value.validate()?;
@@ -59,6 +279,13 @@ impl ConfigData {
         self.language.value.validate()?;
         self.name.value.validate()?;
         self.email.value.validate()?;
+        validate_range("temperature", &self.temperature.value, 0.0..=2.0)?;
+        validate_range("top_p", &self.top_p.value, 0.0..=1.0)?;
+        validate_range(
+            "diff_context_lines",
+            &self.diff_context_lines.value,
+            0..=MAX_DIFF_CONTEXT_LINES,
+        )?;
         Ok(())
     }
 
@@ -72,6 +299,49 @@ impl ConfigData {
             ConfigKey::OpenAiApiKey => self.open_ai_api_key.update(value)?,
             ConfigKey::ApiHost => self.api_host.update(value)?,
             ConfigKey::OpenAiModel => self.open_ai_model.update(value)?,
+            ConfigKey::MaxBodyBullets => self.max_body_bullets.update(value)?,
+            ConfigKey::FallbackModels => self.fallback_models.update(value)?,
+            ConfigKey::StopSequences => self.stop_sequences.update(value)?,
+            ConfigKey::PullBeforePush => self.pull_before_push.update(value)?,
+            ConfigKey::FewShot => self.few_shot.update(value)?,
+            ConfigKey::ConfirmShowStatus => self.confirm_show_status.update(value)?,
+            ConfigKey::ConfirmStageAll => self.confirm_stage_all.update(value)?,
+            ConfigKey::ConfirmPush => self.confirm_push.update(value)?,
+            ConfigKey::ConfirmPull => self.confirm_pull.update(value)?,
+            ConfigKey::ConfirmContinue => self.confirm_continue.update(value)?,
+            ConfigKey::ReviewBody => self.review_body.update(value)?,
+            ConfigKey::CloseKeyword => self.close_keyword.update(value)?,
+            ConfigKey::AutoCloseIssues => self.auto_close_issues.update(value)?,
+            ConfigKey::EnforceImperative => self.enforce_imperative.update(value)?,
+            ConfigKey::MaxDiffLines => self.max_diff_lines.update(value)?,
+            ConfigKey::VaryTemperature => self.vary_temperature.update(value)?,
+            ConfigKey::RemoteConfigUrl => self.remote_config_url.update(value)?,
+            ConfigKey::DiffPrivacy => self.diff_privacy.update(value)?,
+            ConfigKey::MessageFilterCommand => self.message_filter_command.update(value)?,
+            ConfigKey::AllowedVerbs => self.allowed_verbs.update(value)?,
+            ConfigKey::Notify => self.notify.update(value)?,
+            ConfigKey::CommitterName => self.committer_name.update(value)?,
+            ConfigKey::CommitterEmail => self.committer_email.update(value)?,
+            ConfigKey::ResponseLanguage => self.response_language.update(value)?,
+            ConfigKey::MaxMessageBytes => self.max_message_bytes.update(value)?,
+            ConfigKey::ConfirmBeforeSend => self.confirm_before_send.update(value)?,
+            ConfigKey::RequestTimeoutSecs => self.request_timeout_secs.update(value)?,
+            ConfigKey::ApiProvider => self.api_provider.update(value)?,
+            ConfigKey::Deployment => self.deployment.update(value)?,
+            ConfigKey::ApiVersion => self.api_version.update(value)?,
+            ConfigKey::Temperature => self.temperature.update(value)?,
+            ConfigKey::TopP => self.top_p.update(value)?,
+            ConfigKey::MaxTokens => self.max_tokens.update(value)?,
+            ConfigKey::CommitStyle => self.commit_style.update(value)?,
+            ConfigKey::MaxSubjectLength => self.max_subject_length.update(value)?,
+            ConfigKey::CoAuthors => self.co_authors.update(value)?,
+            ConfigKey::SignCommits => self.sign_commits.update(value)?,
+            ConfigKey::SigningKey => self.signing_key.update(value)?,
+            ConfigKey::DiffExcludeGlobs => self.diff_exclude_globs.update(value)?,
+            ConfigKey::DiffContextLines => self.diff_context_lines.update(value)?,
+            ConfigKey::MaxDiffTokens => self.max_diff_tokens.update(value)?,
+            ConfigKey::CacheEnabled => self.cache_enabled.update(value)?,
+            ConfigKey::ProxyUrl => self.proxy_url.update(value)?,
         }
         Ok(())
     }
@@ -86,6 +356,67 @@ impl ConfigData {
             ConfigKey::OpenAiApiKey => self.open_ai_api_key.get_value(),
             ConfigKey::ApiHost => self.api_host.get_value(),
             ConfigKey::OpenAiModel => self.open_ai_model.get_value(),
+            ConfigKey::MaxBodyBullets => self.max_body_bullets.get_value(),
+            ConfigKey::FallbackModels => self.fallback_models.get_value(),
+            ConfigKey::StopSequences => self.stop_sequences.get_value(),
+            ConfigKey::PullBeforePush => self.pull_before_push.get_value(),
+            ConfigKey::FewShot => self.few_shot.get_value(),
+            ConfigKey::ConfirmShowStatus => self.confirm_show_status.get_value(),
+            ConfigKey::ConfirmStageAll => self.confirm_stage_all.get_value(),
+            ConfigKey::ConfirmPush => self.confirm_push.get_value(),
+            ConfigKey::ConfirmPull => self.confirm_pull.get_value(),
+            ConfigKey::ConfirmContinue => self.confirm_continue.get_value(),
+            ConfigKey::ReviewBody => self.review_body.get_value(),
+            ConfigKey::CloseKeyword => self.close_keyword.get_value(),
+            ConfigKey::AutoCloseIssues => self.auto_close_issues.get_value(),
+            ConfigKey::EnforceImperative => self.enforce_imperative.get_value(),
+            ConfigKey::MaxDiffLines => self.max_diff_lines.get_value(),
+            ConfigKey::VaryTemperature => self.vary_temperature.get_value(),
+            ConfigKey::RemoteConfigUrl => self.remote_config_url.get_value(),
+            ConfigKey::DiffPrivacy => self.diff_privacy.get_value(),
+            ConfigKey::MessageFilterCommand => self.message_filter_command.get_value(),
+            ConfigKey::AllowedVerbs => self.allowed_verbs.get_value(),
+            ConfigKey::Notify => self.notify.get_value(),
+            ConfigKey::CommitterName => self.committer_name.get_value(),
+            ConfigKey::CommitterEmail => self.committer_email.get_value(),
+            ConfigKey::ResponseLanguage => self.response_language.get_value(),
+            ConfigKey::MaxMessageBytes => self.max_message_bytes.get_value(),
+            ConfigKey::ConfirmBeforeSend => self.confirm_before_send.get_value(),
+            ConfigKey::RequestTimeoutSecs => self.request_timeout_secs.get_value(),
+            ConfigKey::ApiProvider => self.api_provider.get_value(),
+            ConfigKey::Deployment => self.deployment.get_value(),
+            ConfigKey::ApiVersion => self.api_version.get_value(),
+            ConfigKey::Temperature => self.temperature.get_value(),
+            ConfigKey::TopP => self.top_p.get_value(),
+            ConfigKey::MaxTokens => self.max_tokens.get_value(),
+            ConfigKey::CommitStyle => self.commit_style.get_value(),
+            ConfigKey::MaxSubjectLength => self.max_subject_length.get_value(),
+            ConfigKey::CoAuthors => self.co_authors.get_value(),
+            ConfigKey::SignCommits => self.sign_commits.get_value(),
+            ConfigKey::SigningKey => self.signing_key.get_value(),
+            ConfigKey::DiffExcludeGlobs => self.diff_exclude_globs.get_value(),
+            ConfigKey::DiffContextLines => self.diff_context_lines.get_value(),
+            ConfigKey::MaxDiffTokens => self.max_diff_tokens.get_value(),
+            ConfigKey::CacheEnabled => self.cache_enabled.get_value(),
+            ConfigKey::ProxyUrl => self.proxy_url.get_value(),
         }
     }
 }
+
+/// Checks that an optional number lies in `range` when it's set.
+fn validate_range<T: Copy + PartialOrd + fmt::Display>(
+    key: &str,
+    value: &OptionNumber<T>,
+    range: RangeInclusive<T>,
+) -> anyhow::Result<()> {
+    match value.get_inner_value() {
+        Some(number) if !range.contains(&number) => Err(anyhow!(
+            "{} must be between {} and {}, got {}",
+            key,
+            range.start(),
+            range.end(),
+            number
+        )),
+        _ => Ok(()),
+    }
+}
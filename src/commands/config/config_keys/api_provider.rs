@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use anyhow::anyhow;
+use std::{fmt, str::FromStr};
+
+use super::config_item::ConfigValue;
+
+/// The kind of API the requests are sent to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiProvider {
+    /// The OpenAI API or a compatible one at `api_host`.
+    #[default]
+    OpenAI,
+    /// An Azure OpenAI resource at `api_host`, using `deployment`.
+    Azure,
+    /// Anthropic's messages API at `api_host`.
+    Anthropic,
+    /// A local Ollama server at `api_host`, which needs no API key.
+    Ollama,
+}
+
+impl ApiProvider {
+    pub const VARIANTS: [&'static str; 4] = ["openai", "azure", "anthropic", "ollama"];
+
+    /// The provider's name as shown to users, e.g. in "No Anthropic API key".
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::OpenAI => "OpenAI",
+            Self::Azure => "Azure OpenAI",
+            Self::Anthropic => "Anthropic",
+            Self::Ollama => "Ollama",
+        }
+    }
+}
+
+impl FromStr for ApiProvider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "openai" => Ok(Self::OpenAI),
+            "azure" => Ok(Self::Azure),
+            "anthropic" => Ok(Self::Anthropic),
+            "ollama" => Ok(Self::Ollama),
+            _ => Err(anyhow!(
+                "Unsupported API provider: {}. Use one of: {}",
+                s,
+                Self::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ApiProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OpenAI => write!(f, "openai"),
+            Self::Azure => write!(f, "azure"),
+            Self::Anthropic => write!(f, "anthropic"),
+            Self::Ollama => write!(f, "ollama"),
+        }
+    }
+}
+
+impl ConfigValue for ApiProvider {
+    fn validate(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, value: &str) -> anyhow::Result<()> {
+        *self = value.parse()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> String {
+        self.to_string()
+    }
+}
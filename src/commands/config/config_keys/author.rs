@@ -0,0 +1,110 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use super::config_item::ConfigValue;
+
+/// The commit author's name, written into commit authorship as-is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorName(String);
+
+impl AuthorName {
+    pub fn get_inner_value(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for AuthorName {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Invalid name: the name must not be empty"));
+        }
+        if name.contains(['<', '>']) {
+            return Err(anyhow!(
+                "Invalid name: {}. The name must not contain '<' or '>'",
+                name
+            ));
+        }
+        Ok(Self(name.to_owned()))
+    }
+}
+
+impl fmt::Display for AuthorName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl ConfigValue for AuthorName {
+    fn validate(&self) -> anyhow::Result<()> {
+        self.0.parse::<Self>().map(|_| ())
+    }
+
+    fn update(&mut self, value: &str) -> anyhow::Result<()> {
+        *self = value.parse()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// The commit author's email address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorEmail(String);
+
+impl AuthorEmail {
+    pub fn get_inner_value(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for AuthorEmail {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let email = s.trim();
+        let looks_valid = match email.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.is_empty()
+                    && !domain.contains('@')
+                    && !email.contains(|c: char| c.is_whitespace() || c == '<' || c == '>')
+            }
+            None => false,
+        };
+        if !looks_valid {
+            return Err(anyhow!(
+                "Invalid email: '{}'. Expected an address like name@example.com",
+                email
+            ));
+        }
+        Ok(Self(email.to_owned()))
+    }
+}
+
+impl fmt::Display for AuthorEmail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl ConfigValue for AuthorEmail {
+    fn validate(&self) -> anyhow::Result<()> {
+        self.0.parse::<Self>().map(|_| ())
+    }
+
+    fn update(&mut self, value: &str) -> anyhow::Result<()> {
+        *self = value.parse()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+use anyhow::anyhow;
+use std::{fmt, str::FromStr};
+
+use super::config_item::ConfigValue;
+
+const CLOSE_KEYWORDS: [&str; 9] = [
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CloseKeyword(String);
+
+impl CloseKeyword {
+    pub fn get_inner_value(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for CloseKeyword {
+    fn default() -> Self {
+        Self(String::from("Closes"))
+    }
+}
+
+impl FromStr for CloseKeyword {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keyword = s.trim();
+        if CLOSE_KEYWORDS.contains(&keyword.to_lowercase().as_str()) {
+            Ok(Self(keyword.to_owned()))
+        } else {
+            Err(anyhow!(
+                "Unsupported close keyword: {}. Use one of: {}",
+                s,
+                CLOSE_KEYWORDS.join(", ")
+            ))
+        }
+    }
+}
+
+impl fmt::Display for CloseKeyword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CloseKeyword {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl ConfigValue for CloseKeyword {
+    fn validate(&self) -> anyhow::Result<()> {
+        self.0.parse::<Self>().map(|_| ())
+    }
+
+    fn update(&mut self, value: &str) -> anyhow::Result<()> {
+        *self = value.parse()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> String {
+        self.to_string()
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use anyhow::anyhow;
+use std::{fmt, str::FromStr};
+
+use super::config_item::ConfigValue;
+
+/// The format generated commit messages follow.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitStyle {
+    /// No particular format beyond the other settings.
+    #[default]
+    Free,
+    /// `type(scope): subject`, following Conventional Commits.
+    Conventional,
+}
+
+impl CommitStyle {
+    pub const VARIANTS: [&'static str; 2] = ["free", "conventional"];
+}
+
+impl FromStr for CommitStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "free" => Ok(Self::Free),
+            "conventional" => Ok(Self::Conventional),
+            _ => Err(anyhow!(
+                "Unsupported commit style: {}. Use one of: {}",
+                s,
+                Self::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CommitStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Free => write!(f, "free"),
+            Self::Conventional => write!(f, "conventional"),
+        }
+    }
+}
+
+impl ConfigValue for CommitStyle {
+    fn validate(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, value: &str) -> anyhow::Result<()> {
+        *self = value.parse()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> String {
+        self.to_string()
+    }
+}
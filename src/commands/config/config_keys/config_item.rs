@@ -41,7 +41,7 @@ impl ConfigValue for String {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ConfigItem<T>
 where
     T: ConfigValue,
@@ -49,6 +49,15 @@ where
     pub value: T,
 }
 
+impl<T> Default for ConfigItem<T>
+where
+    T: ConfigValue + Default,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
 impl<T> Serialize for ConfigItem<T>
 where
     T: Serialize + ConfigValue,
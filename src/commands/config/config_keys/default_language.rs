@@ -1,30 +1,24 @@
 use serde::Serialize;
 
 use crate::i18n::language::Language;
-use anyhow::anyhow;
 use std::{fmt, str::FromStr};
 
 use super::config_item::ConfigValue;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DefaultLanguage(pub Language);
 
 impl FromStr for DefaultLanguage {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "english" => Ok(Self(Language::English)),
-            _ => Ok(Self(Language::English)),
-        }
+        s.parse().map(Self)
     }
 }
 
 impl fmt::Display for DefaultLanguage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self.0 {
-            Language::English => write!(f, "english"),
-        }
+        write!(f, "{}", self.0)
     }
 }
 
@@ -34,10 +28,7 @@ impl<'de> serde::Deserialize<'de> for DefaultLanguage {
         D: serde::Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        match s.to_lowercase().as_str() {
-            "english" => Ok(Self(Language::English)),
-            _ => Ok(Self(Language::English)),
-        }
+        s.parse().map_err(serde::de::Error::custom)
     }
 }
 
@@ -47,15 +38,11 @@ impl ConfigValue for DefaultLanguage {
     }
 
     fn update(&mut self, value: &str) -> anyhow::Result<()> {
-        match value {
-            "english" => self.0 = Language::English,
-            _ => return Err(anyhow!("Unsupported language")),
-        }
-
+        *self = value.parse()?;
         Ok(())
     }
 
     fn get_value(&self) -> String {
-        format!("{:?}", self.0).to_lowercase()
+        self.to_string()
     }
 }
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use anyhow::anyhow;
+use std::{fmt, str::FromStr};
+
+use super::config_item::ConfigValue;
+
+/// How much of the diff metadata is sent to the model.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffPrivacy {
+    /// The diff as is, including file paths.
+    #[default]
+    Full,
+    /// File paths are replaced with `file1`, `file2` and so on.
+    NoPaths,
+    /// Only the hunks are sent, without any file headers.
+    HunksOnly,
+}
+
+impl DiffPrivacy {
+    pub const VARIANTS: [&'static str; 3] = ["full", "no-paths", "hunks-only"];
+}
+
+impl FromStr for DiffPrivacy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "full" => Ok(Self::Full),
+            "no-paths" => Ok(Self::NoPaths),
+            "hunks-only" => Ok(Self::HunksOnly),
+            _ => Err(anyhow!(
+                "Unsupported diff privacy: {}. Use one of: {}",
+                s,
+                Self::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DiffPrivacy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => write!(f, "full"),
+            Self::NoPaths => write!(f, "no-paths"),
+            Self::HunksOnly => write!(f, "hunks-only"),
+        }
+    }
+}
+
+impl ConfigValue for DiffPrivacy {
+    fn validate(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, value: &str) -> anyhow::Result<()> {
+        *self = value.parse()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> String {
+        self.to_string()
+    }
+}
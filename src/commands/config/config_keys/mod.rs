@@ -1,13 +1,31 @@
 use strum::{Display, EnumIter, EnumString};
 
+use crate::utils::redact_url_credentials;
+
+mod api_provider;
+mod author;
+mod close_keyword;
+mod commit_style;
 mod config_item;
 mod default_language;
+mod diff_privacy;
+mod option_number;
 mod option_string;
+mod string_list;
+#[cfg(test)]
+mod tests;
 
+pub use api_provider::ApiProvider;
+pub use author::{AuthorEmail, AuthorName};
+pub use close_keyword::CloseKeyword;
+pub use commit_style::CommitStyle;
 pub use config_item::ConfigItem;
 pub use config_item::ConfigValue;
 pub use default_language::DefaultLanguage;
+pub use diff_privacy::DiffPrivacy;
+pub use option_number::OptionNumber;
 pub use option_string::OptionString;
+pub use string_list::StringList;
 
 #[derive(Debug, PartialEq, Display, EnumIter, EnumString)]
 pub enum ConfigKey {
@@ -27,4 +45,139 @@ pub enum ConfigKey {
     Name,
     #[strum(serialize = "email")]
     Email,
+    #[strum(serialize = "max_body_bullets")]
+    MaxBodyBullets,
+    #[strum(serialize = "fallback_models")]
+    FallbackModels,
+    #[strum(serialize = "stop_sequences")]
+    StopSequences,
+    #[strum(serialize = "pull_before_push")]
+    PullBeforePush,
+    #[strum(serialize = "few_shot")]
+    FewShot,
+    #[strum(serialize = "confirm_show_status")]
+    ConfirmShowStatus,
+    #[strum(serialize = "confirm_stage_all")]
+    ConfirmStageAll,
+    #[strum(serialize = "confirm_push")]
+    ConfirmPush,
+    #[strum(serialize = "confirm_pull")]
+    ConfirmPull,
+    #[strum(serialize = "confirm_continue")]
+    ConfirmContinue,
+    #[strum(serialize = "review_body")]
+    ReviewBody,
+    #[strum(serialize = "close_keyword")]
+    CloseKeyword,
+    #[strum(serialize = "auto_close_issues")]
+    AutoCloseIssues,
+    #[strum(serialize = "enforce_imperative")]
+    EnforceImperative,
+    #[strum(serialize = "max_diff_lines")]
+    MaxDiffLines,
+    #[strum(serialize = "vary_temperature")]
+    VaryTemperature,
+    #[strum(serialize = "remote_config_url")]
+    RemoteConfigUrl,
+    #[strum(serialize = "diff_privacy")]
+    DiffPrivacy,
+    #[strum(serialize = "message_filter_command")]
+    MessageFilterCommand,
+    #[strum(serialize = "allowed_verbs")]
+    AllowedVerbs,
+    #[strum(serialize = "notify")]
+    Notify,
+    #[strum(serialize = "committer_name")]
+    CommitterName,
+    #[strum(serialize = "committer_email")]
+    CommitterEmail,
+    #[strum(serialize = "response_language")]
+    ResponseLanguage,
+    #[strum(serialize = "max_message_bytes")]
+    MaxMessageBytes,
+    #[strum(serialize = "confirm_before_send")]
+    ConfirmBeforeSend,
+    #[strum(serialize = "request_timeout_secs")]
+    RequestTimeoutSecs,
+    #[strum(to_string = "api_provider", serialize = "provider")]
+    ApiProvider,
+    #[strum(serialize = "deployment")]
+    Deployment,
+    #[strum(serialize = "api_version")]
+    ApiVersion,
+    #[strum(serialize = "temperature")]
+    Temperature,
+    #[strum(serialize = "top_p")]
+    TopP,
+    #[strum(serialize = "max_tokens")]
+    MaxTokens,
+    #[strum(serialize = "commit_style")]
+    CommitStyle,
+    #[strum(serialize = "max_subject_length")]
+    MaxSubjectLength,
+    #[strum(serialize = "co_authors")]
+    CoAuthors,
+    #[strum(serialize = "sign_commits")]
+    SignCommits,
+    #[strum(serialize = "signing_key")]
+    SigningKey,
+    #[strum(serialize = "diff_exclude_globs")]
+    DiffExcludeGlobs,
+    #[strum(serialize = "diff_context_lines")]
+    DiffContextLines,
+    #[strum(serialize = "max_diff_tokens")]
+    MaxDiffTokens,
+    #[strum(serialize = "cache_enabled")]
+    CacheEnabled,
+    #[strum(serialize = "proxy_url")]
+    ProxyUrl,
+}
+
+impl ConfigKey {
+    /// Whether only the user's own config may set the key. A repository or
+    /// remote config could otherwise send the API key and the staged code to
+    /// a host of its choosing, or run its own commands.
+    pub fn is_user_only(&self) -> bool {
+        matches!(
+            self,
+            ConfigKey::OpenAiApiKey
+                | ConfigKey::ApiHost
+                | ConfigKey::ApiProvider
+                | ConfigKey::Deployment
+                | ConfigKey::ApiVersion
+                | ConfigKey::ProxyUrl
+                | ConfigKey::ConfirmBeforeSend
+                | ConfigKey::RemoteConfigUrl
+                | ConfigKey::MessageFilterCommand
+        )
+    }
+
+    /// Whether the key holds a secret that is masked when printed.
+    pub fn is_sensitive(&self) -> bool {
+        matches!(self, ConfigKey::OpenAiApiKey | ConfigKey::ProxyUrl)
+    }
+
+    /// Masks the value of a sensitive key for display. URLs keep everything
+    /// but their credentials.
+    pub fn mask(&self, value: &str) -> String {
+        match self {
+            ConfigKey::ProxyUrl => redact_url_credentials(value),
+            _ => mask_secret(value),
+        }
+    }
+}
+
+/// Masks a secret for display, keeping its prefix and last 4 characters
+/// when it is long enough to stay unguessable, e.g. `sk-…abcd`.
+fn mask_secret(value: &str) -> String {
+    let chars = value.chars().collect::<Vec<_>>();
+    if chars.is_empty() {
+        return String::new();
+    }
+    if chars.len() < 12 {
+        return "…".to_owned();
+    }
+    let prefix = chars[..3].iter().collect::<String>();
+    let suffix = chars[chars.len() - 4..].iter().collect::<String>();
+    format!("{}…{}", prefix, suffix)
 }
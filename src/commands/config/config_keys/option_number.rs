@@ -0,0 +1,73 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use super::config_item::ConfigValue;
+use anyhow::anyhow;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OptionNumber<T>(Option<T>);
+
+impl<T: Copy> OptionNumber<T> {
+    pub fn new(value: T) -> Self {
+        Self(Some(value))
+    }
+
+    pub fn get_inner_value(&self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T> Default for OptionNumber<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T: FromStr> FromStr for OptionNumber<T> {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self(None));
+        }
+
+        s.parse()
+            .map(|value| Self(Some(value)))
+            .map_err(|_| anyhow!("Invalid value for number: {}", s))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for OptionNumber<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(value) => write!(f, "{}", value),
+            None => write!(f, ""),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for OptionNumber<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = Option::<T>::deserialize(deserializer)?;
+        Ok(OptionNumber(value))
+    }
+}
+
+impl<T: FromStr + fmt::Display> ConfigValue for OptionNumber<T> {
+    fn validate(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, value: &str) -> anyhow::Result<()> {
+        *self = value.parse()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> String {
+        self.to_string()
+    }
+}
@@ -5,7 +5,7 @@ use serde::Serialize;
 use super::config_item::ConfigValue;
 use anyhow::anyhow;
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct OptionString(Option<String>);
 impl OptionString {
     pub fn get_inner_value(&self) -> Option<String> {
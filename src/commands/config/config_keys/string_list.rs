@@ -0,0 +1,63 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::config_item::ConfigValue;
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct StringList(Vec<String>);
+
+impl StringList {
+    pub fn new(items: &[&str]) -> Self {
+        Self(items.iter().map(|item| item.to_string()).collect())
+    }
+
+    pub fn get_inner_value(&self) -> Vec<String> {
+        self.0.clone()
+    }
+}
+
+impl std::str::FromStr for StringList {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let items = s
+            .split(',')
+            .map(|item| item.trim())
+            .filter(|item| !item.is_empty())
+            .map(|item| item.to_owned())
+            .collect();
+        Ok(Self(items))
+    }
+}
+
+impl fmt::Display for StringList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join(","))
+    }
+}
+
+impl<'de> Deserialize<'de> for StringList {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let items = Vec::<String>::deserialize(deserializer)?;
+        Ok(StringList(items))
+    }
+}
+
+impl ConfigValue for StringList {
+    fn validate(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, value: &str) -> anyhow::Result<()> {
+        *self = value.parse()?;
+        Ok(())
+    }
+
+    fn get_value(&self) -> String {
+        self.to_string()
+    }
+}
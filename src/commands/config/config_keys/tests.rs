@@ -0,0 +1,81 @@
+#[test]
+fn test_author_email_validation() {
+    use super::AuthorEmail;
+
+    assert!("jane@example.com".parse::<AuthorEmail>().is_ok());
+    assert!(" jane@example.com ".parse::<AuthorEmail>().is_ok());
+    assert!("notanemail".parse::<AuthorEmail>().is_err());
+    assert!("".parse::<AuthorEmail>().is_err());
+    assert!("@example.com".parse::<AuthorEmail>().is_err());
+    assert!("jane@".parse::<AuthorEmail>().is_err());
+    assert!("jane doe@example.com".parse::<AuthorEmail>().is_err());
+    assert!("<jane@example.com>".parse::<AuthorEmail>().is_err());
+}
+
+#[test]
+fn test_author_name_validation() {
+    use super::AuthorName;
+
+    assert!("Jane Doe".parse::<AuthorName>().is_ok());
+    assert!("  ".parse::<AuthorName>().is_err());
+    assert!("Jane <jane@example.com>".parse::<AuthorName>().is_err());
+}
+
+#[test]
+fn test_default_language_parsing() {
+    use super::DefaultLanguage;
+    use crate::i18n::{self, language::Language};
+
+    assert_eq!(
+        "french".parse::<DefaultLanguage>().unwrap().0,
+        Language::French
+    );
+    assert_eq!(
+        "German".parse::<DefaultLanguage>().unwrap().0,
+        Language::German
+    );
+    assert_eq!(
+        "en".parse::<DefaultLanguage>().unwrap().0,
+        Language::English
+    );
+    assert_eq!(
+        "zh".parse::<DefaultLanguage>().unwrap().0,
+        Language::Chinese
+    );
+    assert!("klingon".parse::<DefaultLanguage>().is_err());
+
+    for language in Language::ALL {
+        let parsed = language.to_string().parse::<DefaultLanguage>().unwrap();
+        assert_eq!(parsed.0, language);
+        assert!(i18n::get_translation(&language).is_some());
+    }
+}
+
+#[test]
+fn test_mask_secret() {
+    use super::{mask_secret, ConfigKey};
+
+    assert_eq!(mask_secret("sk-1234567890abcd"), "sk-…abcd");
+    assert_eq!(mask_secret("sk-short"), "…");
+    assert_eq!(mask_secret(""), "");
+    assert!(ConfigKey::OpenAiApiKey.is_sensitive());
+    assert!(ConfigKey::ProxyUrl.is_sensitive());
+    assert!(!ConfigKey::OpenAiModel.is_sensitive());
+
+    assert_eq!(
+        ConfigKey::OpenAiApiKey.mask("sk-1234567890abcd"),
+        "sk-…abcd"
+    );
+    assert_eq!(
+        ConfigKey::ProxyUrl.mask("http://user:p@ss@proxy.example:8080/path"),
+        "http://…@proxy.example:8080/path"
+    );
+    assert_eq!(
+        ConfigKey::ProxyUrl.mask("http://proxy.example:8080"),
+        "http://proxy.example:8080"
+    );
+    assert_eq!(
+        ConfigKey::ProxyUrl.mask("user:secret@proxy.example"),
+        "…@proxy.example"
+    );
+}
@@ -1,8 +1,8 @@
 use std::path::PathBuf;
 
-use log::debug;
+use log::{debug, warn};
 
-use super::{config_keys::ConfigKey, AutocommitConfig};
+use super::{config_keys::ConfigKey, remote_config::load_remote_config, AutocommitConfig};
 
 pub struct AutocommitService {
     config: AutocommitConfig,
@@ -15,12 +15,27 @@ impl AutocommitService {
         Ok(Self { config })
     }
 
-    pub fn update_config(&mut self, key: &ConfigKey, value: &str) -> anyhow::Result<()> {
-        self.config.update_config(key, value)
+    /// Fills in defaults from the organization-wide config at
+    /// `remote_config_url`, if one is set.
+    pub async fn apply_remote_config(&mut self) {
+        let config_data = &self.config.config_data;
+        let Some(url) = config_data
+            .remote_config_url
+            .get_value_ref()
+            .get_inner_value()
+        else {
+            return;
+        };
+        let proxy = config_data.proxy_url.get_value_ref().get_inner_value();
+        if let Some(contents) = load_remote_config(&url, proxy.as_deref()).await {
+            if let Err(e) = self.config.merge_remote(&contents) {
+                warn!("Ignoring remote config from {}: {}", url, e);
+            }
+        }
     }
 
-    pub fn get_config_value(&self, key: &ConfigKey) -> String {
-        self.config.get_config_value(key)
+    pub fn update_config(&mut self, key: &ConfigKey, value: &str) -> anyhow::Result<()> {
+        self.config.set_config(key, value)
     }
 
     pub fn get_config_values(&self, keys: &[ConfigKey]) -> Vec<(String, String)> {
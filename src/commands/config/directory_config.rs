@@ -0,0 +1,86 @@
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Context;
+use log::debug;
+
+use crate::{git::GitRepository, utils::outro};
+
+use super::AutocommitConfig;
+
+const DIRECTORY_CONFIG_FILE: &str = ".autocommit.toml";
+
+/// Returns the deepest directory containing all of `files`, which are
+/// relative to the repository root.
+pub fn common_dir(files: &[String]) -> PathBuf {
+    let mut common: Option<Vec<Component>> = None;
+    for file in files {
+        let components = Path::new(file)
+            .parent()
+            .map(|parent| parent.components().collect::<Vec<_>>())
+            .unwrap_or_default();
+        common = Some(match common {
+            None => components,
+            Some(common) => common
+                .into_iter()
+                .zip(components)
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    common.unwrap_or_default().into_iter().collect()
+}
+
+/// Returns the `.autocommit.toml` files from `root` down to `root/dir`,
+/// so that the nearest file comes last and takes precedence.
+pub fn find_directory_configs(root: &Path, dir: &Path) -> Vec<PathBuf> {
+    let mut current = root.to_path_buf();
+    let mut dirs = vec![current.clone()];
+    for component in dir.components() {
+        current.push(component);
+        dirs.push(current.clone());
+    }
+
+    dirs.into_iter()
+        .map(|dir| dir.join(DIRECTORY_CONFIG_FILE))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Layers the `.autocommit.toml` files between the repository root and the
+/// common directory of `files` over `config`, like `.gitignore` resolution.
+pub async fn apply_directory_configs(
+    config: &AutocommitConfig,
+    files: &[String],
+) -> anyhow::Result<AutocommitConfig> {
+    let root = GitRepository::get_repo_root()?;
+    let paths = find_directory_configs(&root, &common_dir(files));
+
+    let mut config = config.clone();
+    for path in &paths {
+        debug!("Applying config overrides from {:?}", path);
+        let contents = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        config
+            .apply_overrides(&contents)
+            .with_context(|| format!("Invalid config file: {}", path.display()))?;
+    }
+
+    if !paths.is_empty() {
+        let relative_paths = paths
+            .iter()
+            .map(|path| {
+                path.strip_prefix(&root)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        outro(&format!(
+            "Using config overrides from {}",
+            relative_paths.join(", ")
+        ));
+    }
+    Ok(config)
+}
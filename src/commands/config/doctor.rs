@@ -0,0 +1,274 @@
+use std::{path::Path, str::FromStr, time::Duration};
+
+use anyhow::anyhow;
+use colored::Colorize;
+
+use crate::{
+    git::GitRepository,
+    utils::{http_client, OAIModel, SUPPORTED_MODELS},
+};
+
+use super::{config_keys::ApiProvider, config_service::AutocommitService, AutocommitConfig};
+
+enum CheckStatus {
+    Pass,
+    Warn(String),
+    Fail(String),
+}
+
+struct Check {
+    name: String,
+    status: CheckStatus,
+}
+
+impl Check {
+    fn pass(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+        }
+    }
+
+    fn warn(name: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Warn(hint.into()),
+        }
+    }
+
+    fn fail(name: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Fail(hint.into()),
+        }
+    }
+
+    fn print(&self) {
+        match &self.status {
+            CheckStatus::Pass => println!("{} {}", "✔".green(), self.name),
+            CheckStatus::Warn(hint) => {
+                println!("{} {}\n    {}", "⚠".yellow(), self.name, hint.dimmed())
+            }
+            CheckStatus::Fail(hint) => {
+                println!("{} {}\n    {}", "✖".red(), self.name.red(), hint.dimmed())
+            }
+        }
+    }
+}
+
+/// Runs every check, prints a checklist with remediation hints and fails if
+/// any check failed.
+pub async fn run_doctor(config_path: &Path) -> anyhow::Result<()> {
+    let mut checks = Vec::new();
+
+    let service = if config_path.exists() {
+        match AutocommitService::new(&config_path.to_path_buf()).await {
+            Ok(service) => {
+                checks.push(Check::pass(format!(
+                    "Config file parses: {}",
+                    config_path.display()
+                )));
+                Some(service)
+            }
+            Err(e) => {
+                checks.push(Check::fail(
+                    format!("Config file parses: {}", config_path.display()),
+                    format!(
+                        "{:#}. Fix the file by hand or run `autocommit config reset`.",
+                        e
+                    ),
+                ));
+                None
+            }
+        }
+    } else {
+        checks.push(Check::warn(
+            format!("Config file exists: {}", config_path.display()),
+            "It will be created with default values the first time autocommit runs.",
+        ));
+        None
+    };
+
+    if let Some(service) = &service {
+        let config = service.get_config();
+        // Ollama runs locally without an API key
+        if *config.config_data.api_provider.get_value_ref() != ApiProvider::Ollama {
+            checks.push(check_api_key(config));
+        }
+        checks.push(check_models(config));
+        checks.push(check_api_host(config).await);
+    }
+
+    checks.push(match GitRepository::assert_git_installed().await {
+        Ok(()) => Check::pass("git executable is installed"),
+        Err(e) => Check::fail("git executable is installed", e.to_string()),
+    });
+    checks.push(check_git_identity());
+    let in_repo = GitRepository::get_repo_root().ok();
+    checks.push(match &in_repo {
+        Some(_) => Check::pass("Inside a git repository"),
+        None => Check::fail(
+            "Inside a git repository",
+            "Run autocommit from a git working tree, or create one with `git init`.",
+        ),
+    });
+    if let Some(root) = &in_repo {
+        checks.push(check_ignore_files(root));
+    }
+
+    for check in &checks {
+        check.print();
+    }
+
+    let failures = checks
+        .iter()
+        .filter(|check| matches!(check.status, CheckStatus::Fail(_)))
+        .count();
+    if failures > 0 {
+        return Err(anyhow!("{} of {} checks failed", failures, checks.len()));
+    }
+    Ok(())
+}
+
+fn check_api_key(config: &AutocommitConfig) -> Check {
+    const NAME: &str = "OpenAI API key is set";
+    let config_data = &config.config_data;
+    let Some(api_key) = config_data
+        .open_ai_api_key
+        .get_value_ref()
+        .get_inner_value()
+    else {
+        return Check::fail(
+            NAME,
+            "Run `autocommit config set open_ai_api_key=<key>` or export OPENAI_API_KEY.",
+        );
+    };
+
+    let uses_openai = config_data
+        .api_host
+        .get_value_ref()
+        .contains("api.openai.com");
+    if uses_openai && !api_key.starts_with("sk-") {
+        return Check::warn(
+            NAME,
+            "The key doesn't start with 'sk-', double-check that it is an OpenAI API key.",
+        );
+    }
+    Check::pass(NAME)
+}
+
+fn check_models(config: &AutocommitConfig) -> Check {
+    const NAME: &str = "Models are supported";
+    let config_data = &config.config_data;
+    if matches!(
+        config_data.api_provider.get_value_ref(),
+        ApiProvider::Anthropic | ApiProvider::Ollama
+    ) {
+        // Only the OpenAI models are known
+        return Check::pass(NAME);
+    }
+    let model = config_data
+        .open_ai_model
+        .get_value_ref()
+        .get_inner_value()
+        .unwrap_or_else(|| String::from("gpt-3.5-turbo"));
+    let unknown_models = std::iter::once(model)
+        .chain(
+            config_data
+                .fallback_models
+                .get_value_ref()
+                .get_inner_value(),
+        )
+        .filter(|model| !OAIModel::from_str(model).is_ok_and(|model| model.is_known()))
+        .collect::<Vec<_>>();
+
+    if unknown_models.is_empty() {
+        Check::pass(NAME)
+    } else {
+        Check::warn(
+            NAME,
+            format!(
+                "Unknown model(s): {}. They're sent to the API as is, so make sure it serves them, or use one of {}.",
+                unknown_models.join(", "),
+                SUPPORTED_MODELS.join(", ")
+            ),
+        )
+    }
+}
+
+async fn check_api_host(config: &AutocommitConfig) -> Check {
+    let api_host = config.config_data.api_host.get_value_ref();
+    let name = format!("API host is reachable: {}", api_host);
+    let proxy = config
+        .config_data
+        .proxy_url
+        .get_value_ref()
+        .get_inner_value();
+    let client = match http_client(api_host, proxy.as_deref()) {
+        Ok(client) => client,
+        Err(e) => return Check::fail(name, format!("{}. Check the proxy_url value.", e)),
+    };
+    match tokio::time::timeout(Duration::from_secs(5), client.get(api_host)).await {
+        Ok(Ok(_)) => Check::pass(name),
+        Ok(Err(e)) => Check::fail(
+            name,
+            format!(
+                "{}. Check your network connection, proxy settings and the api_host value.",
+                e
+            ),
+        ),
+        Err(_) => Check::fail(
+            name,
+            "The request timed out after 5 seconds. Check your network connection and the api_host value.",
+        ),
+    }
+}
+
+fn check_git_identity() -> Check {
+    const NAME: &str = "Git identity is set";
+    let missing = [
+        ("user.name", GitRepository::get_git_user_name().is_err()),
+        ("user.email", GitRepository::get_git_user_email().is_err()),
+    ]
+    .into_iter()
+    .filter_map(|(key, is_missing)| is_missing.then_some(key))
+    .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        Check::pass(NAME)
+    } else {
+        Check::fail(
+            NAME,
+            format!(
+                "Missing {}. Run {}.",
+                missing.join(" and "),
+                missing
+                    .iter()
+                    .map(|key| format!("`git config --global {} <value>`", key))
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            ),
+        )
+    }
+}
+
+fn check_ignore_files(root: &Path) -> Check {
+    const NAME: &str = "Ignore files are present";
+    let missing = [".gitignore", ".autoignore"]
+        .into_iter()
+        .filter(|file| !root.join(file).exists())
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        Check::pass(NAME)
+    } else {
+        Check::warn(
+            NAME,
+            format!(
+                "No {} found in {}. Add one to keep generated or large files out of the prompt.",
+                missing.join(" or "),
+                root.display()
+            ),
+        )
+    }
+}
@@ -1,20 +1,36 @@
 use anyhow::{anyhow, Result};
 use colored::*;
+use dialoguer::{theme::ColorfulTheme, Confirm, Password};
 use log::{debug, info};
-use std::{path::PathBuf, str::FromStr};
+use std::{io::IsTerminal, path::PathBuf, str::FromStr};
 use structopt::StructOpt;
 use strum::IntoEnumIterator;
 
 use crate::utils::outro;
 
 pub use autocommit_config::AutocommitConfig;
+pub use config_keys::{CommitStyle, DiffPrivacy};
+pub use directory_config::apply_directory_configs;
+pub use send_confirmation::confirm_send;
 
-use self::{config_keys::ConfigKey, config_service::AutocommitService};
+use self::{
+    config_keys::ConfigKey,
+    config_service::AutocommitService,
+    doctor::run_doctor,
+    profile::{profile_path, ProfileCommand},
+};
 
 mod autocommit_config;
 mod config_data;
 mod config_keys;
 mod config_service;
+mod directory_config;
+mod doctor;
+mod profile;
+mod remote_config;
+mod send_confirmation;
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug, StructOpt)]
 pub enum ConfigCommand {
@@ -30,6 +46,9 @@ pub enum ConfigCommand {
             help = "Path to the configuration file"
         )]
         config_path: Option<PathBuf>,
+
+        #[structopt(long, help = "Print secret values such as the API key unmasked")]
+        show_secrets: bool,
     },
 
     #[structopt(name = "set")]
@@ -60,39 +79,45 @@ pub enum ConfigCommand {
             help = "The shell for which to print environment variables. Options are: bash, fish, powershell"
         )]
         shell: Option<String>,
+
+        #[structopt(long, help = "Print secret values such as the API key unmasked")]
+        show_secrets: bool,
     },
+    #[structopt(name = "profile")]
+    Profile(ProfileCommand),
+    #[structopt(
+        name = "doctor",
+        about = "Check the config and environment for common problems"
+    )]
+    Doctor,
 }
 
 impl ConfigCommand {
-    async fn get_service(&self) -> anyhow::Result<AutocommitService> {
-        let config_path = self.get_config_path()?;
-        let service = AutocommitService::new(&config_path).await?;
+    async fn get_service(&self, profile: Option<&str>) -> anyhow::Result<AutocommitService> {
+        let config_path = self.get_config_path(profile)?;
+        let mut service = AutocommitService::new(&config_path).await?;
+        // Keep remote values out of the local config file when saving it
+        if !matches!(self, ConfigCommand::Set { .. } | ConfigCommand::Reset) {
+            service.apply_remote_config().await;
+        }
         Ok(service)
     }
 
-    pub async fn run(&self) -> Result<()> {
-        let mut service = self.get_service().await?;
+    pub async fn run(&self, profile: Option<&str>) -> Result<()> {
         match self {
-            ConfigCommand::Get { keys, .. } => {
-                let config_values = if keys.is_empty() {
-                    let all_keys = ConfigKey::iter().collect::<Vec<_>>();
-                    service.get_config_values(&all_keys)
-                } else {
-                    keys.iter()
-                        .map(|key| {
-                            ConfigKey::from_str(key).map(|config_key| {
-                                (key.to_string(), service.get_config_value(&config_key))
-                            })
-                        })
-                        .filter_map(Result::ok)
-                        .collect()
-                };
+            ConfigCommand::Get {
+                keys, show_secrets, ..
+            } => {
+                let service = self.get_service(profile).await?;
+                let config_keys = parse_get_keys(keys)?;
+                let config_values = printable_values(&service, &config_keys, *show_secrets);
 
                 for (key, value) in config_values {
                     println!("{} = {}", key.bold(), value.green());
                 }
             }
             ConfigCommand::Set { key_values, .. } => {
+                let mut service = self.get_service(profile).await?;
                 for key_value in key_values {
                     let parts: Vec<&str> = key_value.splitn(2, '=').collect();
                     if parts.len() != 2 {
@@ -108,22 +133,28 @@ impl ConfigCommand {
                     service.update_config(&config_key, value)?;
                 }
 
-                let config_path = self.get_config_path()?;
+                let config_path = self.get_config_path(profile)?;
                 debug!("Saving config to {:?}", config_path);
                 service.save_config_to(&config_path).await?;
                 outro(&format!("{} Config successfully set", "✔".green()));
             }
             ConfigCommand::Reset => {
-                let config_path = self.get_config_path()?;
+                let config_path = self.get_config_path(profile)?;
                 let service = AutocommitService::new(&config_path).await?;
                 debug!("Saving config to {:?}", config_path);
                 service.save_config_to(&config_path).await?;
                 outro(&format!("{} Config successfully reset", "✔".green()));
             }
-            ConfigCommand::Env { shell } => {
-                let config = self.get_service().await?;
-                let config_values =
-                    config.get_config_values(&ConfigKey::iter().collect::<Vec<_>>());
+            ConfigCommand::Env {
+                shell,
+                show_secrets,
+            } => {
+                let config = self.get_service(profile).await?;
+                let config_values = printable_values(
+                    &config,
+                    &ConfigKey::iter().collect::<Vec<_>>(),
+                    *show_secrets,
+                );
 
                 match shell.as_deref() {
                     Some("bash") => {
@@ -148,21 +179,45 @@ impl ConfigCommand {
                     }
                 }
             }
+            ConfigCommand::Profile(profile_command) => {
+                let default_config_path = Self::default_config_path()
+                    .ok_or_else(|| anyhow!("Could not determine config path"))?;
+                profile_command.run(&default_config_path, profile).await?;
+            }
+            ConfigCommand::Doctor => {
+                run_doctor(&self.get_config_path(profile)?).await?;
+            }
         }
 
         Ok(())
     }
 
-    fn get_config_path(&self) -> anyhow::Result<PathBuf> {
+    fn get_config_path(&self, profile: Option<&str>) -> anyhow::Result<PathBuf> {
         let config_path = match self {
             ConfigCommand::Get { config_path, .. } => config_path.clone(),
             ConfigCommand::Set { config_path, .. } => config_path.clone(),
             ConfigCommand::Reset => None,
             ConfigCommand::Env { .. } => None,
+            ConfigCommand::Profile(_) => None,
+            ConfigCommand::Doctor => None,
         };
-        let default_config_path = Self::default_config_path();
-        let config_path = config_path.or(default_config_path);
-        config_path.ok_or_else(|| anyhow!("Could not determine config path"))
+        if let Some(config_path) = config_path {
+            return Ok(config_path);
+        }
+
+        if let Some(profile) = profile {
+            let path = profile_path(profile)?;
+            if !path.exists() {
+                return Err(anyhow!(
+                    "Profile '{}' not found, create it with `autocommit config profile create {}`",
+                    profile,
+                    profile
+                ));
+            }
+            return Ok(path);
+        }
+
+        Self::default_config_path().ok_or_else(|| anyhow!("Could not determine config path"))
     }
 
     fn default_config_path() -> Option<PathBuf> {
@@ -173,11 +228,106 @@ impl ConfigCommand {
     }
 }
 
-pub async fn get_service() -> Result<AutocommitService> {
+/// The keys `get` prints, all of them when none are given. Fails on the
+/// first unknown key, listing the valid ones.
+fn parse_get_keys(keys: &[String]) -> Result<Vec<ConfigKey>> {
+    if keys.is_empty() {
+        return Ok(ConfigKey::iter().collect());
+    }
+    keys.iter()
+        .map(|key| {
+            ConfigKey::from_str(key).map_err(|_| {
+                anyhow!(
+                    "Unsupported config key: {}. Valid keys are: {}",
+                    key,
+                    ConfigKey::iter()
+                        .map(|key| key.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+/// The values of `keys` as printed by `get` and `env`, with sensitive ones
+/// masked unless `show_secrets` is set.
+fn printable_values(
+    service: &AutocommitService,
+    keys: &[ConfigKey],
+    show_secrets: bool,
+) -> Vec<(String, String)> {
+    service
+        .get_config_values(keys)
+        .into_iter()
+        .zip(keys)
+        .map(|((name, value), key)| {
+            if key.is_sensitive() && !show_secrets {
+                (name, key.mask(&value))
+            } else {
+                (name, value)
+            }
+        })
+        .collect()
+}
+
+/// Makes sure an OpenAI API key is available before any work is done. When
+/// running in a terminal, offers to enter the key right away.
+pub async fn ensure_api_key(service: &mut AutocommitService, profile: Option<&str>) -> Result<()> {
+    let Err(error) = service.get_config().get_oai_config() else {
+        return Ok(());
+    };
+    if !std::io::stdin().is_terminal() {
+        return Err(error);
+    }
+
+    let provider = service
+        .get_config()
+        .config_data
+        .api_provider
+        .get_value_ref()
+        .display_name();
+    outro(&format!(
+        "{} No {} API key is configured",
+        "⚠".yellow(),
+        provider
+    ));
+    let api_key = Password::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "Enter your {} API key (leave empty to abort)",
+            provider
+        ))
+        .allow_empty_password(true)
+        .interact()?;
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        return Err(error);
+    }
+    service.update_config(&ConfigKey::OpenAiApiKey, api_key)?;
+
+    let config_path = ConfigCommand::Reset.get_config_path(profile)?;
+    let save_key = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("Save the key to {}?", config_path.display()))
+        .default(true)
+        .interact_opt()?
+        .unwrap_or(false);
+    if save_key {
+        // Save only the key, without any values from a remote config
+        let mut local_service = AutocommitService::new(&config_path).await?;
+        local_service.update_config(&ConfigKey::OpenAiApiKey, api_key)?;
+        debug!("Saving config to {:?}", config_path);
+        local_service.save_config_to(&config_path).await?;
+        outro(&format!("{} API key saved", "✔".green()));
+    }
+    Ok(())
+}
+
+pub async fn get_service(profile: Option<&str>) -> Result<AutocommitService> {
     let config_command = ConfigCommand::Get {
         keys: vec![],
         config_path: None,
+        show_secrets: false,
     };
     info!("Getting config");
-    config_command.get_service().await
+    config_command.get_service(profile).await
 }
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Context, Result};
+use colored::Colorize;
+use log::debug;
+use structopt::StructOpt;
+
+use crate::utils::outro;
+
+use super::config_service::AutocommitService;
+
+#[derive(Debug, StructOpt)]
+pub enum ProfileCommand {
+    #[structopt(name = "list")]
+    List,
+    #[structopt(name = "create")]
+    Create {
+        #[structopt(help = "Name of the profile to create")]
+        name: String,
+    },
+    #[structopt(name = "delete")]
+    Delete {
+        #[structopt(help = "Name of the profile to delete")]
+        name: String,
+    },
+}
+
+impl ProfileCommand {
+    pub async fn run(
+        &self,
+        default_config_path: &PathBuf,
+        active_profile: Option<&str>,
+    ) -> Result<()> {
+        match self {
+            ProfileCommand::List => {
+                let profiles = list_profiles().await?;
+                if profiles.is_empty() {
+                    outro("No profiles found");
+                }
+                for profile in profiles {
+                    if Some(profile.as_str()) == active_profile {
+                        println!("{} {}", "*".green(), profile.green().bold());
+                    } else {
+                        println!("  {}", profile);
+                    }
+                }
+            }
+            ProfileCommand::Create { name } => {
+                let path = profile_path(name)?;
+                if path.exists() {
+                    bail!("Profile '{}' already exists", name);
+                }
+
+                let profiles_dir = profiles_dir()?;
+                tokio::fs::create_dir_all(&profiles_dir)
+                    .await
+                    .with_context(|| {
+                        format!("Failed to create directory: {}", profiles_dir.display())
+                    })?;
+
+                let service = AutocommitService::new(default_config_path).await?;
+                debug!("Saving profile to {:?}", path);
+                service.save_config_to(&path).await?;
+                outro(&format!(
+                    "{} Profile '{}' created at {}",
+                    "✔".green(),
+                    name,
+                    path.display()
+                ));
+            }
+            ProfileCommand::Delete { name } => {
+                let path = profile_path(name)?;
+                tokio::fs::remove_file(&path)
+                    .await
+                    .with_context(|| format!("Failed to delete profile '{}'", name))?;
+                outro(&format!("{} Profile '{}' deleted", "✔".green(), name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn profiles_dir() -> Result<PathBuf> {
+    dirs::home_dir()
+        .map(|mut path| {
+            path.push(".autocommit.profiles");
+            path
+        })
+        .ok_or_else(|| anyhow!("Could not determine profiles directory"))
+}
+
+pub fn profile_path(name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        bail!("Invalid profile name: {}", name);
+    }
+
+    let mut path = profiles_dir()?;
+    path.push(format!("{}.toml", name));
+    Ok(path)
+}
+
+async fn list_profiles() -> Result<Vec<String>> {
+    let profiles_dir = profiles_dir()?;
+    if !profiles_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut entries = tokio::fs::read_dir(&profiles_dir)
+        .await
+        .with_context(|| format!("Failed to read directory: {}", profiles_dir.display()))?;
+
+    let mut profiles = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            if let Some(name) = path.file_stem() {
+                profiles.push(name.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    profiles.sort();
+    Ok(profiles)
+}
@@ -0,0 +1,111 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::http_client;
+
+/// How long a fetched remote config is used before fetching it again.
+const REMOTE_CONFIG_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// A remote config as last fetched, stored in `~/.autocommit.remote`.
+#[derive(Debug, Deserialize, Serialize)]
+struct RemoteConfigCache {
+    url: String,
+    fetched_at: u64,
+    contents: String,
+}
+
+impl RemoteConfigCache {
+    fn path() -> anyhow::Result<PathBuf> {
+        dirs::home_dir()
+            .map(|mut path| {
+                path.push(".autocommit.remote");
+                path
+            })
+            .ok_or_else(|| anyhow!("Could not determine remote config cache path"))
+    }
+
+    async fn load(url: &str) -> Option<Self> {
+        let path = Self::path().ok()?;
+        let contents = tokio::fs::read_to_string(&path).await.ok()?;
+        toml::from_str::<Self>(&contents)
+            .ok()
+            .filter(|cache| cache.url == url)
+    }
+
+    async fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path()?;
+        let contents = toml::to_string(self).context("Failed to serialize remote config cache")?;
+        tokio::fs::write(&path, contents)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn is_fresh(&self) -> bool {
+        now().saturating_sub(self.fetched_at) < REMOTE_CONFIG_TTL.as_secs()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+async fn fetch(url: &str, proxy: Option<&str>) -> anyhow::Result<String> {
+    let client = http_client(url, proxy)?;
+    let mut response = tokio::time::timeout(Duration::from_secs(5), client.get(url))
+        .await
+        .map_err(|_| anyhow!("Timed out"))?
+        .map_err(|e| anyhow!("{}", e))?;
+    if !response.status().is_success() {
+        return Err(anyhow!("Unexpected HTTP response: {}", response.status()));
+    }
+    response.body_string().await.map_err(|e| anyhow!("{}", e))
+}
+
+/// Returns the contents of the remote config at `url`, fetching it again
+/// once the cached copy is older than the TTL. Falls back to the cached copy
+/// when the fetch fails, so autocommit keeps working offline. Fetches
+/// through `proxy`, like the API requests.
+pub async fn load_remote_config(url: &str, proxy: Option<&str>) -> Option<String> {
+    // Over plain http, anyone on the network path could change the config
+    if !url.starts_with("https://") {
+        warn!(
+            "Ignoring remote config from {}: only https URLs are allowed",
+            url
+        );
+        return None;
+    }
+
+    let cache = RemoteConfigCache::load(url).await;
+    if let Some(cache) = cache.as_ref().filter(|cache| cache.is_fresh()) {
+        debug!("Using cached remote config from {}", url);
+        return Some(cache.contents.clone());
+    }
+
+    debug!("Fetching remote config from {}", url);
+    match fetch(url, proxy).await {
+        Ok(contents) => {
+            let cache = RemoteConfigCache {
+                url: url.to_owned(),
+                fetched_at: now(),
+                contents,
+            };
+            if let Err(e) = cache.save().await {
+                warn!("Failed to cache remote config: {}", e);
+            }
+            Some(cache.contents)
+        }
+        Err(e) => {
+            warn!("Failed to fetch remote config from {}: {}", url, e);
+            cache.map(|cache| cache.contents)
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::anyhow;
+use dialoguer::{theme::ColorfulTheme, Confirm};
+
+use crate::utils::assume_yes;
+
+use super::AutocommitConfig;
+
+/// Whether the user already agreed to send data during this run.
+pub(super) static SEND_CONFIRMED: AtomicBool = AtomicBool::new(false);
+
+/// Asks before the first request of the run sends `payload_bytes` bytes to
+/// the API host, when `confirm_before_send` is enabled. As the prompt
+/// defaults to no, `--yes` doesn't count as agreeing.
+pub fn confirm_send(config: &AutocommitConfig, payload_bytes: usize) -> anyhow::Result<()> {
+    if !*config.config_data.confirm_before_send.get_value_ref()
+        || SEND_CONFIRMED.load(Ordering::SeqCst)
+    {
+        return Ok(());
+    }
+
+    let api_host = config.config_data.api_host.get_value_ref();
+    if assume_yes() {
+        return Err(anyhow!(
+            "confirm_before_send is enabled, so nothing was sent to {} without asking. \
+            Run without --yes to confirm, or set AUTOCOMMIT_CONFIRM_BEFORE_SEND=false to allow sending in this run",
+            api_host
+        ));
+    }
+    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "About to send ~{} bytes of code and diff to {}. Continue?",
+            payload_bytes, api_host
+        ))
+        .default(false)
+        .interact_opt()?
+        .unwrap_or(false);
+    if !confirmed {
+        return Err(anyhow!("Cancelled, nothing was sent to {}", api_host));
+    }
+
+    SEND_CONFIRMED.store(true, Ordering::SeqCst);
+    Ok(())
+}
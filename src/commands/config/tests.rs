@@ -0,0 +1,402 @@
+#[test]
+fn test_merge_remote_config() -> anyhow::Result<()> {
+    use super::{config_keys::ConfigKey, AutocommitConfig};
+
+    let mut config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = true
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+open_ai_api_key = "sk-local"
+api_host = "https://api.openai.com"
+"#,
+    )?;
+    config.merge_remote(
+        r#"
+[config]
+description = true
+emoji = false
+name = "Org Bot"
+open_ai_model = "gpt-4"
+fallback_models = ["gpt-3.5-turbo", "gpt-3.5-turbo-0301"]
+open_ai_api_key = "sk-remote"
+api_host = "https://attacker.example"
+proxy_url = "http://attacker.example:8080"
+message_filter_command = "sh -c evil"
+confirm_before_send = true
+unknown_key = 1
+"#,
+    )?;
+
+    // Defaults are replaced by the remote values
+    assert_eq!(
+        config.get_config_value(&ConfigKey::DescriptionEnabled),
+        "true"
+    );
+    assert_eq!(config.get_config_value(&ConfigKey::OpenAiModel), "gpt-4");
+    assert_eq!(
+        config.get_config_value(&ConfigKey::FallbackModels),
+        "gpt-3.5-turbo,gpt-3.5-turbo-0301"
+    );
+    // Local changes and the author identity are kept
+    assert_eq!(config.get_config_value(&ConfigKey::EmojiEnabled), "true");
+    assert_eq!(config.get_config_value(&ConfigKey::Name), "Jane Doe");
+    // Keys only the user may set are never taken from the remote config
+    assert_eq!(
+        config.get_config_value(&ConfigKey::ApiHost),
+        "https://api.openai.com"
+    );
+    assert_eq!(config.get_config_value(&ConfigKey::ProxyUrl), "");
+    assert_eq!(
+        config.get_config_value(&ConfigKey::MessageFilterCommand),
+        ""
+    );
+    assert_eq!(
+        config.get_config_value(&ConfigKey::ConfirmBeforeSend),
+        "false"
+    );
+
+    assert!(config.merge_remote("description = true").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_merge_remote_keeps_keys_set_to_defaults() -> anyhow::Result<()> {
+    use super::{config_keys::ConfigKey, AutocommitConfig};
+
+    let mut config: AutocommitConfig = toml::from_str(
+        r#"
+set_keys = ["description"]
+
+[config]
+description = false
+emoji = false
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "https://api.openai.com"
+"#,
+    )?;
+    config.set_config(&ConfigKey::FewShot, "true")?;
+    config.merge_remote("[config]\ndescription = true\nfew_shot = false\nemoji = true")?;
+
+    assert_eq!(
+        config.get_config_value(&ConfigKey::DescriptionEnabled),
+        "false"
+    );
+    assert_eq!(config.get_config_value(&ConfigKey::FewShot), "true");
+    assert_eq!(config.get_config_value(&ConfigKey::EmojiEnabled), "true");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_remote_config_requires_https() {
+    use super::remote_config::load_remote_config;
+
+    assert!(
+        load_remote_config("http://example.com/autocommit.toml", None)
+            .await
+            .is_none()
+    );
+}
+
+#[test]
+fn test_common_dir() {
+    use super::directory_config::common_dir;
+    use std::path::PathBuf;
+
+    let files = |files: &[&str]| files.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        common_dir(&files(&["packages/a/src/lib.rs", "packages/a/Cargo.toml"])),
+        PathBuf::from("packages/a")
+    );
+    assert_eq!(
+        common_dir(&files(&["packages/a/lib.rs", "packages/b/lib.rs"])),
+        PathBuf::from("packages")
+    );
+    assert_eq!(
+        common_dir(&files(&["packages/a/lib.rs", "Readme.md"])),
+        PathBuf::new()
+    );
+    assert_eq!(common_dir(&[]), PathBuf::new());
+}
+
+#[test]
+fn test_find_directory_configs() -> anyhow::Result<()> {
+    use super::directory_config::find_directory_configs;
+    use std::path::Path;
+
+    let root = tempfile::tempdir()?;
+    std::fs::create_dir_all(root.path().join("packages/a/src"))?;
+    std::fs::write(root.path().join(".autocommit.toml"), "")?;
+    std::fs::write(root.path().join("packages/a/.autocommit.toml"), "")?;
+
+    assert_eq!(
+        find_directory_configs(root.path(), Path::new("packages/a/src")),
+        vec![
+            root.path().join(".autocommit.toml"),
+            root.path().join("packages/a/.autocommit.toml")
+        ]
+    );
+    assert_eq!(
+        find_directory_configs(root.path(), Path::new("packages")),
+        vec![root.path().join(".autocommit.toml")]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_apply_overrides() -> anyhow::Result<()> {
+    use super::{config_keys::ConfigKey, AutocommitConfig};
+
+    let mut config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = true
+emoji = true
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+open_ai_api_key = "sk-local"
+api_host = "https://api.openai.com"
+"#,
+    )?;
+    assert_eq!(config.get_config_value(&ConfigKey::MaxMessageBytes), "8192");
+    assert_eq!(
+        config.get_config_value(&ConfigKey::RequestTimeoutSecs),
+        "30"
+    );
+
+    config.apply_overrides("[config]\nemoji = false\nstop_sequences = [\"END\"]")?;
+    assert_eq!(config.get_config_value(&ConfigKey::EmojiEnabled), "false");
+    assert_eq!(
+        config.get_config_value(&ConfigKey::DescriptionEnabled),
+        "true"
+    );
+    assert_eq!(config.get_config_value(&ConfigKey::StopSequences), "END");
+
+    assert!(config.apply_overrides("[config]\nunknown = 1").is_err());
+
+    for key_value in [
+        "open_ai_api_key = \"sk-repo\"",
+        "api_host = \"https://attacker.example\"",
+        "api_provider = \"ollama\"",
+        "proxy_url = \"http://attacker.example:8080\"",
+        "confirm_before_send = false",
+        "remote_config_url = \"https://attacker.example/config.toml\"",
+        "message_filter_command = \"sh -c evil\"",
+    ] {
+        assert!(config
+            .apply_overrides(&format!("[config]\n{}", key_value))
+            .is_err());
+    }
+    assert_eq!(
+        config.get_config_value(&ConfigKey::OpenAiApiKey),
+        "sk-local"
+    );
+    assert_eq!(
+        config.get_config_value(&ConfigKey::ApiHost),
+        "https://api.openai.com"
+    );
+    assert_eq!(config.get_config_value(&ConfigKey::ProxyUrl), "");
+    Ok(())
+}
+
+#[test]
+fn test_azure_requires_deployment() -> anyhow::Result<()> {
+    use super::{config_keys::ConfigKey, AutocommitConfig};
+
+    let mut config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = false
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+open_ai_api_key = "azure-key"
+api_host = "https://example.openai.azure.com"
+api_provider = "azure"
+"#,
+    )?;
+    let error = config.get_oai_config().err().unwrap();
+    assert!(error.to_string().contains("deployment"));
+
+    config.update_config(&ConfigKey::Deployment, "gpt-4o-prod")?;
+    assert!(config.get_oai_config().is_ok());
+    assert!(config
+        .update_config(&ConfigKey::ApiProvider, "cohere")
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_provider_alias() -> anyhow::Result<()> {
+    use super::{config_keys::ConfigKey, AutocommitConfig};
+    use std::str::FromStr;
+
+    let config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = false
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "https://api.anthropic.com"
+provider = "anthropic"
+"#,
+    )?;
+    assert_eq!(
+        config.get_config_value(&ConfigKey::ApiProvider),
+        "anthropic"
+    );
+    assert_eq!(ConfigKey::from_str("provider")?, ConfigKey::ApiProvider);
+    assert_eq!(ConfigKey::ApiProvider.to_string(), "api_provider");
+    let error = config.get_oai_config().err().unwrap();
+    assert!(error
+        .to_string()
+        .starts_with("Please set your Anthropic API key"));
+
+    Ok(())
+}
+
+#[test]
+fn test_ollama_needs_no_api_key() -> anyhow::Result<()> {
+    use super::{config_keys::ConfigKey, AutocommitConfig};
+
+    let mut config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = false
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "http://localhost:11434"
+"#,
+    )?;
+    assert!(config.get_oai_config().is_err());
+
+    config.update_config(&ConfigKey::ApiProvider, "ollama")?;
+    assert!(config.get_oai_config().is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn test_sampling_keys_are_validated() -> anyhow::Result<()> {
+    use super::{config_keys::ConfigKey, AutocommitConfig};
+
+    let mut config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = false
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "https://api.openai.com"
+"#,
+    )?;
+    assert_eq!(config.get_config_value(&ConfigKey::Temperature), "0.5");
+    assert_eq!(config.get_config_value(&ConfigKey::TopP), "0.1");
+    assert_eq!(config.get_config_value(&ConfigKey::MaxTokens), "");
+    assert_eq!(config.get_config_value(&ConfigKey::MaxSubjectLength), "72");
+    assert_eq!(config.get_config_value(&ConfigKey::MaxDiffTokens), "12000");
+    assert_eq!(
+        config.get_config_value(&ConfigKey::DiffExcludeGlobs),
+        "*.lock"
+    );
+
+    config.update_config(&ConfigKey::Temperature, "0")?;
+    config.update_config(&ConfigKey::TopP, "")?;
+    config.update_config(&ConfigKey::MaxTokens, "200")?;
+    assert_eq!(config.get_config_value(&ConfigKey::Temperature), "0");
+    assert_eq!(config.get_config_value(&ConfigKey::TopP), "");
+
+    assert!(config
+        .update_config(&ConfigKey::Temperature, "2.5")
+        .is_err());
+    config.update_config(&ConfigKey::Temperature, "2")?;
+    assert!(config.update_config(&ConfigKey::TopP, "1.1").is_err());
+    assert_eq!(config.get_config_value(&ConfigKey::TopP), "");
+    assert!(config.update_config(&ConfigKey::MaxTokens, "-1").is_err());
+
+    config.update_config(&ConfigKey::DiffContextLines, "0")?;
+    assert_eq!(config.get_config_value(&ConfigKey::DiffContextLines), "0");
+    assert!(config
+        .update_config(&ConfigKey::DiffContextLines, "-1")
+        .is_err());
+    assert!(config
+        .update_config(&ConfigKey::DiffContextLines, "1001")
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_confirm_send() -> anyhow::Result<()> {
+    use std::sync::atomic::Ordering;
+
+    use super::{
+        config_keys::ConfigKey, confirm_send, send_confirmation::SEND_CONFIRMED, AutocommitConfig,
+    };
+    use crate::utils::tests::AssumeYes;
+
+    let mut config: AutocommitConfig = toml::from_str(
+        r#"
+[config]
+description = false
+emoji = false
+language = "en"
+name = "Jane Doe"
+email = "jane@example.com"
+api_host = "https://api.openai.com"
+"#,
+    )?;
+    let _assume_yes = AssumeYes::on();
+
+    // Nothing to ask when the setting is off
+    confirm_send(&config, 100)?;
+
+    // --yes doesn't count as agreeing, so without a prompt nothing is sent
+    config.update_config(&ConfigKey::ConfirmBeforeSend, "true")?;
+    let error = confirm_send(&config, 100).unwrap_err();
+    assert!(error.to_string().contains("nothing was sent"));
+    assert!(error
+        .to_string()
+        .contains("AUTOCOMMIT_CONFIRM_BEFORE_SEND=false"));
+
+    // Once agreed to, later requests of the run aren't asked about again
+    SEND_CONFIRMED.store(true, Ordering::SeqCst);
+    let result = confirm_send(&config, 100);
+    SEND_CONFIRMED.store(false, Ordering::SeqCst);
+    result?;
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_get_keys_reports_unknown_keys() -> anyhow::Result<()> {
+    use super::{config_keys::ConfigKey, parse_get_keys};
+    use strum::IntoEnumIterator;
+
+    assert_eq!(parse_get_keys(&[])?, ConfigKey::iter().collect::<Vec<_>>());
+    assert_eq!(
+        parse_get_keys(&["emoji".to_owned(), "language".to_owned()])?,
+        vec![ConfigKey::EmojiEnabled, ConfigKey::Language]
+    );
+
+    let error = parse_get_keys(&["emoji".to_owned(), "emojis".to_owned()]).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("Unsupported config key: emojis. Valid keys are: "));
+    assert!(error.to_string().contains("language"));
+
+    Ok(())
+}
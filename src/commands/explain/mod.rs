@@ -0,0 +1,62 @@
+use log::info;
+use structopt::StructOpt;
+
+use crate::{
+    git::GitRepository,
+    utils::{generate_message, outro, spinner, Message, MessageRole},
+};
+
+use super::config::{confirm_send, AutocommitConfig};
+
+#[derive(Debug, StructOpt)]
+pub struct ExplainCommand {
+    #[structopt(default_value = "HEAD", help = "Commit to explain")]
+    rev: String,
+}
+
+impl ExplainCommand {
+    pub async fn run(&self, config: &AutocommitConfig) -> anyhow::Result<()> {
+        GitRepository::assert_git_repo().await?;
+
+        let message = GitRepository::get_commit_message(&self.rev)?;
+        let diff = GitRepository::get_commit_diff(&self.rev)?;
+
+        let prompt = vec![
+            Message::new(
+                MessageRole::System,
+                String::from(
+                    "You are a senior developer helping a teammate understand an existing commit during code review or onboarding. \
+                    From the commit message and diff the user provides, explain in plain English what the commit does and why it was likely made. \
+                    Point out the important parts of the change and any side effects worth knowing about. \
+                    Do not write a commit message; respond with the explanation only.",
+                ),
+            ),
+            Message::new(
+                MessageRole::User,
+                format!("Commit message:\n{}\n\nDiff:\n{}", message, diff),
+            ),
+        ];
+
+        let oai_config = config.get_oai_config()?;
+        let model = config
+            .config_data
+            .open_ai_model
+            .get_value_ref()
+            .get_inner_value();
+        let fallback_models = config
+            .config_data
+            .fallback_models
+            .get_value_ref()
+            .get_inner_value();
+
+        confirm_send(config, message.len() + diff.len())?;
+        let mut explain_spinner = spinner();
+        explain_spinner.start(&format!("Explaining {}...", self.rev));
+        let explanation = generate_message(&prompt, oai_config, &model, &fallback_models).await?;
+        explain_spinner.stop("📝 Explanation generated successfully");
+
+        outro(&explanation);
+        info!("Commit explained");
+        Ok(())
+    }
+}
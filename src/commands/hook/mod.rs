@@ -0,0 +1,155 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use colored::Colorize;
+use git2::Repository;
+use log::info;
+use structopt::StructOpt;
+
+use crate::utils::outro;
+
+#[cfg(test)]
+mod tests;
+
+const HOOK_NAME: &str = "prepare-commit-msg";
+/// Where a hook that was there before is kept while autocommit's is installed.
+const BACKUP_NAME: &str = "prepare-commit-msg.autocommit-backup";
+/// Identifies hooks written by autocommit, so other hooks are never removed.
+const HOOK_MARKER: &str = "# Installed by autocommit";
+
+const HOOK_SCRIPT: &str = r#"#!/bin/sh
+# Installed by autocommit
+# Fills in the commit message from the staged changes when `git commit` is
+# run without one. Remove it with `autocommit hook uninstall`.
+
+# Run the hook that was here before autocommit's first
+backup="$0.autocommit-backup"
+if [ -x "$backup" ]; then
+    "$backup" "$@" || exit $?
+fi
+
+# Keep messages given with -m or -F, templates, merges, squashes and amends
+[ -z "$2" ] || exit 0
+
+message=$(autocommit commit --print-only --yes) || exit 0
+[ -n "$message" ] || exit 0
+{ printf '%s\n' "$message"; cat "$1"; } > "$1.autocommit" && mv "$1.autocommit" "$1"
+"#;
+
+#[derive(Debug, StructOpt)]
+pub enum HookCommand {
+    #[structopt(
+        name = "install",
+        about = "Install a prepare-commit-msg hook that fills in the message for `git commit`"
+    )]
+    Install,
+    #[structopt(
+        name = "uninstall",
+        about = "Remove the hook and restore the one it replaced"
+    )]
+    Uninstall,
+}
+
+impl HookCommand {
+    pub async fn run(&self) -> anyhow::Result<()> {
+        let hooks_dir = hooks_dir()?;
+        let message = match self {
+            HookCommand::Install => install(&hooks_dir)?,
+            HookCommand::Uninstall => uninstall(&hooks_dir)?,
+        };
+        outro(&format!("{} {}", "✔".green(), message));
+        info!("Hook command finished");
+        Ok(())
+    }
+}
+
+/// Where git runs hooks from: `core.hooksPath` when it's set, relative to
+/// the working tree like git resolves it, or the repository's hooks directory.
+fn hooks_dir() -> anyhow::Result<PathBuf> {
+    let repo = Repository::open_from_env().map_err(|err| {
+        anyhow!(
+            "The current working directory is not a Git repository: {}",
+            err
+        )
+    })?;
+    let hooks_path = repo
+        .config()
+        .and_then(|config| config.get_path("core.hooksPath"))
+        .ok();
+    Ok(match hooks_path {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repo.workdir().unwrap_or_else(|| repo.path()).join(path),
+        None => repo.path().join("hooks"),
+    })
+}
+
+fn is_autocommit_hook(path: &Path) -> anyhow::Result<bool> {
+    let contents =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(String::from_utf8_lossy(&contents).contains(HOOK_MARKER))
+}
+
+/// Writes the hook, moving an existing hook aside so it keeps running first.
+fn install(hooks_dir: &Path) -> anyhow::Result<String> {
+    let hook_path = hooks_dir.join(HOOK_NAME);
+    let backup_path = hooks_dir.join(BACKUP_NAME);
+    let mut message = format!("Installed the hook at {}", hook_path.display());
+    if hook_path.exists() {
+        if is_autocommit_hook(&hook_path)? {
+            message = format!("Updated the hook at {}", hook_path.display());
+        } else if backup_path.exists() {
+            return Err(anyhow!(
+                "Both {} and {} already exist, move one of them away first",
+                hook_path.display(),
+                backup_path.display()
+            ));
+        } else {
+            std::fs::rename(&hook_path, &backup_path)
+                .with_context(|| format!("Failed to back up {}", hook_path.display()))?;
+            message = format!(
+                "Installed the hook at {}. The existing hook was moved to {} and still runs first",
+                hook_path.display(),
+                backup_path.display()
+            );
+        }
+    }
+
+    std::fs::create_dir_all(hooks_dir)
+        .with_context(|| format!("Failed to create {}", hooks_dir.display()))?;
+    std::fs::write(&hook_path, HOOK_SCRIPT)
+        .with_context(|| format!("Failed to write {}", hook_path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to make {} executable", hook_path.display()))?;
+    }
+    Ok(message)
+}
+
+/// Removes autocommit's hook and puts back the hook it replaced, if any.
+fn uninstall(hooks_dir: &Path) -> anyhow::Result<String> {
+    let hook_path = hooks_dir.join(HOOK_NAME);
+    let backup_path = hooks_dir.join(BACKUP_NAME);
+    if !hook_path.exists() {
+        return Err(anyhow!("No {} hook is installed", HOOK_NAME));
+    }
+    if !is_autocommit_hook(&hook_path)? {
+        return Err(anyhow!(
+            "{} wasn't installed by autocommit, leaving it alone",
+            hook_path.display()
+        ));
+    }
+
+    std::fs::remove_file(&hook_path)
+        .with_context(|| format!("Failed to remove {}", hook_path.display()))?;
+    if backup_path.exists() {
+        std::fs::rename(&backup_path, &hook_path)
+            .with_context(|| format!("Failed to restore {}", backup_path.display()))?;
+        return Ok(format!(
+            "Removed the hook and restored the previous one at {}",
+            hook_path.display()
+        ));
+    }
+    Ok(format!("Removed the hook at {}", hook_path.display()))
+}
@@ -0,0 +1,66 @@
+#[test]
+fn test_install_and_uninstall_keep_existing_hook() -> anyhow::Result<()> {
+    use super::{install, uninstall, BACKUP_NAME, HOOK_NAME, HOOK_SCRIPT};
+
+    let hooks_dir = tempfile::tempdir()?;
+    let hook_path = hooks_dir.path().join(HOOK_NAME);
+    let backup_path = hooks_dir.path().join(BACKUP_NAME);
+    std::fs::write(&hook_path, "#!/bin/sh\necho mine\n")?;
+
+    install(hooks_dir.path())?;
+    assert_eq!(std::fs::read_to_string(&hook_path)?, HOOK_SCRIPT);
+    assert_eq!(
+        std::fs::read_to_string(&backup_path)?,
+        "#!/bin/sh\necho mine\n"
+    );
+    // Installing again updates the hook without touching the backup
+    install(hooks_dir.path())?;
+    assert!(backup_path.exists());
+
+    uninstall(hooks_dir.path())?;
+    assert_eq!(
+        std::fs::read_to_string(&hook_path)?,
+        "#!/bin/sh\necho mine\n"
+    );
+    assert!(!backup_path.exists());
+
+    // A hook autocommit didn't write is never removed
+    assert!(uninstall(hooks_dir.path()).is_err());
+    std::fs::write(&backup_path, "#!/bin/sh\n")?;
+    assert!(install(hooks_dir.path()).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_uninstall_without_backup_removes_hook() -> anyhow::Result<()> {
+    use super::{install, uninstall, HOOK_NAME};
+
+    let hooks_dir = tempfile::tempdir()?;
+    install(hooks_dir.path())?;
+    uninstall(hooks_dir.path())?;
+    assert!(!hooks_dir.path().join(HOOK_NAME).exists());
+    assert!(uninstall(hooks_dir.path()).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hooks_dir_follows_core_hooks_path() -> anyhow::Result<()> {
+    use super::hooks_dir;
+    use crate::git::tests::TestRepo;
+
+    let repo = TestRepo::new().await?;
+    let git_repo = git2::Repository::open_from_env()?;
+    assert_eq!(hooks_dir()?, git_repo.path().join("hooks"));
+
+    repo.git(&["config", "core.hooksPath", ".githooks"]).await?;
+    let workdir = git_repo.workdir().expect("not a bare repository");
+    assert_eq!(hooks_dir()?, workdir.join(".githooks"));
+
+    let shared_hooks = tempfile::tempdir()?;
+    let shared_hooks_path = shared_hooks.path().to_string_lossy();
+    repo.git(&["config", "core.hooksPath", &shared_hooks_path])
+        .await?;
+    assert_eq!(hooks_dir()?, shared_hooks.path());
+
+    Ok(())
+}
@@ -1,9 +1,14 @@
 use structopt::StructOpt;
 
+mod check;
 mod commit;
 mod config;
+mod explain;
+mod hook;
+mod models;
+mod release_notes;
 
-pub use config::get_service;
+pub use config::{ensure_api_key, get_service};
 
 #[derive(Debug, StructOpt)]
 pub enum Command {
@@ -11,4 +16,23 @@ pub enum Command {
     ConfigCommand(config::ConfigCommand),
     #[structopt(name = "commit")]
     CommitCommand(commit::CommitCommand),
+    #[structopt(name = "release-notes")]
+    ReleaseNotes(release_notes::ReleaseNotesCommand),
+    #[structopt(
+        name = "explain",
+        about = "Explain in plain English what a commit does"
+    )]
+    Explain(explain::ExplainCommand),
+    #[structopt(
+        name = "check",
+        about = "Check a commit message against the configured rules, e.g. from a commit-msg hook"
+    )]
+    Check(check::CheckCommand),
+    #[structopt(name = "models", about = "List the models your API key can access")]
+    Models(models::ModelsCommand),
+    #[structopt(
+        name = "hook",
+        about = "Install or remove a git hook that writes the message for `git commit`"
+    )]
+    Hook(hook::HookCommand),
 }
@@ -0,0 +1,55 @@
+use std::str::FromStr;
+
+use colored::Colorize;
+use log::{info, warn};
+use structopt::StructOpt;
+
+use crate::utils::{list_models, outro, spinner, OAIModel, SUPPORTED_MODELS};
+
+use super::config::AutocommitConfig;
+
+#[derive(Debug, StructOpt)]
+pub struct ModelsCommand {}
+
+impl ModelsCommand {
+    pub async fn run(&self, config: &AutocommitConfig) -> anyhow::Result<()> {
+        let oai_config = config.get_oai_config()?;
+
+        let mut models_spinner = spinner();
+        models_spinner.start("Fetching the available models...");
+        let models = match list_models(&oai_config).await {
+            Ok(models) => {
+                models_spinner.stop("📋 Models fetched successfully");
+                models
+            }
+            Err(e) => {
+                models_spinner.stop(&format!(
+                    "{} Failed to fetch the models, showing the supported ones instead",
+                    "⚠".yellow()
+                ));
+                warn!("Failed to list models: {}", e);
+                SUPPORTED_MODELS
+                    .iter()
+                    .map(|model| model.to_string())
+                    .collect()
+            }
+        };
+
+        let lines = models
+            .iter()
+            .map(|model| {
+                if OAIModel::from_str(model).is_ok_and(|model| model.is_known()) {
+                    format!("{} {}", "✔".green(), model.green())
+                } else {
+                    format!("  {}", model.dimmed())
+                }
+            })
+            .collect::<Vec<_>>();
+        outro(&format!(
+            "{}\n\nModels marked with ✔ are known to work as open_ai_model or in fallback_models.",
+            lines.join("\n")
+        ));
+        info!("Listed {} models", models.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use colored::Colorize;
+use lazy_static::lazy_static;
+use log::info;
+use regex::Regex;
+use structopt::StructOpt;
+
+use crate::{
+    git::GitRepository,
+    utils::{generate_message, outro, spinner, Message, MessageRole},
+};
+
+use super::config::{confirm_send, AutocommitConfig};
+
+#[cfg(test)]
+mod tests;
+
+lazy_static! {
+    static ref CONVENTIONAL_TYPE: Regex = Regex::new(r"^(\w+)(\([^)]*\))?!?:").unwrap();
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ReleaseNotesCommand {
+    #[structopt(long = "since-tag", help = "Tag or revision to start from (exclusive)")]
+    from_tag: String,
+
+    #[structopt(
+        long = "to",
+        default_value = "HEAD",
+        help = "Revision to end at (inclusive)"
+    )]
+    to_ref: String,
+}
+
+impl ReleaseNotesCommand {
+    pub async fn run(&self, config: &AutocommitConfig) -> anyhow::Result<()> {
+        GitRepository::assert_git_repo().await?;
+
+        let subjects = GitRepository::get_commit_subjects(&self.from_tag, &self.to_ref)?;
+        if subjects.is_empty() {
+            outro(&format!(
+                "{}",
+                format!(
+                    "No commits found between {} and {}",
+                    self.from_tag, self.to_ref
+                )
+                .yellow()
+            ));
+            return Ok(());
+        }
+
+        let grouped_subjects = Self::group_by_type(&subjects)
+            .iter()
+            .map(|(commit_type, subjects)| {
+                let lines = subjects
+                    .iter()
+                    .map(|subject| format!("- {}", subject))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!("{}:\n{}", commit_type, lines)
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        confirm_send(config, grouped_subjects.len())?;
+        let prompt = vec![
+            Message::new(
+                MessageRole::System,
+                String::from(
+                    "You are a release manager writing release notes for a software project. \
+                    Write a concise changelog section in Markdown from the commit subjects the user provides, which are grouped by conventional commit type. \
+                    Use a heading per group (e.g. Features, Bug Fixes, Other Changes), merge duplicates, and omit purely internal noise. \
+                    Respond with the changelog section only.",
+                ),
+            ),
+            Message::new(MessageRole::User, grouped_subjects),
+        ];
+
+        let oai_config = config.get_oai_config()?;
+        let model = config
+            .config_data
+            .open_ai_model
+            .get_value_ref()
+            .get_inner_value();
+        let fallback_models = config
+            .config_data
+            .fallback_models
+            .get_value_ref()
+            .get_inner_value();
+
+        let mut release_notes_spinner = spinner();
+        release_notes_spinner.start(&format!(
+            "Generating release notes for {} commits...",
+            subjects.len()
+        ));
+        let release_notes = generate_message(&prompt, oai_config, &model, &fallback_models).await?;
+        release_notes_spinner.stop("📝 Release notes generated successfully");
+
+        outro(&release_notes);
+        info!("Release notes generated");
+        Ok(())
+    }
+
+    fn group_by_type(subjects: &[String]) -> BTreeMap<String, Vec<String>> {
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for subject in subjects {
+            let commit_type = CONVENTIONAL_TYPE
+                .captures(subject)
+                .map(|captures| captures[1].to_lowercase())
+                .unwrap_or_else(|| String::from("other"));
+            groups.entry(commit_type).or_default().push(subject.clone());
+        }
+        groups
+    }
+}
@@ -0,0 +1,32 @@
+#[test]
+fn test_group_by_type() {
+    use super::ReleaseNotesCommand;
+
+    let subjects = [
+        "feat: add login form",
+        "fix(parser): handle empty input",
+        "Feat(ui)!: drop the old theme",
+        "Update the readme",
+        "fix: keep trailing newlines",
+        "chore : bump versions",
+    ]
+    .map(String::from);
+
+    let groups = ReleaseNotesCommand::group_by_type(&subjects);
+    assert_eq!(groups.keys().collect::<Vec<_>>(), ["feat", "fix", "other"]);
+    assert_eq!(
+        groups["feat"],
+        ["feat: add login form", "Feat(ui)!: drop the old theme"]
+    );
+    assert_eq!(
+        groups["fix"],
+        [
+            "fix(parser): handle empty input",
+            "fix: keep trailing newlines"
+        ]
+    );
+    assert_eq!(
+        groups["other"],
+        ["Update the readme", "chore : bump versions"]
+    );
+}
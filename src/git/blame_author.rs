@@ -0,0 +1,6 @@
+/// Someone who last changed lines that the staged changes touch.
+pub struct BlameAuthor {
+    pub name: String,
+    pub email: String,
+    pub lines: usize,
+}
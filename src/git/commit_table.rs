@@ -1,5 +1,10 @@
 use prettytable::{Cell, Row, Table};
 
+pub enum SigningStatus {
+    Signed { key_id: Option<String> },
+    Unsigned,
+}
+
 pub struct CommitSummary {
     pub branch_name: String,
     pub commit_hash: String,
@@ -9,6 +14,8 @@ pub struct CommitSummary {
     pub files_changed: usize,
     pub insertions: usize,
     pub deletions: usize,
+    /// `None` when signing is off and the commit isn't signed.
+    pub signing: Option<SigningStatus>,
 }
 
 impl CommitSummary {
@@ -46,6 +53,26 @@ impl CommitSummary {
             Cell::new(&self.insertions.to_string()),
             Cell::new(&self.deletions.to_string()),
         ]));
+        match &self.signing {
+            Some(SigningStatus::Signed { key_id }) => {
+                let status = match key_id {
+                    Some(key_id) => format!("✔ ({})", key_id),
+                    None => String::from("✔"),
+                };
+                table.add_row(Row::new(vec![
+                    Cell::new("Signed"),
+                    Cell::new(&status).style_spec("Fg"),
+                ]));
+            }
+            Some(SigningStatus::Unsigned) => {
+                table.add_row(Row::new(vec![
+                    Cell::new("Signed"),
+                    Cell::new("✖ (commit.gpgsign is set but the commit is not signed)")
+                        .style_spec("Fr"),
+                ]));
+            }
+            None => {}
+        }
 
         table
     }
@@ -0,0 +1,58 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::anyhow;
+
+/// How much context surrounds the changes in the diff sent to the model.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStyle {
+    /// Only the changed lines.
+    Minimal,
+    /// Three lines of context, like `git diff`.
+    #[default]
+    Unified,
+    /// The whole file around the changes.
+    Full,
+    /// A set number of context lines, from `diff_context_lines`.
+    Lines(u32),
+}
+
+impl DiffStyle {
+    pub const VARIANTS: [&'static str; 3] = ["unified", "minimal", "full"];
+
+    pub fn context_lines(&self) -> u32 {
+        match self {
+            DiffStyle::Minimal => 0,
+            DiffStyle::Unified => 3,
+            DiffStyle::Full => u32::MAX,
+            DiffStyle::Lines(lines) => *lines,
+        }
+    }
+}
+
+impl FromStr for DiffStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "minimal" => Ok(DiffStyle::Minimal),
+            "unified" => Ok(DiffStyle::Unified),
+            "full" => Ok(DiffStyle::Full),
+            _ => Err(anyhow!(
+                "Invalid diff style: {}. Use one of: {}",
+                s,
+                Self::VARIANTS.join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DiffStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffStyle::Minimal => write!(f, "minimal"),
+            DiffStyle::Unified => write!(f, "unified"),
+            DiffStyle::Full => write!(f, "full"),
+            DiffStyle::Lines(lines) => write!(f, "{}", lines),
+        }
+    }
+}
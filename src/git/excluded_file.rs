@@ -0,0 +1,6 @@
+/// A staged file left out of the diffs, with the pattern that matched it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExcludedFile {
+    pub path: String,
+    pub pattern: String,
+}
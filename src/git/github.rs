@@ -0,0 +1,39 @@
+use anyhow::anyhow;
+use log::{debug, error};
+use tokio::process::Command;
+
+pub struct GitHubCli {}
+
+impl GitHubCli {
+    pub async fn is_available() -> bool {
+        Command::new("gh")
+            .arg("--version")
+            .output()
+            .await
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    pub async fn create_pull_request(title: &str, body: &str) -> anyhow::Result<String> {
+        let output = Command::new("gh")
+            .arg("pr")
+            .arg("create")
+            .arg("--title")
+            .arg(title)
+            .arg("--body")
+            .arg(body)
+            .output()
+            .await
+            .map_err(|e| anyhow!("Command 'gh pr create' failed: {}", e))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            error!("Failed to create pull request: {}", stderr);
+            return Err(anyhow!("Failed to create pull request: {}", stderr));
+        }
+
+        debug!("Pull request created: {}", stdout);
+        Ok(stdout)
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use log::warn;
+
+/// A copy of the index file, taken before staging files for a dry run so
+/// the index can be put back as it was. A backup that is dropped without
+/// being restored, e.g. when a timed-out run is aborted, restores itself.
+pub struct IndexBackup {
+    pub(super) path: PathBuf,
+    pub(super) contents: Option<Vec<u8>>,
+    pub(super) restored: bool,
+}
+
+impl IndexBackup {
+    /// Writes the saved index back, or removes the index if there was none.
+    /// Leaves an unchanged index alone, e.g. one git holds a lock on while
+    /// running a hook.
+    pub fn restore(mut self) -> anyhow::Result<()> {
+        self.restored = true;
+        self.write_back()
+    }
+
+    fn write_back(&self) -> anyhow::Result<()> {
+        if std::fs::read(&self.path).ok() == self.contents {
+            return Ok(());
+        }
+        match &self.contents {
+            Some(contents) => std::fs::write(&self.path, contents)
+                .with_context(|| format!("Failed to restore {}", self.path.display())),
+            None if self.path.exists() => std::fs::remove_file(&self.path)
+                .with_context(|| format!("Failed to remove {}", self.path.display())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for IndexBackup {
+    fn drop(&mut self) {
+        if !self.restored {
+            if let Err(e) = self.write_back() {
+                warn!("{:#}", e);
+            }
+        }
+    }
+}
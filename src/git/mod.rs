@@ -1,21 +1,91 @@
 use anyhow::anyhow;
-use git2::{DiffOptions, Repository, RepositoryOpenFlags, Status, StatusOptions};
+use git2::{
+    Diff, DiffFindOptions, DiffOptions, Pathspec, PathspecFlags, Repository, RepositoryOpenFlags,
+    RepositoryState, Status, StatusOptions,
+};
 use ignore::{
     gitignore::{Gitignore, GitignoreBuilder},
     WalkBuilder,
 };
 use log::{debug, error};
 use prettytable::{Cell, Row, Table};
+use std::path::Path;
+mod blame_author;
 mod commit_table;
-use tokio::process::Command;
+mod diff_style;
+mod excluded_file;
+mod github;
+mod index_backup;
+mod staged_change;
+mod worktree_change;
+use tokio::{process::Command, sync::OnceCell};
+
+pub use self::blame_author::BlameAuthor;
+use self::commit_table::{CommitSummary, SigningStatus};
+pub use self::diff_style::DiffStyle;
+pub use self::excluded_file::ExcludedFile;
+pub use self::github::GitHubCli;
+pub use self::index_backup::IndexBackup;
+pub use self::staged_change::StagedChange;
+pub use self::worktree_change::WorktreeChange;
+#[cfg(test)]
+pub(crate) mod tests;
+
+/// The tree the staged changes are compared against.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DiffBase {
+    /// The current commit, for a new commit.
+    #[default]
+    Head,
+    /// The parent of the current commit, so the diff covers the current
+    /// commit's changes as well, for amending it.
+    HeadParent,
+}
+
+/// Index statuses that make a file part of the next commit.
+const STAGED_STATUSES: Status = Status::INDEX_NEW
+    .union(Status::INDEX_MODIFIED)
+    .union(Status::INDEX_DELETED)
+    .union(Status::INDEX_RENAMED)
+    .union(Status::INDEX_TYPECHANGE);
 
-use self::commit_table::CommitSummary;
-mod tests;
+/// Whether `git_commit` asks git to sign the commit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CommitSigning<'a> {
+    /// Leave it to git's `commit.gpgsign` setting.
+    #[default]
+    GitConfig,
+    /// Sign with `user.signingkey`, or with the given key instead.
+    Sign(Option<&'a str>),
+}
 
 pub struct GitRepository {}
 
 impl GitRepository {
+    /// Checks once per run that the `git` executable can be started, since
+    /// committing, pulling and pushing shell out to it.
+    pub async fn assert_git_installed() -> anyhow::Result<()> {
+        static GIT_INSTALLED: OnceCell<bool> = OnceCell::const_new();
+        let installed = *GIT_INSTALLED
+            .get_or_init(|| async {
+                Command::new("git")
+                    .arg("--version")
+                    .output()
+                    .await
+                    .map(|output| output.status.success())
+                    .unwrap_or(false)
+            })
+            .await;
+        if !installed {
+            return Err(anyhow!(
+                "git executable not found; install git or ensure it's on PATH"
+            ));
+        }
+        Ok(())
+    }
+
     pub async fn assert_git_repo() -> anyhow::Result<()> {
+        Self::assert_git_installed().await?;
         Repository::open_from_env().map_err(|err| {
             anyhow!(
                 "The current working directory is not a Git repository: {}",
@@ -25,6 +95,41 @@ impl GitRepository {
         Ok(())
     }
 
+    pub fn get_repo_root() -> anyhow::Result<std::path::PathBuf> {
+        let repo = Repository::open_from_env()?;
+        repo.workdir()
+            .map(|path| path.to_path_buf())
+            .ok_or_else(|| anyhow!("The repository does not have a working directory"))
+    }
+
+    /// Converts a path given relative to the current directory into a path
+    /// relative to the repository root, as used by the status functions.
+    pub fn to_repo_path(path: &str) -> anyhow::Result<String> {
+        let root = Self::get_repo_root()?;
+        let root = root.canonicalize().unwrap_or(root);
+        let mut absolute = std::env::current_dir()?;
+        absolute = absolute.canonicalize().unwrap_or(absolute);
+
+        for component in Path::new(path).components() {
+            match component {
+                std::path::Component::CurDir => {}
+                std::path::Component::ParentDir => {
+                    absolute.pop();
+                }
+                component => absolute.push(component),
+            }
+        }
+
+        let relative = absolute
+            .strip_prefix(&root)
+            .map_err(|_| anyhow!("Path '{}' is outside of the repository", path))?;
+        Ok(relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"))
+    }
+
     pub fn get_changed_files() -> anyhow::Result<Vec<String>> {
         let repo = Repository::open_from_env().map_err(|err| {
             anyhow!(
@@ -52,6 +157,84 @@ impl GitRepository {
         Ok(files)
     }
 
+    /// Returns the paths of initialized submodules that have uncommitted
+    /// changes of their own.
+    pub fn get_changed_submodules() -> anyhow::Result<Vec<String>> {
+        let repo = Self::open_repository()?;
+        let mut paths = Vec::new();
+        for submodule in repo
+            .submodules()
+            .map_err(|e| anyhow!("Failed to list submodules: {}", e))?
+        {
+            let Ok(submodule_repo) = submodule.open() else {
+                continue;
+            };
+            let mut opts = StatusOptions::new();
+            opts.include_untracked(true);
+            if !submodule_repo.statuses(Some(&mut opts))?.is_empty() {
+                paths.push(submodule.path().to_string_lossy().to_string());
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Returns the unstaged changes of the given files, untracked files
+    /// included, in the order the files are given.
+    pub fn get_worktree_changes(files: &[String]) -> anyhow::Result<Vec<WorktreeChange>> {
+        let repo = Self::open_repository()?;
+        let mut diff_opts = DiffOptions::new();
+        diff_opts
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        for file in files {
+            diff_opts.pathspec(file);
+        }
+        let diff = repo
+            .diff_index_to_workdir(None, Some(&mut diff_opts))
+            .map_err(|e| anyhow!("Failed to get unstaged diff: {}", e))?;
+
+        let mut changes = Vec::new();
+        for index in 0..diff.deltas().len() {
+            let Some(mut patch) = git2::Patch::from_diff(&diff, index)? else {
+                continue;
+            };
+            let Some(path) = patch.delta().new_file().path() else {
+                continue;
+            };
+            let path = path.to_string_lossy().to_string();
+            let (_, additions, deletions) = patch.line_stats()?;
+            let patch = patch
+                .to_buf()?
+                .as_str()
+                .map(str::to_owned)
+                .unwrap_or_else(|| String::from("(binary or non-UTF-8 content)"));
+            changes.push(WorktreeChange {
+                path,
+                additions,
+                deletions,
+                patch,
+            });
+        }
+
+        Ok(files
+            .iter()
+            .map(|file| {
+                changes
+                    .iter()
+                    .position(|change| &change.path == file)
+                    .map(|index| changes.swap_remove(index))
+                    .unwrap_or_else(|| WorktreeChange {
+                        path: file.clone(),
+                        additions: 0,
+                        deletions: 0,
+                        patch: String::new(),
+                    })
+            })
+            .collect())
+    }
+
     pub fn get_ignore_patterns() -> anyhow::Result<Gitignore> {
         let top_level_dir = std::env::current_dir()?;
         let mut ignore_file_paths = Vec::new();
@@ -66,7 +249,7 @@ impl GitRepository {
         {
             let entry = result?;
             let pat = ".autoignore";
-            if entry.file_type().map_or(false, |t| t.is_file())
+            if entry.file_type().is_some_and(|t| t.is_file())
                 && entry.file_name().to_string_lossy().ends_with(pat)
             {
                 ignore_file_paths.push(entry.path().to_owned());
@@ -82,6 +265,34 @@ impl GitRepository {
         Ok(ig.build()?)
     }
 
+    /// Splits `files` into the ones to diff and the ones matching one of the
+    /// gitignore-style `globs`, which are left out of the diffs.
+    pub fn exclude_diff_files(
+        files: &[String],
+        globs: &[String],
+    ) -> anyhow::Result<(Vec<String>, Vec<ExcludedFile>)> {
+        let mut builder = GitignoreBuilder::new("");
+        for glob in globs {
+            builder
+                .add_line(None, glob)
+                .map_err(|e| anyhow!("Invalid diff_exclude_globs pattern '{}': {}", glob, e))?;
+        }
+        let matcher = builder.build()?;
+
+        let mut diff_files = Vec::new();
+        let mut excluded_files = Vec::new();
+        for file in files {
+            match matcher.matched_path_or_any_parents(file, false) {
+                ignore::Match::Ignore(glob) => excluded_files.push(ExcludedFile {
+                    path: file.clone(),
+                    pattern: glob.original().to_owned(),
+                }),
+                _ => diff_files.push(file.clone()),
+            }
+        }
+        Ok((diff_files, excluded_files))
+    }
+
     pub fn get_staged_files() -> anyhow::Result<Vec<String>> {
         let repo = Repository::open_from_env().map_err(|err| {
             anyhow!(
@@ -97,13 +308,10 @@ impl GitRepository {
         let mut files = Vec::new();
         for entry in statuses.iter() {
             let path = entry.path().unwrap().to_string();
-            if entry.status().intersects(
-                git2::Status::INDEX_NEW
-                    | git2::Status::INDEX_MODIFIED
-                    | git2::Status::INDEX_DELETED,
-            ) && ignore_patterns
-                .matched_path_or_any_parents(&path, false)
-                .is_none()
+            if entry.status().intersects(STAGED_STATUSES)
+                && ignore_patterns
+                    .matched_path_or_any_parents(&path, false)
+                    .is_none()
             {
                 files.push(path);
             }
@@ -113,23 +321,27 @@ impl GitRepository {
         Ok(files)
     }
 
-    pub fn get_staged_file_diffs(files: &[String]) -> anyhow::Result<Vec<String>> {
+    fn get_staged_diff<'a>(
+        repo: &'a Repository,
+        files: &[String],
+        diff_style: DiffStyle,
+        base: DiffBase,
+    ) -> anyhow::Result<Diff<'a>> {
         let mut diff_opts = DiffOptions::new();
-        let mut excluded_files = Vec::new();
+        diff_opts.context_lines(diff_style.context_lines());
         for file in files {
-            if file.ends_with(".lock") {
-                excluded_files.push(file.clone());
-            } else {
-                diff_opts.pathspec(file);
-            }
+            diff_opts.pathspec(file);
         }
 
-        let repo =
-            Repository::open_ext(".", RepositoryOpenFlags::empty(), std::path::Path::new(""))
-                .map_err(|e| anyhow!("Failed to open repository: {}", e))?;
-
-        let head_tree = match repo.head().and_then(|head| head.peel_to_tree()) {
-            Ok(tree) => Some(tree),
+        let head_tree = match repo.head().and_then(|head| head.peel_to_commit()) {
+            Ok(commit) => match base {
+                DiffBase::Head => Some(commit.tree()?),
+                // A root commit is compared against the empty tree
+                DiffBase::HeadParent => match commit.parent(0) {
+                    Ok(parent) => Some(parent.tree()?),
+                    Err(_) => None,
+                },
+            },
             Err(e) => {
                 if e.code() == git2::ErrorCode::UnbornBranch {
                     None
@@ -151,12 +363,97 @@ impl GitRepository {
             .find_tree(staged_tree_oid)
             .map_err(|e| anyhow!("Failed to find staged tree: {}", e))?;
 
-        let diff = repo
+        let mut diff = repo
             .diff_tree_to_tree(head_tree.as_ref(), Some(&staged_tree), Some(&mut diff_opts))
             .map_err(|e| anyhow!("Failed to get diff: {}", e))?;
 
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+            .map_err(|e| anyhow!("Failed to detect renames: {}", e))?;
+
+        Ok(diff)
+    }
+
+    fn open_repository() -> anyhow::Result<Repository> {
+        Repository::open_ext(".", RepositoryOpenFlags::empty(), std::path::Path::new(""))
+            .map_err(|e| anyhow!("Failed to open repository: {}", e))
+    }
+
+    pub fn get_staged_changes(
+        files: &[String],
+        base: DiffBase,
+    ) -> anyhow::Result<Vec<StagedChange>> {
+        let repo = Self::open_repository()?;
+        let diff = Self::get_staged_diff(&repo, files, DiffStyle::default(), base)?;
+
+        let mut changes = Vec::new();
+        for delta in diff.deltas() {
+            let old_file = delta.old_file();
+            let new_file = delta.new_file();
+            let path_of = |file: &git2::DiffFile| {
+                file.path()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            };
+            let blob_of = |file: &git2::DiffFile| {
+                if file.id().is_zero() {
+                    Ok(None)
+                } else {
+                    repo.find_blob(file.id()).map(Some)
+                }
+            };
+            let old_blob = blob_of(&old_file)?;
+            let new_blob = blob_of(&new_file)?;
+
+            changes.push(StagedChange {
+                status: delta.status(),
+                old_path: path_of(&old_file),
+                new_path: path_of(&new_file),
+                content_changed: old_file.id() != new_file.id(),
+                is_binary: [&old_blob, &new_blob]
+                    .iter()
+                    .any(|blob| blob.as_ref().is_some_and(|blob| blob.is_binary())),
+                old_size: old_blob.as_ref().map_or(0, |blob| blob.size()),
+                new_size: new_blob.as_ref().map_or(0, |blob| blob.size()),
+                old_mode: old_file.mode().into(),
+                new_mode: new_file.mode().into(),
+            });
+        }
+
+        Ok(changes)
+    }
+
+    /// Returns the number of files changed, insertions and deletions in
+    /// the staged diff of `files`.
+    pub fn get_staged_short_stat(
+        files: &[String],
+        base: DiffBase,
+    ) -> anyhow::Result<(usize, usize, usize)> {
+        let repo = Self::open_repository()?;
+        let diff = Self::get_staged_diff(&repo, files, DiffStyle::default(), base)?;
+        let stats = diff
+            .stats()
+            .map_err(|e| anyhow!("Failed to get diff stats: {}", e))?;
+        Ok((stats.files_changed(), stats.insertions(), stats.deletions()))
+    }
+
+    pub fn get_staged_file_diffs(
+        files: &[String],
+        diff_style: DiffStyle,
+        base: DiffBase,
+    ) -> anyhow::Result<Vec<String>> {
+        let repo = Self::open_repository()?;
+        let diff = Self::get_staged_diff(&repo, files, diff_style, base)?;
+
         let mut diff_text = Vec::new();
-        diff.print(git2::DiffFormat::Patch, |_delta, _, line| {
+        diff.print(git2::DiffFormat::Patch, |delta, _, line| {
+            // Binary contents mean nothing to the model, so they're described
+            // once per file, in place of the header
+            if delta.flags().is_binary() {
+                if line.origin() == 'F' {
+                    diff_text.push(Self::describe_binary_delta(&delta));
+                }
+                return true;
+            }
             let text = String::from_utf8_lossy(line.content());
             let line_text = format!("{}{}", line.origin(), text);
             match line.origin() {
@@ -174,6 +471,108 @@ impl GitRepository {
         Ok(diff_text)
     }
 
+    fn describe_binary_delta(delta: &git2::DiffDelta) -> String {
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        format!(
+            "Binary file {} changed ({}→{} bytes)\n",
+            path,
+            delta.old_file().size(),
+            delta.new_file().size()
+        )
+    }
+
+    /// Counts who last changed the lines that the staged changes modify or
+    /// delete, most lines first. Added lines have no previous author.
+    pub fn get_blame_authors(files: &[String]) -> anyhow::Result<Vec<BlameAuthor>> {
+        if !Self::has_commits()? {
+            return Ok(Vec::new());
+        }
+        let repo = Self::open_repository()?;
+        let diff = Self::get_staged_diff(&repo, files, DiffStyle::Minimal, DiffBase::Head)?;
+
+        let mut authors: Vec<BlameAuthor> = Vec::new();
+        for index in 0..diff.deltas().len() {
+            let Some(patch) = git2::Patch::from_diff(&diff, index)? else {
+                continue;
+            };
+            let Some(path) = patch.delta().old_file().path() else {
+                continue;
+            };
+            if patch.delta().status() == git2::Delta::Added {
+                continue;
+            }
+            let blame = match repo.blame_file(path, None) {
+                Ok(blame) => blame,
+                Err(e) => {
+                    debug!("Failed to blame {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            for hunk_index in 0..patch.num_hunks() {
+                let (hunk, _) = patch.hunk(hunk_index)?;
+                for line in hunk.old_start()..hunk.old_start() + hunk.old_lines() {
+                    let Some(blame_hunk) = blame.get_line(line as usize) else {
+                        continue;
+                    };
+                    let signature = blame_hunk.final_signature();
+                    let (Some(name), Some(email)) = (signature.name(), signature.email()) else {
+                        continue;
+                    };
+                    match authors
+                        .iter_mut()
+                        .find(|author| author.email.eq_ignore_ascii_case(email))
+                    {
+                        Some(author) => author.lines += 1,
+                        None => authors.push(BlameAuthor {
+                            name: name.to_owned(),
+                            email: email.to_owned(),
+                            lines: 1,
+                        }),
+                    }
+                }
+            }
+        }
+
+        authors.sort_by_key(|author| std::cmp::Reverse(author.lines));
+        Ok(authors)
+    }
+
+    pub fn get_files_with_conflict_markers(files: &[String]) -> anyhow::Result<Vec<String>> {
+        let repo = Self::open_repository()?;
+        let index = repo
+            .index()
+            .map_err(|e| anyhow!("Failed to get index: {}", e))?;
+
+        let mut conflicted_files = Vec::new();
+        for file in files {
+            let Some(entry) = index.get_path(std::path::Path::new(file), 0) else {
+                continue;
+            };
+            let blob = repo.find_blob(entry.id)?;
+            if blob.is_binary() {
+                continue;
+            }
+
+            let has_conflict_markers =
+                String::from_utf8_lossy(blob.content()).lines().any(|line| {
+                    ["<<<<<<<", ">>>>>>>"]
+                        .iter()
+                        .any(|marker| line == *marker || line.starts_with(&format!("{} ", marker)))
+                });
+            if has_conflict_markers {
+                conflicted_files.push(file.clone());
+            }
+        }
+
+        Ok(conflicted_files)
+    }
+
     pub async fn git_add(files: &[String]) -> anyhow::Result<()> {
         let mut command = Command::new("git");
         command.arg("add").args(files);
@@ -189,6 +588,102 @@ impl GitRepository {
         Ok(())
     }
 
+    /// Returns the changed files matched by each pathspec, in the order the
+    /// pathspecs were given.
+    pub fn match_pathspecs(
+        pathspecs: &[String],
+        changed_files: &[String],
+    ) -> anyhow::Result<Vec<(String, Vec<String>)>> {
+        pathspecs
+            .iter()
+            .map(|pathspec| {
+                let matcher = Pathspec::new([pathspec.as_str()])
+                    .map_err(|e| anyhow!("Invalid pathspec '{}': {}", pathspec, e))?;
+                let matched_files = changed_files
+                    .iter()
+                    .filter(|file| {
+                        matcher.matches_path(Path::new(file.as_str()), PathspecFlags::DEFAULT)
+                    })
+                    .cloned()
+                    .collect();
+                Ok((pathspec.clone(), matched_files))
+            })
+            .collect()
+    }
+
+    pub async fn git_add_pathspecs(pathspecs: &[String]) -> anyhow::Result<()> {
+        let output = Command::new("git")
+            .arg("add")
+            .arg("--")
+            .args(pathspecs)
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Command 'git add' failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Saves the index so staging can be undone, e.g. after a dry run.
+    pub fn backup_index() -> anyhow::Result<IndexBackup> {
+        let repo = Self::open_repository()?;
+        let path = repo.path().join("index");
+        let contents = match std::fs::read(&path) {
+            Ok(contents) => Some(contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(anyhow!("Failed to read {}: {}", path.display(), e)),
+        };
+        Ok(IndexBackup {
+            path,
+            contents,
+            restored: false,
+        })
+    }
+
+    /// Writes the index as a tree and returns its id, so what is staged now
+    /// can be staged again part by part with `stage_from_tree`.
+    pub fn write_index_tree() -> anyhow::Result<String> {
+        let repo = Self::open_repository()?;
+        let mut index = repo
+            .index()
+            .map_err(|e| anyhow!("Failed to get index: {}", e))?;
+        let tree = index
+            .write_tree()
+            .map_err(|e| anyhow!("Failed to write index tree: {}", e))?;
+        Ok(tree.to_string())
+    }
+
+    /// Resets the index to HEAD, then stages `files` as they are in `tree`.
+    /// Unlike `git add`, this keeps partially staged files as they were.
+    pub async fn stage_from_tree(tree: &str, files: &[String]) -> anyhow::Result<()> {
+        let base = if Self::has_commits()? {
+            "HEAD"
+        } else {
+            "--empty"
+        };
+        Self::run_git(&["read-tree", base]).await?;
+        let mut args = vec!["reset", "-q", tree, "--"];
+        args.extend(files.iter().map(String::as_str));
+        Self::run_git(&args).await
+    }
+
+    async fn run_git(args: &[&str]) -> anyhow::Result<()> {
+        let output = Command::new("git").args(args).output().await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Command 'git {}' failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
     pub fn git_add_all() -> anyhow::Result<()> {
         let repo = Repository::open_from_env().map_err(|err| {
             anyhow!(
@@ -209,33 +704,59 @@ impl GitRepository {
         Ok(())
     }
 
-    pub async fn git_commit(message: &str, name: &str, email: &str) -> anyhow::Result<String> {
+    fn has_staged_changes() -> anyhow::Result<bool> {
         let repo = Repository::open_from_env()?;
         let statuses = repo.statuses(Some(StatusOptions::new().include_untracked(true)))?;
-        let mut has_staged_changes = false;
         for entry in statuses.iter() {
             debug!("File {:?} has status {:?}", entry.path(), entry.status());
-            if entry.status().intersects(
-                git2::Status::INDEX_NEW
-                    | git2::Status::INDEX_MODIFIED
-                    | git2::Status::INDEX_DELETED,
-            ) {
-                has_staged_changes = true;
-                break;
+            if entry.status().intersects(STAGED_STATUSES) {
+                return Ok(true);
             }
         }
+        Ok(false)
+    }
 
-        if !has_staged_changes {
+    /// Commits with `name` and `email` as the author and `committer` as the
+    /// `(name, email)` of the committer.
+    pub async fn git_commit(
+        message: &str,
+        name: &str,
+        email: &str,
+        committer: (&str, &str),
+        amend: bool,
+        signing: CommitSigning<'_>,
+        no_verify: bool,
+    ) -> anyhow::Result<String> {
+        if !amend && !Self::has_staged_changes()? {
             let message = String::from("Failed to commit. Have you manually committed recently?");
             return Err(anyhow::anyhow!(message));
         }
 
-        let output = Command::new("git")
-            .arg("commit")
+        let mut command = Command::new("git");
+        command.arg("commit");
+        if amend {
+            command.arg("--amend");
+        }
+        // Skips the pre-commit and commit-msg hooks
+        if no_verify {
+            command.arg("--no-verify");
+        }
+        match signing {
+            CommitSigning::GitConfig => {}
+            CommitSigning::Sign(None) => {
+                command.arg("--gpg-sign");
+            }
+            CommitSigning::Sign(Some(key)) => {
+                command.arg(format!("--gpg-sign={}", key));
+            }
+        }
+        let output = command
             .arg("-m")
             .arg(message)
             .arg("--author")
             .arg(format!("{} <{}>", name, email))
+            .env("GIT_COMMITTER_NAME", committer.0)
+            .env("GIT_COMMITTER_EMAIL", committer.1)
             .output()
             .await
             .map_err(|e| anyhow!("Command 'git commit' failed: {}", e))?;
@@ -243,13 +764,82 @@ impl GitRepository {
         let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
         if !output.status.success() {
             error!("Failed to commit changes: {}", stderr);
+            // git can't write the commit object when signing fails
+            if signing != CommitSigning::GitConfig
+                && (stderr.contains("gpg failed to sign")
+                    || stderr.contains("failed to write commit object"))
+            {
+                return Err(anyhow!(
+                    "Failed to sign the commit. Check that gpg and its agent (or ssh-keygen for SSH keys) work and that signing_key or user.signingkey is right:\n{}",
+                    stderr
+                ));
+            }
             return Err(anyhow!(stderr));
         }
 
         Ok(stdout.trim().to_string())
     }
 
-    pub async fn get_commit_summary_table(name: &str, email: &str) -> anyhow::Result<Table> {
+    /// Returns the files changed by the current commit together with the
+    /// staged files, i.e. everything an amended commit will contain changes to.
+    pub fn get_amend_files() -> anyhow::Result<Vec<String>> {
+        let repo = Self::open_repository()?;
+        let head_commit = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|e| {
+                if e.code() == git2::ErrorCode::UnbornBranch {
+                    anyhow!("There are no commits to amend yet, run without --amend to create the first one")
+                } else {
+                    anyhow!("There is no commit to amend: {}", e)
+                }
+            })?;
+        let parent_tree = match head_commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&head_commit.tree()?), None)
+            .map_err(|e| anyhow!("Failed to get diff of the current commit: {}", e))?;
+
+        let mut files = Self::get_staged_files()?;
+        for delta in diff.deltas() {
+            for file in [delta.old_file(), delta.new_file()] {
+                if let Some(path) = file.path() {
+                    files.push(path.to_string_lossy().to_string());
+                }
+            }
+        }
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    /// Returns `false` for a freshly initialized repository whose HEAD points
+    /// to a branch without any commits yet.
+    pub fn has_commits() -> anyhow::Result<bool> {
+        let repo = Repository::open_from_env()?;
+        let head = repo.head();
+        match head {
+            Ok(_) => Ok(true),
+            Err(e)
+                if e.code() == git2::ErrorCode::UnbornBranch
+                    || e.code() == git2::ErrorCode::NotFound =>
+            {
+                Ok(false)
+            }
+            Err(e) => Err(anyhow!("Failed to get HEAD reference: {}", e)),
+        }
+    }
+
+    pub async fn get_commit_summary_table(
+        name: &str,
+        email: &str,
+    ) -> anyhow::Result<Option<Table>> {
+        if !Self::has_commits()? {
+            return Ok(None);
+        }
+
         let repo = Repository::open_from_env()?;
         let head = repo.head()?;
         let latest_commit = head.peel_to_commit()?;
@@ -267,10 +857,37 @@ impl GitRepository {
             files_changed,
             insertions,
             deletions,
+            signing: Self::get_signing_status(&repo, latest_commit_id)?,
         };
         let table = commit_summary.get_table();
 
-        Ok(table)
+        Ok(Some(table))
+    }
+
+    fn get_signing_status(
+        repo: &Repository,
+        commit_id: git2::Oid,
+    ) -> anyhow::Result<Option<SigningStatus>> {
+        if repo.extract_signature(&commit_id, None).is_ok() {
+            return Ok(Some(SigningStatus::Signed {
+                key_id: Self::get_signing_key_id(commit_id),
+            }));
+        }
+
+        let signing_enabled = repo.config()?.get_bool("commit.gpgsign").unwrap_or(false);
+        Ok(signing_enabled.then_some(SigningStatus::Unsigned))
+    }
+
+    /// Asks git for the key that signed the commit, as git2 doesn't parse
+    /// signatures.
+    fn get_signing_key_id(commit_id: git2::Oid) -> Option<String> {
+        let output = std::process::Command::new("git")
+            .args(["log", "-1", "--format=%GK"])
+            .arg(commit_id.to_string())
+            .output()
+            .ok()?;
+        let key_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !key_id.is_empty()).then_some(key_id)
     }
 
     pub async fn git_pull(remote: &str) -> anyhow::Result<()> {
@@ -291,6 +908,97 @@ impl GitRepository {
         Ok(())
     }
 
+    /// Returns the files left with unresolved conflicts, e.g. by a pull.
+    pub fn get_unmerged_files() -> anyhow::Result<Vec<String>> {
+        let repo = Self::open_repository()?;
+        let index = repo
+            .index()
+            .map_err(|e| anyhow!("Failed to get index: {}", e))?;
+
+        let mut files = Vec::new();
+        for conflict in index
+            .conflicts()
+            .map_err(|e| anyhow!("Failed to get conflicts: {}", e))?
+        {
+            let conflict = conflict.map_err(|e| anyhow!("Failed to read conflict: {}", e))?;
+            if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+                files.push(String::from_utf8_lossy(&entry.path).to_string());
+            }
+        }
+        Ok(files)
+    }
+
+    /// Aborts the merge or rebase left behind by a conflicted pull.
+    pub async fn git_abort_pull() -> anyhow::Result<()> {
+        let repo = Self::open_repository()?;
+        let operation = match repo.state() {
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => "rebase",
+            _ => "merge",
+        };
+
+        let output = Command::new("git")
+            .arg(operation)
+            .arg("--abort")
+            .output()
+            .await
+            .map_err(|e| anyhow!("Command 'git {} --abort' failed: {}", operation, e))?;
+        if !output.status.success() {
+            let error_message = String::from_utf8_lossy(&output.stderr);
+            error!("Failed to abort the {}: {}", operation, error_message);
+            return Err(anyhow!(
+                "Failed to abort the {}: {}",
+                operation,
+                error_message
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn git_fetch(remote: &str) -> anyhow::Result<()> {
+        let output = Command::new("git")
+            .arg("fetch")
+            .arg(remote)
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let error_message = String::from_utf8_lossy(&output.stderr);
+            error!(
+                "Failed to fetch from remote repository {}: {}",
+                remote, error_message
+            );
+            return Err(anyhow!(
+                "Failed to fetch from remote repository {}: {}",
+                remote,
+                error_message
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn get_commits_behind(remote: &str) -> anyhow::Result<usize> {
+        let repo = Repository::open_from_env()?;
+        let head = repo
+            .head()
+            .map_err(|e| anyhow!("Failed to get HEAD reference: {}", e))?;
+        let (Some(branch_name), Some(local_oid)) = (head.shorthand(), head.target()) else {
+            return Ok(0);
+        };
+
+        let remote_ref = format!("refs/remotes/{}/{}", remote, branch_name);
+        let remote_oid = match repo.refname_to_id(&remote_ref) {
+            Ok(oid) => oid,
+            Err(_) => return Ok(0),
+        };
+
+        let (_ahead, behind) = repo
+            .graph_ahead_behind(local_oid, remote_oid)
+            .map_err(|e| anyhow!("Failed to compare with {}: {}", remote_ref, e))?;
+        Ok(behind)
+    }
+
     pub async fn git_push(remote: &str) -> anyhow::Result<()> {
         let mut command = Command::new("git");
         command.arg("push").arg("--verbose").arg(remote);
@@ -355,6 +1063,44 @@ impl GitRepository {
         Ok(name)
     }
 
+    /// Returns `core.commentChar`, falling back to `#` when it is unset or
+    /// set to `auto`.
+    pub fn get_comment_char() -> anyhow::Result<char> {
+        let repo = Repository::open_from_env()?;
+        let config = repo
+            .config()
+            .map_err(|e| anyhow!("Failed to get repository configuration: {}", e))?;
+        let comment_char = config
+            .get_string("core.commentChar")
+            .ok()
+            .filter(|value| value != "auto")
+            .and_then(|value| value.chars().next())
+            .unwrap_or('#');
+        Ok(comment_char)
+    }
+
+    pub async fn get_editor() -> anyhow::Result<String> {
+        let output = Command::new("git")
+            .arg("var")
+            .arg("GIT_EDITOR")
+            .output()
+            .await
+            .map_err(|e| anyhow!("Command 'git var' failed: {}", e))?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to determine the editor: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    pub fn get_edit_message_path() -> anyhow::Result<std::path::PathBuf> {
+        let repo = Repository::open_from_env()?;
+        Ok(repo.path().join("AUTOCOMMIT_EDITMSG"))
+    }
+
     pub async fn git_status() -> anyhow::Result<String> {
         let repo =
             Repository::open_from_env().map_err(|e| anyhow!("Failed to open repository: {}", e))?;
@@ -390,7 +1136,112 @@ impl GitRepository {
         Ok(table.to_string())
     }
 
+    pub fn get_commit_subjects(from: &str, to: &str) -> anyhow::Result<Vec<String>> {
+        let repo = Repository::open_from_env()?;
+        let resolve = |rev: &str| {
+            repo.revparse_single(rev)
+                .and_then(|object| object.peel_to_commit())
+                .map(|commit| commit.id())
+                .map_err(|e| anyhow!("Tag or revision '{}' not found: {}", rev, e))
+        };
+        let from_oid = resolve(from)?;
+        let to_oid = resolve(to)?;
+
+        let mut revwalk = repo
+            .revwalk()
+            .map_err(|e| anyhow!("Failed to create Revwalk object: {}", e))?;
+        revwalk.push(to_oid)?;
+        revwalk.hide(from_oid)?;
+
+        let mut subjects = Vec::new();
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            if let Some(subject) = commit.summary() {
+                subjects.push(subject.to_string());
+            }
+        }
+
+        Ok(subjects)
+    }
+
+    fn find_commit<'a>(repo: &'a Repository, rev: &str) -> anyhow::Result<git2::Commit<'a>> {
+        repo.revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| anyhow!("Revision '{}' not found: {}", rev, e))
+    }
+
+    pub fn get_commit_message(rev: &str) -> anyhow::Result<String> {
+        let repo = Repository::open_from_env()?;
+        let commit = Self::find_commit(&repo, rev)?;
+        Ok(commit.message().unwrap_or_default().trim().to_owned())
+    }
+
+    /// Returns the patch a commit introduces over its first parent. A root
+    /// commit is compared against the empty tree.
+    pub fn get_commit_diff(rev: &str) -> anyhow::Result<String> {
+        let repo = Repository::open_from_env()?;
+        let commit = Self::find_commit(&repo, rev)?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)
+            .map_err(|e| anyhow!("Failed to get diff of '{}': {}", rev, e))?;
+
+        let mut diff_text = String::new();
+        diff.print(git2::DiffFormat::Patch, |_delta, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                diff_text.push(line.origin());
+            }
+            diff_text.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })
+        .map_err(|e| anyhow!("Failed to print diff: {}", e))?;
+
+        Ok(diff_text)
+    }
+
+    /// Returns the name of the checked out branch, or `None` on a detached HEAD.
+    pub fn get_current_branch() -> anyhow::Result<Option<String>> {
+        let repo = Repository::open_from_env()?;
+        let head = match repo.head() {
+            Ok(head) => head,
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+                // No commits yet, but HEAD still names the branch
+                let head = repo.find_reference("HEAD")?;
+                return Ok(head
+                    .symbolic_target()
+                    .and_then(|target| target.strip_prefix("refs/heads/"))
+                    .map(|branch| branch.to_owned()));
+            }
+            Err(e) => return Err(anyhow!("Failed to get HEAD reference: {}", e)),
+        };
+
+        if !head.is_branch() {
+            return Ok(None);
+        }
+        Ok(head.shorthand().map(|branch| branch.to_owned()))
+    }
+
+    pub fn get_head_commit_hash() -> anyhow::Result<String> {
+        if !Self::has_commits()? {
+            return Err(anyhow!("The repository does not have any commits yet"));
+        }
+
+        let repo = Repository::open_from_env()?;
+        let head_commit = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|e| anyhow!("Failed to get HEAD commit: {}", e))?;
+        Ok(head_commit.id().to_string())
+    }
+
     pub fn get_commit_count() -> anyhow::Result<usize> {
+        if !Self::has_commits()? {
+            return Ok(0);
+        }
+
         let repo = Repository::open_from_env()?;
         let head = repo
             .head()
@@ -409,6 +1260,10 @@ impl GitRepository {
     }
 
     fn get_short_stat() -> anyhow::Result<(usize, usize, usize)> {
+        if !Self::has_commits()? {
+            return Ok((0, 0, 0));
+        }
+
         // Open the repository in the current directory
         let repo = Repository::open_from_env()?;
 
@@ -418,13 +1273,12 @@ impl GitRepository {
         // Get the tree for the HEAD commit
         let tree = head.tree()?;
 
-        // Get the diff between the HEAD commit and its parent
-        let parent_commit = head.parent(0);
-        let parent_tree = match parent_commit {
-            Ok(commit) => commit.tree()?,
-            Err(_) => return Ok((0, 0, 0)), // Return 0 for all values if parent commit doesn't exist
+        // Get the diff between the HEAD commit and its parent, or the empty tree for a root commit
+        let parent_tree = match head.parent(0) {
+            Ok(commit) => Some(commit.tree()?),
+            Err(_) => None,
         };
-        let diff = repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), None)?;
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
 
         // Get the number of insertions and deletions in the diff
         let stats = diff.stats()?;
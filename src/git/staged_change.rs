@@ -0,0 +1,60 @@
+use git2::Delta;
+
+#[derive(Clone)]
+pub struct StagedChange {
+    pub status: Delta,
+    pub old_path: String,
+    pub new_path: String,
+    pub content_changed: bool,
+    pub is_binary: bool,
+    pub old_size: usize,
+    pub new_size: usize,
+    pub old_mode: u32,
+    pub new_mode: u32,
+}
+
+fn format_size(bytes: usize) -> String {
+    match bytes {
+        bytes if bytes < 1024 => format!("{}B", bytes),
+        bytes if bytes < 1024 * 1024 => format!("{}KB", bytes / 1024),
+        bytes => format!("{:.1}MB", bytes as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+impl StagedChange {
+    pub fn is_pure_rename(&self) -> bool {
+        self.status == Delta::Renamed && !self.content_changed
+    }
+
+    /// Whether only the file mode changed, e.g. after `chmod +x`.
+    pub fn is_mode_change(&self) -> bool {
+        self.status == Delta::Modified && !self.content_changed && self.old_mode != self.new_mode
+    }
+
+    /// The permission bits of the new file mode in octal, such as `755`.
+    pub fn new_permissions(&self) -> String {
+        format!("{:o}", self.new_mode & 0o777)
+    }
+
+    /// A one-line summary such as `add logo.png (24KB)`, used when the diff
+    /// itself carries no useful content.
+    pub fn describe(&self) -> String {
+        match self.status {
+            Delta::Added => format!("add {} ({})", self.new_path, format_size(self.new_size)),
+            Delta::Deleted => format!("delete {} ({})", self.old_path, format_size(self.old_size)),
+            Delta::Renamed => format!(
+                "rename {} to {} ({} -> {})",
+                self.old_path,
+                self.new_path,
+                format_size(self.old_size),
+                format_size(self.new_size)
+            ),
+            _ => format!(
+                "modify {} ({} -> {})",
+                self.new_path,
+                format_size(self.old_size),
+                format_size(self.new_size)
+            ),
+        }
+    }
+}
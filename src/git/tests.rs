@@ -1,6 +1,70 @@
+use std::{env, path::PathBuf};
+
+use lazy_static::lazy_static;
+use tempfile::TempDir;
+use tokio::{
+    process::Command,
+    sync::{Mutex, MutexGuard},
+};
+
+lazy_static! {
+    // Tests change the process-wide working directory, so they must not run concurrently.
+    static ref CURRENT_DIR_LOCK: Mutex<()> = Mutex::new(());
+}
+
+pub(crate) struct TestRepo {
+    original_dir: PathBuf,
+    temp_dir: TempDir,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl TestRepo {
+    pub(crate) async fn new() -> anyhow::Result<Self> {
+        let lock = CURRENT_DIR_LOCK.lock().await;
+        let original_dir = env::current_dir()?;
+        let temp_dir = tempfile::tempdir()?;
+        env::set_current_dir(&temp_dir)?;
+
+        let repo = Self {
+            original_dir,
+            temp_dir,
+            _lock: lock,
+        };
+        repo.git(&["init"]).await?;
+        repo.git(&["config", "user.name", "Test User"]).await?;
+        repo.git(&["config", "user.email", "test@example.com"])
+            .await?;
+        Ok(repo)
+    }
+
+    pub(crate) async fn git(&self, args: &[&str]) -> anyhow::Result<()> {
+        let output = Command::new("git").args(args).output().await?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_file(&self, path: &str, contents: impl AsRef<[u8]>) -> anyhow::Result<()> {
+        std::fs::write(self.temp_dir.path().join(path), contents)?;
+        Ok(())
+    }
+}
+
+impl Drop for TestRepo {
+    fn drop(&mut self) {
+        let _ = env::set_current_dir(&self.original_dir);
+    }
+}
+
 #[tokio::test]
 async fn test_assert_git_repo_success() -> anyhow::Result<()> {
     use super::GitRepository;
+    let _lock = CURRENT_DIR_LOCK.lock().await;
     use std::env;
     use tokio::process::Command;
     // Store the original directory path
@@ -31,6 +95,7 @@ async fn test_assert_git_repo_success() -> anyhow::Result<()> {
 #[tokio::test]
 async fn test_assert_git_repo_failure() -> anyhow::Result<()> {
     use super::GitRepository;
+    let _lock = CURRENT_DIR_LOCK.lock().await;
     use std::env;
 
     // Store the original directory path
@@ -54,3 +119,901 @@ async fn test_assert_git_repo_failure() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_get_staged_changes_detects_pure_renames() -> anyhow::Result<()> {
+    use super::{DiffBase, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("old.txt", "unchanged contents\n")?;
+    repo.git(&["add", "old.txt"]).await?;
+    repo.git(&["commit", "-m", "Add old.txt"]).await?;
+    repo.git(&["mv", "old.txt", "new.txt"]).await?;
+
+    let staged_files = GitRepository::get_staged_files()?;
+    let changes = GitRepository::get_staged_changes(&staged_files, DiffBase::Head)?;
+
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].is_pure_rename());
+    assert_eq!(changes[0].old_path, "old.txt");
+    assert_eq!(changes[0].new_path, "new.txt");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_staged_changes_detects_binary_files() -> anyhow::Result<()> {
+    use super::{DiffBase, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    let png = [
+        0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, b'I', b'H', b'D',
+        b'R', 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00,
+    ];
+    repo.write_file("logo.png", png)?;
+    repo.git(&["add", "logo.png"]).await?;
+
+    let staged_files = GitRepository::get_staged_files()?;
+    let changes = GitRepository::get_staged_changes(&staged_files, DiffBase::Head)?;
+
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].is_binary);
+    assert_eq!(changes[0].new_size, png.len());
+    assert_eq!(changes[0].describe(), "add logo.png (29B)");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_staged_file_diffs_describe_binary_files() -> anyhow::Result<()> {
+    use super::{DiffBase, DiffStyle, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    let png = [
+        0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, b'I', b'H', b'D',
+        b'R', 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00,
+    ];
+    repo.write_file("logo.png", png)?;
+    repo.write_file("notes.txt", "one\n")?;
+    repo.git(&["add", "logo.png", "notes.txt"]).await?;
+    repo.git(&["commit", "-m", "Add logo"]).await?;
+
+    repo.write_file("logo.png", [&png[..], &[0x00, 0x01, 0x02, 0x03]].concat())?;
+    repo.write_file("notes.txt", "one\ntwo\n")?;
+    repo.git(&["add", "logo.png", "notes.txt"]).await?;
+
+    let staged_files = GitRepository::get_staged_files()?;
+    let diff =
+        GitRepository::get_staged_file_diffs(&staged_files, DiffStyle::default(), DiffBase::Head)?
+            .join("");
+
+    assert!(diff.starts_with("Binary file logo.png changed (29→33 bytes)\n"));
+    assert!(!diff.contains("PNG"));
+    assert!(diff.contains("diff --git a/notes.txt b/notes.txt"));
+    assert!(diff.contains("+two"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_get_staged_changes_detects_mode_changes() -> anyhow::Result<()> {
+    use super::{DiffBase, GitRepository};
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("build.sh", "#!/bin/sh\necho build\n")?;
+    repo.git(&["add", "build.sh"]).await?;
+    repo.git(&["commit", "-m", "Add build.sh"]).await?;
+    std::fs::set_permissions(
+        repo.temp_dir.path().join("build.sh"),
+        std::fs::Permissions::from_mode(0o755),
+    )?;
+    repo.git(&["add", "build.sh"]).await?;
+
+    let staged_files = GitRepository::get_staged_files()?;
+    let changes = GitRepository::get_staged_changes(&staged_files, DiffBase::Head)?;
+
+    assert_eq!(changes.len(), 1);
+    assert!(changes[0].is_mode_change());
+    assert_eq!(changes[0].new_permissions(), "755");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_staged_short_stat() -> anyhow::Result<()> {
+    use super::{DiffBase, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("a.txt", "one\ntwo\n")?;
+    repo.git(&["add", "a.txt"]).await?;
+    repo.git(&["commit", "-m", "Add a.txt"]).await?;
+    repo.write_file("a.txt", "one\nthree\nfour\n")?;
+    repo.write_file("b.txt", "new\n")?;
+    repo.write_file("unstaged.txt", "ignored\n")?;
+    repo.git(&["add", "a.txt", "b.txt"]).await?;
+
+    let staged_files = GitRepository::get_staged_files()?;
+    assert_eq!(
+        GitRepository::get_staged_short_stat(&staged_files, DiffBase::Head)?,
+        (2, 3, 1)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_git_commit_keeps_unstaged_hunks() -> anyhow::Result<()> {
+    use super::{CommitSigning, DiffBase, DiffStyle, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    let lines = (1..=20)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    repo.write_file("file.txt", &lines)?;
+    repo.git(&["add", "file.txt"]).await?;
+    repo.git(&["commit", "-m", "Add file.txt"]).await?;
+
+    let staged = lines.replace("line 2\n", "staged\n");
+    repo.write_file("file.txt", &staged)?;
+    repo.git(&["add", "file.txt"]).await?;
+    let worktree = staged.replace("line 19\n", "unstaged\n");
+    repo.write_file("file.txt", &worktree)?;
+
+    let staged_files = GitRepository::get_staged_files()?;
+    let diff =
+        GitRepository::get_staged_file_diffs(&staged_files, DiffStyle::default(), DiffBase::Head)?
+            .join("");
+    assert!(diff.contains("+staged"));
+    assert!(!diff.contains("unstaged"));
+
+    GitRepository::git_commit(
+        "Change line 2",
+        "Jane Doe",
+        "jane@example.com",
+        ("Jane Doe", "jane@example.com"),
+        false,
+        CommitSigning::GitConfig,
+        false,
+    )
+    .await?;
+
+    let git_repo = git2::Repository::open_from_env()?;
+    let tree = git_repo.head()?.peel_to_tree()?;
+    let blob = tree
+        .get_path(std::path::Path::new("file.txt"))?
+        .to_object(&git_repo)?;
+    assert_eq!(blob.peel_to_blob()?.content(), staged.as_bytes());
+    assert_eq!(
+        std::fs::read_to_string(repo.temp_dir.path().join("file.txt"))?,
+        worktree
+    );
+    assert!(GitRepository::get_changed_files()?.contains(&String::from("file.txt")));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_unmerged_files_and_abort() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("file.txt", "base\n")?;
+    repo.git(&["add", "file.txt"]).await?;
+    repo.git(&["commit", "-m", "Add file.txt"]).await?;
+    repo.git(&["checkout", "-b", "other"]).await?;
+    repo.write_file("file.txt", "theirs\n")?;
+    repo.git(&["commit", "-am", "Change file.txt on other"])
+        .await?;
+    repo.git(&["checkout", "-"]).await?;
+    repo.write_file("file.txt", "ours\n")?;
+    repo.git(&["commit", "-am", "Change file.txt"]).await?;
+    assert!(repo.git(&["merge", "other"]).await.is_err());
+
+    assert_eq!(GitRepository::get_unmerged_files()?, vec!["file.txt"]);
+    GitRepository::git_abort_pull().await?;
+    assert!(GitRepository::get_unmerged_files()?.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(repo.temp_dir.path().join("file.txt"))?,
+        "ours\n"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_git_commit_sets_committer() -> anyhow::Result<()> {
+    use super::{CommitSigning, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("file.txt", "contents\n")?;
+    repo.git(&["add", "file.txt"]).await?;
+
+    GitRepository::git_commit(
+        "Add file.txt",
+        "Jane Doe",
+        "jane@example.com",
+        ("CI Bot", "ci@example.com"),
+        false,
+        CommitSigning::GitConfig,
+        false,
+    )
+    .await?;
+
+    let git_repo = git2::Repository::open_from_env()?;
+    let commit = git_repo.head()?.peel_to_commit()?;
+    assert_eq!(commit.author().name(), Some("Jane Doe"));
+    assert_eq!(commit.author().email(), Some("jane@example.com"));
+    assert_eq!(commit.committer().name(), Some("CI Bot"));
+    assert_eq!(commit.committer().email(), Some("ci@example.com"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_files_with_conflict_markers() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file(
+        "conflicted.txt",
+        "<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\n",
+    )?;
+    repo.write_file("clean.md", "Title\n=======\n")?;
+    repo.git(&["add", "."]).await?;
+
+    let staged_files = GitRepository::get_staged_files()?;
+    let conflicted_files = GitRepository::get_files_with_conflict_markers(&staged_files)?;
+
+    assert_eq!(conflicted_files, vec![String::from("conflicted.txt")]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_summary_before_first_commit() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let _repo = TestRepo::new().await?;
+
+    assert!(!GitRepository::has_commits()?);
+    assert_eq!(GitRepository::get_commit_count()?, 0);
+    assert!(
+        GitRepository::get_commit_summary_table("Test User", "test@example.com")
+            .await?
+            .is_none()
+    );
+    assert!(GitRepository::get_head_commit_hash().is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_summary_after_first_commit() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("README.md", "hello\n")?;
+    repo.git(&["add", "README.md"]).await?;
+    repo.git(&["commit", "-m", "Initial commit"]).await?;
+
+    assert!(GitRepository::has_commits()?);
+    assert_eq!(GitRepository::get_commit_count()?, 1);
+    assert_eq!(GitRepository::get_short_stat()?, (1, 1, 0));
+    assert!(
+        GitRepository::get_commit_summary_table("Test User", "test@example.com")
+            .await?
+            .is_some()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_summary_after_git_commit_on_unborn_branch() -> anyhow::Result<()> {
+    use super::{CommitSigning, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("README.md", "hello\n")?;
+    GitRepository::git_add_all()?;
+    assert!(!GitRepository::has_commits()?);
+
+    let output = GitRepository::git_commit(
+        "Initial commit",
+        "Test User",
+        "test@example.com",
+        ("Test User", "test@example.com"),
+        false,
+        CommitSigning::GitConfig,
+        false,
+    )
+    .await?;
+
+    assert!(output.contains("Initial commit"));
+    assert_eq!(GitRepository::get_commit_count()?, 1);
+    assert!(
+        GitRepository::get_commit_summary_table("Test User", "test@example.com")
+            .await?
+            .is_some()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_match_pathspecs() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let changed_files = vec![
+        "Readme.md".to_owned(),
+        "src/git/mod.rs".to_owned(),
+        "src/git/tests.rs".to_owned(),
+        "src/main.rs".to_owned(),
+    ];
+    let pathspecs = vec!["src/git".to_owned(), "*.md".to_owned(), "docs/".to_owned()];
+
+    assert_eq!(
+        GitRepository::match_pathspecs(&pathspecs, &changed_files)?,
+        vec![
+            (
+                "src/git".to_owned(),
+                vec!["src/git/mod.rs".to_owned(), "src/git/tests.rs".to_owned()]
+            ),
+            ("*.md".to_owned(), vec!["Readme.md".to_owned()]),
+            ("docs/".to_owned(), vec![]),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_current_branch() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.git(&["checkout", "-b", "42-fix-login"]).await?;
+    assert_eq!(
+        GitRepository::get_current_branch()?,
+        Some("42-fix-login".to_owned())
+    );
+
+    repo.write_file("README.md", "hello\n")?;
+    repo.git(&["add", "README.md"]).await?;
+    repo.git(&["commit", "-m", "Initial commit"]).await?;
+    assert_eq!(
+        GitRepository::get_current_branch()?,
+        Some("42-fix-login".to_owned())
+    );
+
+    repo.git(&["checkout", "--detach"]).await?;
+    assert_eq!(GitRepository::get_current_branch()?, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_to_repo_path() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    std::fs::create_dir_all(repo.temp_dir.path().join("src/git"))?;
+    assert_eq!(GitRepository::to_repo_path("./src/main.rs")?, "src/main.rs");
+
+    env::set_current_dir(repo.temp_dir.path().join("src/git"))?;
+    assert_eq!(GitRepository::to_repo_path("mod.rs")?, "src/git/mod.rs");
+    assert_eq!(GitRepository::to_repo_path("../main.rs")?, "src/main.rs");
+    assert!(GitRepository::to_repo_path("../../../outside.rs").is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_signing_status_of_unsigned_commit() -> anyhow::Result<()> {
+    use super::{commit_table::SigningStatus, GitRepository};
+    use git2::Repository;
+
+    let repo = TestRepo::new().await?;
+    repo.git(&["config", "commit.gpgsign", "false"]).await?;
+    repo.write_file("README.md", "hello\n")?;
+    repo.git(&["add", "README.md"]).await?;
+    repo.git(&["commit", "-m", "Initial commit"]).await?;
+
+    let git_repo = Repository::open_from_env()?;
+    let head = git_repo.head()?.peel_to_commit()?.id();
+    assert!(GitRepository::get_signing_status(&git_repo, head)?.is_none());
+
+    repo.git(&["config", "commit.gpgsign", "true"]).await?;
+    let git_repo = Repository::open_from_env()?;
+    assert!(matches!(
+        GitRepository::get_signing_status(&git_repo, head)?,
+        Some(SigningStatus::Unsigned)
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_staged_file_diffs_context() -> anyhow::Result<()> {
+    use super::{DiffBase, DiffStyle, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    let lines = (1..=20)
+        .map(|i| format!("line {}\n", i))
+        .collect::<String>();
+    repo.write_file("file.txt", &lines)?;
+    repo.git(&["add", "file.txt"]).await?;
+    repo.git(&["commit", "-m", "Initial commit"]).await?;
+
+    repo.write_file("file.txt", lines.replace("line 10\n", "line ten\n"))?;
+    repo.git(&["add", "file.txt"]).await?;
+    let files = vec!["file.txt".to_owned()];
+
+    let count_context = |style| -> anyhow::Result<usize> {
+        Ok(
+            GitRepository::get_staged_file_diffs(&files, style, DiffBase::Head)?
+                .iter()
+                .filter(|line| line.starts_with("line "))
+                .count(),
+        )
+    };
+    assert_eq!(count_context(DiffStyle::Minimal)?, 0);
+    assert_eq!(count_context(DiffStyle::Unified)?, 6);
+    assert_eq!(count_context(DiffStyle::Full)?, 19);
+    assert_eq!(count_context(DiffStyle::Lines(0))?, 0);
+    assert_eq!(count_context(DiffStyle::Lines(1))?, 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_comment_char() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.git(&["config", "core.commentChar", "auto"]).await?;
+    assert_eq!(GitRepository::get_comment_char()?, '#');
+
+    repo.git(&["config", "core.commentChar", ";"]).await?;
+    assert_eq!(GitRepository::get_comment_char()?, ';');
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_amend_diff_includes_amended_commit() -> anyhow::Result<()> {
+    use super::{DiffBase, DiffStyle, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("base.txt", "base\n")?;
+    repo.git(&["add", "base.txt"]).await?;
+    repo.git(&["commit", "-m", "base"]).await?;
+    repo.write_file("first.txt", "first\n")?;
+    repo.git(&["add", "first.txt"]).await?;
+    repo.git(&["commit", "-m", "first"]).await?;
+    repo.write_file("second.txt", "second\n")?;
+    repo.git(&["add", "second.txt"]).await?;
+
+    let files = GitRepository::get_amend_files()?;
+    assert_eq!(files, vec!["first.txt", "second.txt"]);
+
+    let diff =
+        GitRepository::get_staged_file_diffs(&files, DiffStyle::Unified, DiffBase::HeadParent)?
+            .join("");
+    assert!(diff.contains("+first"));
+    assert!(diff.contains("+second"));
+
+    let diff =
+        GitRepository::get_staged_file_diffs(&files, DiffStyle::Unified, DiffBase::Head)?.join("");
+    assert!(!diff.contains("+first"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_changed_submodules() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    std::fs::create_dir(repo.temp_dir.path().join("lib"))?;
+    repo.write_file("lib/lib.rs", "fn lib() {}\n")?;
+    repo.git(&["-C", "lib", "init"]).await?;
+    repo.git(&["-C", "lib", "add", "lib.rs"]).await?;
+    repo.git(&[
+        "-C",
+        "lib",
+        "-c",
+        "user.name=Test User",
+        "-c",
+        "user.email=test@example.com",
+        "commit",
+        "-m",
+        "lib",
+    ])
+    .await?;
+    repo.git(&["submodule", "add", "./lib", "lib"]).await?;
+    repo.git(&["commit", "-m", "add lib"]).await?;
+    assert!(GitRepository::get_changed_submodules()?.is_empty());
+
+    repo.write_file("lib/lib.rs", "fn lib() { todo!() }\n")?;
+    assert_eq!(GitRepository::get_changed_submodules()?, vec!["lib"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_commit_diff() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("notes.txt", "first\n")?;
+    repo.git(&["add", "notes.txt"]).await?;
+    repo.git(&["commit", "-m", "Add notes"]).await?;
+    repo.write_file("notes.txt", "first\nsecond\n")?;
+    repo.git(&[
+        "commit",
+        "-am",
+        "Extend notes\n\nBecause one line was not enough.",
+    ])
+    .await?;
+
+    let diff = GitRepository::get_commit_diff("HEAD")?;
+    assert!(diff.contains("diff --git a/notes.txt b/notes.txt"));
+    assert!(diff.contains("+second\n"));
+    assert!(!diff.contains("+first"));
+    assert!(GitRepository::get_commit_diff("HEAD~1")?.contains("+first\n"));
+    assert_eq!(
+        GitRepository::get_commit_message("HEAD")?,
+        "Extend notes\n\nBecause one line was not enough."
+    );
+    assert!(GitRepository::get_commit_diff("missing").is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_worktree_changes() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("tracked.txt", "one\ntwo\n")?;
+    repo.git(&["add", "tracked.txt"]).await?;
+    repo.git(&["commit", "-m", "initial"]).await?;
+    repo.write_file("tracked.txt", "one\nthree\nfour\n")?;
+    repo.write_file("new.txt", "new\n")?;
+
+    let files = vec!["tracked.txt".to_owned(), "new.txt".to_owned()];
+    let changes = GitRepository::get_worktree_changes(&files)?;
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].path, "tracked.txt");
+    assert_eq!((changes[0].additions, changes[0].deletions), (2, 1));
+    assert!(changes[0].patch.contains("+three"));
+    assert_eq!(changes[1].path, "new.txt");
+    assert_eq!((changes[1].additions, changes[1].deletions), (1, 0));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_blame_authors() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("notes.txt", "one\ntwo\nthree\n")?;
+    repo.git(&["add", "notes.txt"]).await?;
+    repo.git(&[
+        "-c",
+        "user.name=Alice",
+        "-c",
+        "user.email=alice@example.com",
+        "commit",
+        "-m",
+        "Add notes",
+    ])
+    .await?;
+    repo.write_file("notes.txt", "one\ntwo\nthree\nfour\n")?;
+    repo.git(&[
+        "-c",
+        "user.name=Bob",
+        "-c",
+        "user.email=bob@example.com",
+        "commit",
+        "-am",
+        "Add four",
+    ])
+    .await?;
+
+    // Changes two of Alice's lines and one of Bob's, and adds a new one
+    repo.write_file("notes.txt", "one\nTWO\nTHREE\nFOUR\nfive\n")?;
+    repo.git(&["add", "notes.txt"]).await?;
+    let authors = GitRepository::get_blame_authors(&["notes.txt".to_owned()])?;
+    let authors = authors
+        .iter()
+        .map(|author| (author.name.as_str(), author.email.as_str(), author.lines))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        authors,
+        vec![
+            ("Alice", "alice@example.com", 2),
+            ("Bob", "bob@example.com", 1)
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_backup_index_undoes_staging() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("a.txt", "one\n")?;
+    repo.git(&["add", "a.txt"]).await?;
+    repo.git(&["commit", "-m", "Add a.txt"]).await?;
+    repo.write_file("a.txt", "two\n")?;
+    repo.write_file("b.txt", "new\n")?;
+    repo.git(&["add", "b.txt"]).await?;
+
+    let backup = GitRepository::backup_index()?;
+    GitRepository::git_add_all()?;
+    assert_eq!(GitRepository::get_staged_files()?, vec!["a.txt", "b.txt"]);
+    backup.restore()?;
+    assert_eq!(GitRepository::get_staged_files()?, vec!["b.txt"]);
+
+    // A backup dropped without restoring, e.g. by an aborted run, restores itself
+    let backup = GitRepository::backup_index()?;
+    GitRepository::git_add_all()?;
+    drop(backup);
+    assert_eq!(GitRepository::get_staged_files()?, vec!["b.txt"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_amend_files_without_commits() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("a.txt", "a\n")?;
+    repo.git(&["add", "a.txt"]).await?;
+
+    let error = GitRepository::get_amend_files().unwrap_err();
+    assert!(error.to_string().contains("no commits to amend yet"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_git_commit_signs_with_ssh_key() -> anyhow::Result<()> {
+    use super::{CommitSigning, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    let key_path = repo.temp_dir.path().join("signing_key");
+    let key_path = key_path.to_string_lossy();
+    let keygen = Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-f", &key_path])
+        .output()
+        .await;
+    if !keygen.is_ok_and(|output| output.status.success()) {
+        // SSH signing needs ssh-keygen
+        return Ok(());
+    }
+    repo.git(&["config", "gpg.format", "ssh"]).await?;
+    repo.write_file("file.txt", "contents\n")?;
+    repo.git(&["add", "file.txt"]).await?;
+
+    let error = GitRepository::git_commit(
+        "Add file.txt",
+        "Jane Doe",
+        "jane@example.com",
+        ("Jane Doe", "jane@example.com"),
+        false,
+        CommitSigning::Sign(Some("/nonexistent/key")),
+        false,
+    )
+    .await
+    .unwrap_err();
+    assert!(error.to_string().starts_with("Failed to sign the commit"));
+
+    GitRepository::git_commit(
+        "Add file.txt",
+        "Jane Doe",
+        "jane@example.com",
+        ("Jane Doe", "jane@example.com"),
+        false,
+        CommitSigning::Sign(Some(&key_path)),
+        false,
+    )
+    .await?;
+    let git_repo = git2::Repository::open_from_env()?;
+    let commit_id = git_repo.head()?.peel_to_commit()?.id();
+    assert!(git_repo.extract_signature(&commit_id, None).is_ok());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_git_commit_hook_failure_is_not_a_signing_error() -> anyhow::Result<()> {
+    use super::{CommitSigning, GitRepository};
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = TestRepo::new().await?;
+    let hook_path = repo.temp_dir.path().join(".git/hooks/pre-commit");
+    std::fs::write(
+        &hook_path,
+        "#!/bin/sh\necho 'Commits must be signed off' >&2\nexit 1\n",
+    )?;
+    std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))?;
+    repo.write_file("file.txt", "contents\n")?;
+    repo.git(&["add", "file.txt"]).await?;
+
+    let error = GitRepository::git_commit(
+        "Add file.txt",
+        "Jane Doe",
+        "jane@example.com",
+        ("Jane Doe", "jane@example.com"),
+        false,
+        CommitSigning::Sign(None),
+        false,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error.to_string(), "Commits must be signed off");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_git_commit_no_verify_skips_hooks() -> anyhow::Result<()> {
+    use super::{CommitSigning, GitRepository};
+
+    let repo = TestRepo::new().await?;
+    let hook_path = repo.temp_dir.path().join(".git/hooks/pre-commit");
+    std::fs::write(&hook_path, "#!/bin/sh\nexit 1\n")?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    repo.write_file("file.txt", "contents\n")?;
+    repo.git(&["add", "file.txt"]).await?;
+
+    let commit = |no_verify| {
+        GitRepository::git_commit(
+            "Add file.txt",
+            "Jane Doe",
+            "jane@example.com",
+            ("Jane Doe", "jane@example.com"),
+            false,
+            CommitSigning::GitConfig,
+            no_verify,
+        )
+    };
+    if cfg!(unix) {
+        assert!(commit(false).await.is_err());
+    }
+    commit(true).await?;
+    assert!(GitRepository::has_commits()?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_staging_records_deleted_files() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("a.txt", "a\n")?;
+    repo.write_file("b.txt", "b\n")?;
+    repo.git(&["add", "a.txt", "b.txt"]).await?;
+    repo.git(&["commit", "-m", "Add files"]).await?;
+    std::fs::remove_file(repo.temp_dir.path().join("a.txt"))?;
+    std::fs::remove_file(repo.temp_dir.path().join("b.txt"))?;
+    repo.write_file("c.txt", "c\n")?;
+
+    GitRepository::git_add(&[String::from("a.txt")]).await?;
+    assert_eq!(GitRepository::get_staged_files()?, vec!["a.txt"]);
+
+    GitRepository::git_add_all()?;
+    assert_eq!(
+        GitRepository::get_staged_files()?,
+        vec!["a.txt", "b.txt", "c.txt"]
+    );
+    let changes = GitRepository::get_staged_changes(
+        &GitRepository::get_staged_files()?,
+        super::DiffBase::Head,
+    )?;
+    assert_eq!(
+        changes
+            .iter()
+            .filter(|change| change.status == git2::Delta::Deleted)
+            .count(),
+        2
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_staged_files_include_renames_and_type_changes() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("old.txt", "old\n")?;
+    repo.write_file("link.txt", "target\n")?;
+    repo.git(&["add", "old.txt", "link.txt"]).await?;
+    repo.git(&["commit", "-m", "Add files"]).await?;
+    repo.git(&["mv", "old.txt", "new.txt"]).await?;
+    std::fs::remove_file(repo.temp_dir.path().join("link.txt"))?;
+    std::os::unix::fs::symlink("new.txt", repo.temp_dir.path().join("link.txt"))?;
+    repo.git(&["add", "link.txt"]).await?;
+
+    assert!(GitRepository::has_staged_changes()?);
+    assert_eq!(
+        GitRepository::get_staged_files()?,
+        vec!["link.txt", "new.txt", "old.txt"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_exclude_diff_files() -> anyhow::Result<()> {
+    use super::{ExcludedFile, GitRepository};
+
+    let files = vec![
+        "Cargo.lock".to_owned(),
+        "src/main.rs".to_owned(),
+        "web/app.min.js".to_owned(),
+        "web/package-lock.json".to_owned(),
+        "web/keep.min.js".to_owned(),
+    ];
+    let globs = vec![
+        "*.lock".to_owned(),
+        "*.min.js".to_owned(),
+        "package-lock.json".to_owned(),
+        "!keep.min.js".to_owned(),
+    ];
+
+    let (diff_files, excluded_files) = GitRepository::exclude_diff_files(&files, &globs)?;
+    assert_eq!(diff_files, vec!["src/main.rs", "web/keep.min.js"]);
+    assert_eq!(
+        excluded_files,
+        vec![
+            ExcludedFile {
+                path: "Cargo.lock".to_owned(),
+                pattern: "*.lock".to_owned(),
+            },
+            ExcludedFile {
+                path: "web/app.min.js".to_owned(),
+                pattern: "*.min.js".to_owned(),
+            },
+            ExcludedFile {
+                path: "web/package-lock.json".to_owned(),
+                pattern: "package-lock.json".to_owned(),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stage_from_tree_keeps_partially_staged_files() -> anyhow::Result<()> {
+    use super::GitRepository;
+
+    let repo = TestRepo::new().await?;
+    repo.write_file("a.txt", "a\n")?;
+    repo.write_file("b.txt", "b\n")?;
+    repo.git(&["add", "a.txt", "b.txt"]).await?;
+    repo.git(&["commit", "-m", "Add files"]).await?;
+
+    repo.write_file("a.txt", "a\nstaged\n")?;
+    repo.git(&["add", "a.txt"]).await?;
+    repo.write_file("a.txt", "a\nstaged\nunstaged\n")?;
+    repo.write_file("c.txt", "c\n")?;
+    repo.git(&["add", "c.txt"]).await?;
+    repo.git(&["rm", "-q", "b.txt"]).await?;
+    let tree = GitRepository::write_index_tree()?;
+
+    GitRepository::stage_from_tree(&tree, &["a.txt".to_owned(), "b.txt".to_owned()]).await?;
+    assert_eq!(GitRepository::get_staged_files()?, vec!["a.txt", "b.txt"]);
+    let staged = Command::new("git")
+        .args(["show", ":a.txt"])
+        .output()
+        .await?;
+    assert_eq!(staged.stdout, b"a\nstaged\n");
+
+    repo.git(&["commit", "-q", "-m", "Change a and remove b"])
+        .await?;
+    GitRepository::stage_from_tree(&tree, &["c.txt".to_owned()]).await?;
+    assert_eq!(GitRepository::get_staged_files()?, vec!["c.txt"]);
+    Ok(())
+}
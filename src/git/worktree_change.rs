@@ -0,0 +1,7 @@
+/// An unstaged change to a file, with its patch for previews.
+pub struct WorktreeChange {
+    pub path: String,
+    pub additions: usize,
+    pub deletions: usize,
+    pub patch: String,
+}
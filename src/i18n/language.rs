@@ -1,15 +1,78 @@
+use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Language {
     English,
-    // Add more languages as needed
+    French,
+    Spanish,
+    German,
+    Portuguese,
+    Italian,
+    Japanese,
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Language; 8] = [
+        Language::English,
+        Language::French,
+        Language::Spanish,
+        Language::German,
+        Language::Portuguese,
+        Language::Italian,
+        Language::Japanese,
+        Language::Chinese,
+    ];
+
+    /// The ISO 639-1 code, accepted as an alias of the name.
+    fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+            Language::Spanish => "es",
+            Language::German => "de",
+            Language::Portuguese => "pt",
+            Language::Italian => "it",
+            Language::Japanese => "ja",
+            Language::Chinese => "zh",
+        }
+    }
 }
 
 impl std::fmt::Display for Language {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Language::English => write!(f, "english"),
+            Language::French => write!(f, "french"),
+            Language::Spanish => write!(f, "spanish"),
+            Language::German => write!(f, "german"),
+            Language::Portuguese => write!(f, "portuguese"),
+            Language::Italian => write!(f, "italian"),
+            Language::Japanese => write!(f, "japanese"),
+            Language::Chinese => write!(f, "chinese"),
         }
     }
 }
+
+impl std::str::FromStr for Language {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim().to_lowercase();
+        Language::ALL
+            .into_iter()
+            .find(|language| language.to_string() == value || language.code() == value)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unsupported language: {}. Use one of: {}",
+                    s,
+                    Language::ALL
+                        .iter()
+                        .map(|language| language.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+}
@@ -27,20 +27,125 @@ impl I18n {
     }
 }
 
+fn translation(
+    commit_fix: &str,
+    commit_feat: &str,
+    commit_description: &str,
+    language: &str,
+) -> I18nTranslation {
+    I18nTranslation {
+        commit_fix: commit_fix.to_string(),
+        commit_feat: commit_feat.to_string(),
+        commit_description: commit_description.to_string(),
+        language: language.to_string(),
+    }
+}
+
 pub fn load_i18n() -> I18n {
     let mut translations = HashMap::new();
     translations.insert(
         Language::English,
-        I18nTranslation {
-            commit_fix: "fix(main.rs): Correct JSON parsing issue for joke response ".to_string(),
-            commit_feat: "feat(main.rs): Add error handling for API request ".to_string(),
-            commit_description: String::from("After further testing, it was determined that JSON response data for the joke endpoint contained leading/trailing white space. To fix the issue, string trimming was added to the JSON parsing step.\n\
+        translation(
+            "fix(main.rs): Correct JSON parsing issue for joke response ",
+            "feat(main.rs): Add error handling for API request ",
+            "After further testing, it was determined that JSON response data for the joke endpoint contained leading/trailing white space. To fix the issue, string trimming was added to the JSON parsing step.\n\
             To improve the error handling logic of the API request, a `match` expression was added to handle the case when the API request fails.\n\
             Changes made:\n\
             - The `serde_json::from_str` function now uses `trim()` function to remove leading/trailing spaces before data parsing.\n\
-            - A `match` expression now handles the `Err` case when making the API request.\n"),
-            language: "English".to_string(),
-        },
+            - A `match` expression now handles the `Err` case when making the API request.\n",
+            "English",
+        ),
+    );
+    translations.insert(
+        Language::French,
+        translation(
+            "fix(main.rs): Corriger le problème d'analyse JSON de la réponse de blague ",
+            "feat(main.rs): Ajouter la gestion des erreurs pour la requête API ",
+            "Après des tests supplémentaires, il a été déterminé que les données de réponse JSON du point de terminaison de blagues contenaient des espaces en début et en fin. Pour corriger le problème, un nettoyage des chaînes a été ajouté à l'étape d'analyse JSON.\n\
+            Pour améliorer la gestion des erreurs de la requête API, une expression `match` a été ajoutée pour traiter le cas où la requête API échoue.\n\
+            Modifications apportées :\n\
+            - La fonction `serde_json::from_str` utilise désormais `trim()` pour supprimer les espaces en début et en fin avant l'analyse des données.\n\
+            - Une expression `match` gère désormais le cas `Err` lors de la requête API.\n",
+            "French",
+        ),
+    );
+    translations.insert(
+        Language::Spanish,
+        translation(
+            "fix(main.rs): Corregir el problema de análisis JSON en la respuesta de chistes ",
+            "feat(main.rs): Añadir manejo de errores para la solicitud a la API ",
+            "Tras más pruebas, se determinó que los datos de respuesta JSON del endpoint de chistes contenían espacios en blanco al principio y al final. Para solucionarlo, se añadió el recorte de cadenas al paso de análisis JSON.\n\
+            Para mejorar la lógica de manejo de errores de la solicitud a la API, se añadió una expresión `match` que gestiona el caso en que la solicitud falla.\n\
+            Cambios realizados:\n\
+            - La función `serde_json::from_str` ahora usa `trim()` para eliminar los espacios al principio y al final antes de analizar los datos.\n\
+            - Una expresión `match` ahora gestiona el caso `Err` al realizar la solicitud a la API.\n",
+            "Spanish",
+        ),
+    );
+    translations.insert(
+        Language::German,
+        translation(
+            "fix(main.rs): JSON-Parsing-Problem bei der Witz-Antwort beheben ",
+            "feat(main.rs): Fehlerbehandlung für die API-Anfrage hinzufügen ",
+            "Weitere Tests haben ergeben, dass die JSON-Antwortdaten des Witz-Endpunkts führende und nachgestellte Leerzeichen enthielten. Um das Problem zu beheben, wurde beim JSON-Parsing ein Trimmen der Zeichenketten ergänzt.\n\
+            Um die Fehlerbehandlung der API-Anfrage zu verbessern, wurde ein `match`-Ausdruck hinzugefügt, der den Fall einer fehlgeschlagenen API-Anfrage behandelt.\n\
+            Änderungen:\n\
+            - Die Funktion `serde_json::from_str` verwendet jetzt `trim()`, um führende und nachgestellte Leerzeichen vor dem Parsen zu entfernen.\n\
+            - Ein `match`-Ausdruck behandelt jetzt den `Err`-Fall bei der API-Anfrage.\n",
+            "German",
+        ),
+    );
+    translations.insert(
+        Language::Portuguese,
+        translation(
+            "fix(main.rs): Corrigir problema de análise JSON na resposta de piadas ",
+            "feat(main.rs): Adicionar tratamento de erros para a requisição à API ",
+            "Após mais testes, foi constatado que os dados de resposta JSON do endpoint de piadas continham espaços em branco no início e no fim. Para corrigir o problema, foi adicionado o corte de strings na etapa de análise do JSON.\n\
+            Para melhorar o tratamento de erros da requisição à API, foi adicionada uma expressão `match` para tratar o caso em que a requisição falha.\n\
+            Alterações feitas:\n\
+            - A função `serde_json::from_str` agora usa `trim()` para remover espaços no início e no fim antes da análise dos dados.\n\
+            - Uma expressão `match` agora trata o caso `Err` ao fazer a requisição à API.\n",
+            "Portuguese",
+        ),
+    );
+    translations.insert(
+        Language::Italian,
+        translation(
+            "fix(main.rs): Correggere il problema di parsing JSON nella risposta delle barzellette ",
+            "feat(main.rs): Aggiungere la gestione degli errori per la richiesta API ",
+            "Dopo ulteriori test, è emerso che i dati di risposta JSON dell'endpoint delle barzellette contenevano spazi iniziali e finali. Per risolvere il problema, è stato aggiunto il trim delle stringhe nella fase di parsing JSON.\n\
+            Per migliorare la gestione degli errori della richiesta API, è stata aggiunta un'espressione `match` che gestisce il caso in cui la richiesta fallisce.\n\
+            Modifiche apportate:\n\
+            - La funzione `serde_json::from_str` ora usa `trim()` per rimuovere gli spazi iniziali e finali prima del parsing dei dati.\n\
+            - Un'espressione `match` ora gestisce il caso `Err` durante la richiesta API.\n",
+            "Italian",
+        ),
+    );
+    translations.insert(
+        Language::Japanese,
+        translation(
+            "fix(main.rs): ジョークのレスポンスのJSON解析の問題を修正 ",
+            "feat(main.rs): APIリクエストのエラー処理を追加 ",
+            "追加のテストの結果、ジョークのエンドポイントから返されるJSONレスポンスデータに前後の空白が含まれていることが判明しました。この問題を修正するため、JSON解析の手順に文字列のトリム処理を追加しました。\n\
+            APIリクエストのエラー処理を改善するため、APIリクエストが失敗した場合を処理する`match`式を追加しました。\n\
+            変更内容:\n\
+            - `serde_json::from_str`関数は、データを解析する前に`trim()`関数で前後の空白を削除するようになりました。\n\
+            - `match`式がAPIリクエスト時の`Err`ケースを処理するようになりました。\n",
+            "Japanese",
+        ),
+    );
+    translations.insert(
+        Language::Chinese,
+        translation(
+            "fix(main.rs): 修复笑话响应的 JSON 解析问题 ",
+            "feat(main.rs): 为 API 请求添加错误处理 ",
+            "经过进一步测试，发现笑话接口返回的 JSON 响应数据包含首尾空白。为解决该问题，在 JSON 解析步骤中加入了字符串裁剪。\n\
+            为改进 API 请求的错误处理逻辑，添加了一个 `match` 表达式来处理 API 请求失败的情况。\n\
+            所做的更改：\n\
+            - `serde_json::from_str` 函数现在先使用 `trim()` 函数去除首尾空格，再解析数据。\n\
+            - `match` 表达式现在会处理发起 API 请求时的 `Err` 情况。\n",
+            "Chinese",
+        ),
     );
 
     I18n::new(translations)
@@ -1,4 +1,7 @@
-use anyhow::Error;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use anyhow::{anyhow, Error};
 use colored::Colorize;
 use structopt::StructOpt;
 
@@ -7,10 +10,15 @@ mod git;
 mod i18n;
 mod utils;
 
-use commands::{get_service, Command};
-use log::info;
+use commands::{ensure_api_key, get_service, Command};
+use log::{info, warn};
 use textwrap::fill;
 use utils::intro;
+
+/// How long an aborted commit gets to clean up, e.g. to restore the index,
+/// before autocommit exits anyway.
+const ABORT_GRACE: Duration = Duration::from_secs(1);
+
 #[derive(Debug, StructOpt)]
 #[structopt(
     name = "autocommit",
@@ -18,44 +26,184 @@ use utils::intro;
     about = "A powerful CLI tool that helps you create professional and meaningful commits with ease, using AI to generate impressive commit messages in seconds. Take control of your code history and make it shine with autocommit!"
 )]
 struct Cli {
+    #[structopt(
+        long,
+        global = true,
+        help = "Name of the config profile to use, stored in ~/.autocommit.profiles/<name>.toml"
+    )]
+    profile: Option<String>,
+
+    #[structopt(
+        long,
+        global = true,
+        help = "Don't clear the screen on start, also set with AUTOCOMMIT_NO_CLEAR=1"
+    )]
+    no_clear: bool,
+
+    #[structopt(long, global = true, help = "Don't show the intro banner")]
+    no_intro: bool,
+
     #[structopt(subcommand)]
     command: Command,
 }
 
-#[tokio::main]
-async fn main() {
-    println!("{esc}c", esc = 27 as char);
-    env_logger::init();
-    intro("Autocommit");
+fn main() -> ExitCode {
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to start the async runtime");
+    let exit_code = runtime.block_on(run());
+    // Don't wait on an aborted commit that is still stuck in a prompt
+    runtime.shutdown_timeout(ABORT_GRACE);
+    exit_code
+}
 
+async fn run() -> ExitCode {
     let cli = Cli::from_args();
 
+    // Keep the output clean when piped or embedded in scripts
+    let is_tty = atty::is(atty::Stream::Stdout)
+        && !matches!(&cli.command, Command::CommitCommand(commit) if commit.print_only());
+    if is_tty && !cli.no_clear && !env_flag("AUTOCOMMIT_NO_CLEAR") {
+        println!("{esc}c", esc = 27 as char);
+    }
+    env_logger::init();
+    load_dotenv();
+    if is_tty && !cli.no_intro {
+        intro("Autocommit");
+    }
+
     match cli.command {
-        Command::ConfigCommand(config) => match config.run().await {
+        Command::ConfigCommand(config) => match config.run(cli.profile.as_deref()).await {
             Ok(_) => (),
             Err(e) => {
                 handle_error(e);
             }
         },
         Command::CommitCommand(mut commit) => {
-            let service = match get_service().await {
+            let mut service = match get_service(cli.profile.as_deref()).await {
                 Ok(s) => s,
                 Err(e) => {
                     handle_error(e);
-                    return;
+                    return ExitCode::SUCCESS;
                 }
             };
+            if commit.uses_api() {
+                if let Err(e) = ensure_api_key(&mut service, cli.profile.as_deref()).await {
+                    handle_error(e);
+                    return ExitCode::SUCCESS;
+                }
+            }
+
+            let timeout = commit.timeout();
+            // Run on its own task so a blocking prompt can't keep the timeout from firing
+            let mut run = tokio::spawn(async move { commit.run(service.get_config()).await });
+            let result = match timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, &mut run).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        // Dropping the aborted task restores the index and
+                        // clears the spinner
+                        run.abort();
+                        let _ = tokio::time::timeout(ABORT_GRACE, run).await;
+                        handle_error(anyhow!(
+                            "Timed out after {} seconds, aborting",
+                            timeout.as_secs()
+                        ));
+                        return ExitCode::FAILURE;
+                    }
+                },
+                None => run.await,
+            }
+            .unwrap_or_else(|e| Err(anyhow!("Commit task failed: {}", e)));
 
-            match commit.run(service.get_config()).await {
-                Ok(_) => (),
+            match result {
+                Ok(outcome) => {
+                    info!(
+                        "Commit outcome: committed={}, hash={:?}, message={:?}, pushed={}",
+                        outcome.committed, outcome.hash, outcome.message, outcome.pushed
+                    );
+                }
+                Err(e) => {
+                    handle_error(e);
+                }
+            }
+        }
+        Command::ReleaseNotes(release_notes) => {
+            let service = match get_service(cli.profile.as_deref()).await {
+                Ok(s) => s,
                 Err(e) => {
                     handle_error(e);
+                    return ExitCode::SUCCESS;
                 }
+            };
+
+            if let Err(e) = release_notes.run(service.get_config()).await {
+                handle_error(e);
+            }
+        }
+        Command::Explain(explain) => {
+            let service = match get_service(cli.profile.as_deref()).await {
+                Ok(s) => s,
+                Err(e) => {
+                    handle_error(e);
+                    return ExitCode::SUCCESS;
+                }
+            };
+
+            if let Err(e) = explain.run(service.get_config()).await {
+                handle_error(e);
+            }
+        }
+        Command::Models(models) => {
+            let service = match get_service(cli.profile.as_deref()).await {
+                Ok(s) => s,
+                Err(e) => {
+                    handle_error(e);
+                    return ExitCode::SUCCESS;
+                }
+            };
+
+            if let Err(e) = models.run(service.get_config()).await {
+                handle_error(e);
+            }
+        }
+        Command::Hook(hook) => {
+            if let Err(e) = hook.run().await {
+                handle_error(e);
+            }
+        }
+        Command::Check(check) => {
+            let service = match get_service(cli.profile.as_deref()).await {
+                Ok(s) => s,
+                Err(e) => {
+                    handle_error(e);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            if let Err(e) = check.run(service.get_config()).await {
+                handle_error(e);
+                // Let a commit-msg hook reject the commit
+                return ExitCode::FAILURE;
             }
         }
     }
 
     info!("Autocommit finished successfully");
+    ExitCode::SUCCESS
+}
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn load_dotenv() {
+    if env_flag("AUTOCOMMIT_LOAD_DOTENV") {
+        match dotenvy::dotenv() {
+            Ok(path) => info!("Loaded environment from {}", path.display()),
+            Err(err) => warn!("Failed to load .env file: {}", err),
+        }
+    }
 }
 
 fn handle_error(e: Error) {
@@ -0,0 +1,31 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use log::warn;
+
+/// Changes the working directory until dropped, then changes it back, even
+/// when the work in between fails or is aborted.
+pub struct CurrentDirGuard {
+    original: PathBuf,
+}
+
+impl CurrentDirGuard {
+    pub fn change_to(path: &Path) -> anyhow::Result<Self> {
+        let original = std::env::current_dir().context("Failed to get the current directory")?;
+        std::env::set_current_dir(path)
+            .with_context(|| format!("Failed to change to {}", path.display()))?;
+        Ok(Self { original })
+    }
+}
+
+impl Drop for CurrentDirGuard {
+    fn drop(&mut self) {
+        if let Err(e) = std::env::set_current_dir(&self.original) {
+            warn!(
+                "Failed to change back to {}: {}",
+                self.original.display(),
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::{path::Path, process::Command};
+
+use anyhow::{anyhow, Context};
+
+/// Opens `path` in `editor` and returns the edited file contents once the
+/// editor exits. The editor command may contain arguments, e.g. `code --wait`.
+pub fn edit_file(editor: &str, path: &Path, content: &str) -> anyhow::Result<String> {
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    open_files(editor, &[path])?;
+    std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// Opens existing files in `editor` and waits for it to exit.
+pub fn open_files(editor: &str, paths: &[&Path]) -> anyhow::Result<()> {
+    let status = if cfg!(windows) {
+        let quoted_paths = paths
+            .iter()
+            .map(|path| format!("\"{}\"", path.display()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Command::new("cmd")
+            .arg("/C")
+            .arg(format!("{} {}", editor, quoted_paths))
+            .status()
+    } else {
+        Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$@\"", editor))
+            .arg(editor)
+            .args(paths)
+            .status()
+    }
+    .map_err(|e| anyhow!("Failed to launch editor '{}': {}", editor, e))?;
+
+    if !status.success() {
+        return Err(anyhow!("Editor '{}' exited with {}", editor, status));
+    }
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Older entries are dropped first once the cache holds this many.
+const MAX_CACHE_ENTRIES: usize = 100;
+
+/// Entries older than a week are no longer used.
+const MAX_CACHE_AGE_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// A generated message along with the temperature it was sampled with.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CachedMessage {
+    pub message: String,
+    pub temperature: Option<f32>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheEntry {
+    created_at: u64,
+    messages: Vec<CachedMessage>,
+}
+
+/// Messages generated before, keyed by a hash of everything that affects
+/// them, stored in `~/.autocommit.cache`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MessageCache {
+    #[serde(default)]
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+impl MessageCache {
+    fn path() -> anyhow::Result<PathBuf> {
+        dirs::home_dir()
+            .map(|mut path| {
+                path.push(".autocommit.cache");
+                path
+            })
+            .ok_or_else(|| anyhow!("Could not determine cache path"))
+    }
+
+    /// Seconds since the Unix epoch, the unit of the cache's timestamps.
+    pub fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs())
+    }
+
+    /// Hashes the parts of a request into a cache key that stays the same
+    /// across Rust versions, unlike `DefaultHasher`'s.
+    pub fn key(parts: &[&str]) -> String {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            // Prefix each part with its length so "ab", "c" and "a", "bc" differ
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Loads the cache, starting over if the file is missing or unreadable.
+    pub async fn load() -> anyhow::Result<Self> {
+        let path = Self::path()?;
+        let contents = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(serde_json::from_str(&contents).unwrap_or_default())
+    }
+
+    pub async fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path()?;
+        let contents = serde_json::to_string(self).context("Failed to serialize cache")?;
+        tokio::fs::write(&path, contents)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// The messages cached under `key`, unless they have expired by `now`.
+    pub fn get(&self, key: &str, now: u64) -> Option<&[CachedMessage]> {
+        self.entries
+            .get(key)
+            .filter(|entry| now.saturating_sub(entry.created_at) <= MAX_CACHE_AGE_SECS)
+            .map(|entry| entry.messages.as_slice())
+    }
+
+    /// Caches `messages` under `key`, dropping expired entries and then the
+    /// oldest ones to stay within the size bound.
+    pub fn insert(&mut self, key: String, messages: Vec<CachedMessage>, now: u64) {
+        self.entries.insert(
+            key,
+            CacheEntry {
+                created_at: now,
+                messages,
+            },
+        );
+        self.entries
+            .retain(|_, entry| now.saturating_sub(entry.created_at) <= MAX_CACHE_AGE_SECS);
+        while self.entries.len() > MAX_CACHE_ENTRIES {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.created_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
@@ -1,9 +1,25 @@
+mod current_dir;
+mod editor;
 mod is_unicode_supported;
+mod message_cache;
+mod notification;
 mod openai;
 mod prompts;
 mod spinner;
+mod state;
+#[cfg(test)]
+pub(crate) mod tests;
 
+pub use current_dir::CurrentDirGuard;
+pub use editor::{edit_file, open_files};
 pub use is_unicode_supported::get_unicode_string;
-pub use openai::{generate_message, Message, MessageRole};
-pub use prompts::{intro, outro};
+pub use message_cache::{CachedMessage, MessageCache};
+pub use notification::send_notification;
+pub use openai::{
+    generate_message, generate_message_streaming, http_client, list_models, redact_url_credentials,
+    AzureDeployment, ChatBackend, Message, MessageRole, OAIConfig, OAIModel, OnProgress,
+    SUPPORTED_MODELS,
+};
+pub use prompts::{assume_yes, intro, outro, set_assume_yes, set_ui_on_stderr, ui_on_stderr};
 pub use spinner::spinner;
+pub use state::State;
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use log::debug;
+use notify_rust::Notification;
+
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Shows a desktop notification. Failures are only logged, since not every
+/// environment has a notification service, e.g. SSH sessions or CI.
+pub async fn send_notification(summary: &str, body: &str) {
+    let mut notification = Notification::new();
+    notification
+        .appname("autocommit")
+        .summary(summary)
+        .body(body);
+
+    let task = tokio::task::spawn_blocking(move || notification.show().map(|_| ()));
+    match tokio::time::timeout(NOTIFICATION_TIMEOUT, task).await {
+        Ok(Ok(Ok(()))) => debug!("Notification sent"),
+        Ok(Ok(Err(e))) => debug!("Failed to send notification: {}", e),
+        Ok(Err(e)) => debug!("Notification task failed: {}", e),
+        Err(_) => debug!("Timed out sending notification"),
+    }
+}
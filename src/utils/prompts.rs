@@ -1,7 +1,24 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use colored::*;
 
 use crate::utils::get_unicode_string;
 
+/// Whether prompts take their default answer instead of asking.
+static ASSUME_YES: AtomicBool = AtomicBool::new(false);
+/// Whether progress output goes to stderr, leaving stdout for the result.
+static UI_ON_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Prints a line of progress output to stdout, or to stderr once
+/// `set_ui_on_stderr` was called.
+pub(super) fn print_ui(text: &str) {
+    if ui_on_stderr() {
+        eprintln!("{}", text);
+    } else {
+        println!("{}", text);
+    }
+}
+
 struct BarColors {
     bar: Color,
     text: Color,
@@ -10,12 +27,12 @@ struct BarColors {
 fn print_outro(message: &str, colors: &BarColors) {
     let s_bar = get_unicode_string("│", "|");
     let s_bar_end = get_unicode_string("└", "—");
-    println!(
+    print_ui(&format!(
         "{}\n{} {}",
         s_bar.color(colors.bar),
         s_bar_end.color(colors.bar),
         message.color(colors.text)
-    );
+    ));
 }
 
 fn print_intro(title: &str, colors: &BarColors) {
@@ -25,11 +42,11 @@ fn print_intro(title: &str, colors: &BarColors) {
     if max_len > 80 {
         panic!("Title is too long to fit within the bars.");
     }
-    println!(
+    print_ui(&format!(
         "{}  {}",
         s_bar_start.color(colors.bar),
         title.color(colors.text)
-    );
+    ));
 }
 
 pub fn intro(title: &str) {
@@ -55,3 +72,23 @@ pub fn outro(title: &str) {
     };
     print_outro(title, &colors);
 }
+
+/// Makes every prompt take its default answer, for runs without a user,
+/// e.g. in CI.
+pub fn set_assume_yes(assume_yes: bool) {
+    ASSUME_YES.store(assume_yes, Ordering::SeqCst);
+}
+
+pub fn assume_yes() -> bool {
+    ASSUME_YES.load(Ordering::SeqCst)
+}
+
+/// Sends progress output to stderr, so stdout only carries the result, e.g.
+/// the message a git hook reads.
+pub fn set_ui_on_stderr(on_stderr: bool) {
+    UI_ON_STDERR.store(on_stderr, Ordering::SeqCst);
+}
+
+pub fn ui_on_stderr() -> bool {
+    UI_ON_STDERR.load(Ordering::SeqCst)
+}
@@ -4,6 +4,8 @@ use std::time::{Duration, Instant};
 
 use crate::utils::get_unicode_string;
 
+use super::prompts::print_ui;
+
 pub struct Spinner {
     pb: ProgressBar,
     start_time: Instant,
@@ -31,6 +33,11 @@ impl Spinner {
         self.start_time = Instant::now();
     }
 
+    /// Replaces the message without restarting the elapsed time.
+    pub fn set_message(&self, message: &str) {
+        self.pb.set_message(message.to_string());
+    }
+
     pub fn stop(&mut self, message: &str) {
         let elapsed = self.start_time.elapsed();
         let elapsed_secs = elapsed.as_secs();
@@ -53,12 +60,21 @@ impl Spinner {
                 .template("{spinner:.green} {msg}")
                 .unwrap(),
         );
-        println!("{}", s_bar);
+        print_ui(&s_bar.to_string());
         self.pb
             .finish_with_message(format!("{} (elapsed time: {})", message, elapsed_str));
     }
 }
 
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        // Clear spinners that were interrupted, e.g. when the run times out
+        if !self.pb.is_finished() {
+            self.pb.finish_and_clear();
+        }
+    }
+}
+
 pub fn spinner() -> Spinner {
     Spinner::new("", 50)
 }
@@ -0,0 +1,54 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+
+/// Small bits of state remembered between runs, stored in
+/// `~/.autocommit.state`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct State {
+    /// The last remote pushed to, keyed by repository path.
+    #[serde(default)]
+    last_remotes: BTreeMap<String, String>,
+}
+
+impl State {
+    fn path() -> anyhow::Result<PathBuf> {
+        dirs::home_dir()
+            .map(|mut path| {
+                path.push(".autocommit.state");
+                path
+            })
+            .ok_or_else(|| anyhow!("Could not determine state path"))
+    }
+
+    /// Loads the state, starting over if the file is missing or unreadable.
+    pub async fn load() -> anyhow::Result<Self> {
+        let path = Self::path()?;
+        let contents = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        Ok(toml::from_str(&contents).unwrap_or_default())
+    }
+
+    pub async fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path()?;
+        let contents = toml::to_string(self).context("Failed to serialize state")?;
+        tokio::fs::write(&path, contents)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn get_last_remote(&self, repo_path: &str) -> Option<&str> {
+        self.last_remotes
+            .get(repo_path)
+            .map(|remote| remote.as_str())
+    }
+
+    pub fn set_last_remote(&mut self, repo_path: &str, remote: &str) {
+        self.last_remotes
+            .insert(repo_path.to_owned(), remote.to_owned());
+    }
+}
@@ -0,0 +1,625 @@
+/// Turns on `--yes` until dropped, so it doesn't leak into other tests.
+pub(crate) struct AssumeYes;
+
+impl AssumeYes {
+    pub(crate) fn on() -> Self {
+        super::set_assume_yes(true);
+        Self
+    }
+}
+
+impl Drop for AssumeYes {
+    fn drop(&mut self) {
+        super::set_assume_yes(false);
+    }
+}
+
+/// Points the home directory at an empty temporary directory until dropped,
+/// so files autocommit keeps there start out missing.
+pub(crate) struct TempHome {
+    pub(crate) dir: tempfile::TempDir,
+    original: Option<std::ffi::OsString>,
+}
+
+impl TempHome {
+    pub(crate) fn new() -> anyhow::Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let original = std::env::var_os("HOME");
+        std::env::set_var("HOME", dir.path());
+        Ok(Self { dir, original })
+    }
+}
+
+impl Drop for TempHome {
+    fn drop(&mut self) {
+        match &self.original {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+    }
+}
+
+#[test]
+fn test_chat_completions_url() {
+    use super::openai::chat_completions_url;
+
+    let expected = "https://proxy.example.com/v1/chat/completions";
+    assert_eq!(chat_completions_url("https://proxy.example.com"), expected);
+    assert_eq!(chat_completions_url("https://proxy.example.com/"), expected);
+    assert_eq!(
+        chat_completions_url("https://proxy.example.com/v1"),
+        expected
+    );
+    assert_eq!(
+        chat_completions_url("https://proxy.example.com/v1/"),
+        expected
+    );
+    assert_eq!(
+        chat_completions_url("https://proxy.example.com/v1/chat/completions"),
+        expected
+    );
+    assert_eq!(
+        chat_completions_url("https://proxy.example.com/openai"),
+        "https://proxy.example.com/openai/v1/chat/completions"
+    );
+}
+
+#[test]
+fn test_azure_chat_completions_url() {
+    use super::{openai::azure_chat_completions_url, AzureDeployment};
+
+    let azure = AzureDeployment {
+        deployment: String::from("gpt-4o-prod"),
+        api_version: String::from("2024-02-01"),
+    };
+    assert_eq!(
+        azure_chat_completions_url("https://example.openai.azure.com/", &azure),
+        "https://example.openai.azure.com/openai/deployments/gpt-4o-prod/chat/completions?api-version=2024-02-01"
+    );
+}
+
+#[test]
+fn test_supported_models_parse() {
+    use super::{OAIModel, SUPPORTED_MODELS};
+    use std::str::FromStr;
+
+    for model in SUPPORTED_MODELS {
+        let parsed = OAIModel::from_str(model).unwrap();
+        assert_eq!(parsed.to_string(), model);
+        let serialized = toml::Value::try_from(&parsed).unwrap();
+        assert_eq!(serialized.as_str(), Some(model));
+    }
+    assert!(OAIModel::from_str("").is_err());
+
+    for model in ["ft:gpt-3.5-turbo:org::abc", "my-model"] {
+        let parsed = OAIModel::from_str(model).unwrap();
+        assert!(!parsed.is_known());
+        assert_eq!(parsed.to_string(), model);
+        let serialized = toml::Value::try_from(&parsed).unwrap();
+        assert_eq!(serialized.as_str(), Some(model));
+    }
+}
+
+#[test]
+fn test_retry_delays() {
+    use super::openai::{backoff_delay, parse_retry_after};
+    use std::time::Duration;
+
+    assert_eq!(parse_retry_after("3"), Some(Duration::from_secs(3)));
+    assert_eq!(parse_retry_after(" 0.5 "), Some(Duration::from_millis(500)));
+    assert_eq!(parse_retry_after("3600"), Some(Duration::from_secs(60)));
+    assert_eq!(parse_retry_after("-1"), None);
+    assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+
+    let delays = (0..4).map(backoff_delay).collect::<Vec<_>>();
+    assert_eq!(delays, [1, 2, 4, 8].map(Duration::from_secs).to_vec());
+}
+
+#[tokio::test]
+async fn test_client_errors_are_not_retried() -> anyhow::Result<()> {
+    use super::{generate_message, openai::is_retryable, Message, MessageRole, OAIConfig};
+    use std::sync::atomic::Ordering;
+    use surf::StatusCode;
+
+    assert!(is_retryable(StatusCode::TooManyRequests));
+    assert!(is_retryable(StatusCode::RequestTimeout));
+    assert!(is_retryable(StatusCode::InternalServerError));
+    assert!(is_retryable(StatusCode::ServiceUnavailable));
+    assert!(!is_retryable(StatusCode::BadRequest));
+    assert!(!is_retryable(StatusCode::Unauthorized));
+    assert!(!is_retryable(StatusCode::Forbidden));
+
+    let (api_host, requests) = serve_with_status(|_| {
+        (
+            "401 Unauthorized",
+            "application/json",
+            String::from(r#"{"error":{"message":"Incorrect API key provided"}}"#),
+        )
+    })
+    .await?;
+    let prompt = vec![Message::new(MessageRole::User, String::from("diff"))];
+    let error = generate_message(&prompt, OAIConfig::new(api_host, "sk-wrong"), &None, &[])
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("Incorrect API key provided"));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+/// Serves chat completions whose contents are taken from `contents` in turn,
+/// repeating the last one. Returns the API host and the number of requests.
+async fn serve_completions(
+    contents: Vec<&'static str>,
+) -> anyhow::Result<(String, std::sync::Arc<std::sync::atomic::AtomicUsize>)> {
+    serve(move |index| {
+        let content = contents[index.min(contents.len() - 1)];
+        let body = format!(
+            r#"{{"id":"chatcmpl-1","object":"chat.completion","created":0,"model":"gpt-3.5-turbo","choices":[{{"index":0,"message":{{"role":"assistant","content":"{}"}},"finish_reason":"stop"}}]}}"#,
+            content
+        );
+        ("application/json", body)
+    })
+    .await
+}
+
+/// Serves a chat completion streamed in `deltas`.
+async fn serve_stream(
+    deltas: Vec<&'static str>,
+) -> anyhow::Result<(String, std::sync::Arc<std::sync::atomic::AtomicUsize>)> {
+    serve(move |_| {
+        let mut body = deltas
+            .iter()
+            .map(|delta| {
+                format!(
+                    "data: {{\"choices\":[{{\"index\":0,\"delta\":{{\"content\":\"{}\"}}}}]}}\n\n",
+                    delta
+                )
+            })
+            .collect::<String>();
+        body.push_str("data: [DONE]\n\n");
+        ("text/event-stream", body)
+    })
+    .await
+}
+
+/// Answers every request with the content type and body `respond` returns
+/// for its index. Returns the API host and the number of requests.
+async fn serve(
+    respond: impl Fn(usize) -> (&'static str, String) + Send + 'static,
+) -> anyhow::Result<(String, std::sync::Arc<std::sync::atomic::AtomicUsize>)> {
+    serve_with_status(move |index| {
+        let (content_type, body) = respond(index);
+        ("200 OK", content_type, body)
+    })
+    .await
+}
+
+/// Like `serve`, with the status line also taken from `respond`.
+async fn serve_with_status(
+    respond: impl Fn(usize) -> (&'static str, &'static str, String) + Send + 'static,
+) -> anyhow::Result<(String, std::sync::Arc<std::sync::atomic::AtomicUsize>)> {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let api_host = format!("http://{}", listener.local_addr()?);
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            let header_end = loop {
+                let Ok(read) = stream.read(&mut buffer).await else {
+                    return;
+                };
+                request.extend_from_slice(&buffer[..read]);
+                if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                    break end + 4;
+                }
+            };
+            let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+            if headers.contains("expect: 100-continue") {
+                let _ = stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").await;
+            }
+            let content_length = headers
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .and_then(|length| length.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+            while request.len() < header_end + content_length {
+                let Ok(read) = stream.read(&mut buffer).await else {
+                    return;
+                };
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let index = counter.fetch_add(1, Ordering::SeqCst);
+            let (status, content_type, body) = respond(index);
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        }
+    });
+    Ok((api_host, requests))
+}
+
+#[tokio::test]
+async fn test_generate_message_retries_empty_messages() -> anyhow::Result<()> {
+    use super::{generate_message, Message, MessageRole, OAIConfig};
+    use std::sync::atomic::Ordering;
+
+    let prompt = vec![Message::new(MessageRole::User, String::from("diff"))];
+
+    let (api_host, requests) = serve_completions(vec!["", "  \\n", "Add tests"]).await?;
+    let message =
+        generate_message(&prompt, OAIConfig::new(api_host, "sk-test"), &None, &[]).await?;
+    assert_eq!(message, "Add tests");
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+    let (api_host, requests) = serve_completions(vec![""]).await?;
+    let error = generate_message(&prompt, OAIConfig::new(api_host, "sk-test"), &None, &[])
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("empty message"));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_message_streaming() -> anyhow::Result<()> {
+    use super::{generate_message_streaming, Message, MessageRole, OAIConfig};
+
+    let prompt = vec![Message::new(MessageRole::User, String::from("diff"))];
+    let deltas = vec!["Add ", "streaming ", "support\\n\\n- Parse SSE chunks"];
+
+    let (api_host, _) = serve_stream(deltas.clone()).await?;
+    let mut received = Vec::new();
+    let mut on_progress = |streamed: &str| received.push(streamed.to_owned());
+    let message = generate_message_streaming(
+        &prompt,
+        OAIConfig::new(api_host, "sk-test"),
+        &None,
+        &[],
+        &mut on_progress,
+    )
+    .await?;
+    assert_eq!(
+        received,
+        vec![
+            "",
+            "Add ",
+            "Add streaming ",
+            "Add streaming support\n\n- Parse SSE chunks"
+        ]
+    );
+    assert_eq!(received.last(), Some(&message));
+
+    // An empty message is retried, and what it streamed is dropped
+    let (api_host, _) = serve(|index| {
+        let content = if index == 0 { "  " } else { "Add tests" };
+        let body = format!(
+            "data: {{\"choices\":[{{\"index\":0,\"delta\":{{\"content\":\"{}\"}}}}]}}\n\ndata: [DONE]\n\n",
+            content
+        );
+        ("text/event-stream", body)
+    })
+    .await?;
+    let mut received = Vec::new();
+    let mut on_progress = |streamed: &str| received.push(streamed.to_owned());
+    let message = generate_message_streaming(
+        &prompt,
+        OAIConfig::new(api_host, "sk-test"),
+        &None,
+        &[],
+        &mut on_progress,
+    )
+    .await?;
+    assert_eq!(message, "Add tests");
+    assert_eq!(received, vec!["", "  ", "", "Add tests"]);
+
+    // Hosts without streaming support answer with a whole completion
+    let (api_host, _) = serve_completions(vec!["Add tests"]).await?;
+    let message = generate_message_streaming(
+        &prompt,
+        OAIConfig::new(api_host, "sk-test"),
+        &None,
+        &[],
+        &mut |_| {},
+    )
+    .await?;
+    assert_eq!(message, "Add tests");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_message_times_out() -> anyhow::Result<()> {
+    use super::{generate_message, Message, MessageRole, OAIConfig};
+    use std::time::{Duration, Instant};
+
+    // Accepts connections but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let api_host = format!("http://{}", listener.local_addr()?);
+    tokio::spawn(async move {
+        let mut connections = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            connections.push(stream);
+        }
+    });
+
+    let prompt = vec![Message::new(MessageRole::User, String::from("diff"))];
+    let config = OAIConfig::new(api_host.clone(), "sk-test").timeout(Some(Duration::from_secs(1)));
+    let started = Instant::now();
+    let error = generate_message(&prompt, config, &None, &[])
+        .await
+        .unwrap_err();
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert!(error
+        .to_string()
+        .contains(&format!("Request to {} timed out after 1s", api_host)));
+
+    Ok(())
+}
+
+#[test]
+fn test_anthropic_request_moves_system_messages() {
+    use super::{openai::AnthropicRequest, Message, MessageRole, OAIConfig, OAIModel};
+
+    let messages = vec![
+        Message::new(MessageRole::System, String::from("Write commit messages.")),
+        Message::new(MessageRole::User, String::from("diff")),
+        Message::new(MessageRole::Assistant, String::from("Add tests")),
+        Message::new(MessageRole::User, String::from("another diff")),
+    ];
+    let config = OAIConfig::new("https://api.anthropic.com", "sk-ant-test");
+    let model = OAIModel::Other(String::from("claude-haiku-4-5"));
+    let request = serde_json::to_value(AnthropicRequest::new(&model, &messages, &config)).unwrap();
+
+    assert_eq!(request["model"], "claude-haiku-4-5");
+    assert_eq!(request["system"], "Write commit messages.");
+    let roles = request["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["role"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(roles, ["user", "assistant", "user"]);
+    assert!(request.get("top_p").is_none());
+}
+
+#[tokio::test]
+async fn test_generate_message_with_anthropic() -> anyhow::Result<()> {
+    use super::{generate_message, ChatBackend, Message, MessageRole, OAIConfig};
+
+    let (api_host, requests) = serve(|_| {
+        (
+            "application/json",
+            String::from(
+                r#"{"id":"msg_1","type":"message","role":"assistant","content":[{"type":"text","text":"Add tests"}],"stop_reason":"end_turn"}"#,
+            ),
+        )
+    })
+    .await?;
+    let prompt = vec![
+        Message::new(MessageRole::System, String::from("Write commit messages.")),
+        Message::new(MessageRole::User, String::from("diff")),
+    ];
+    let config = OAIConfig::new(api_host, "sk-ant-test").backend(ChatBackend::Anthropic);
+    let message = generate_message(&prompt, config, &None, &[]).await?;
+    assert_eq!(message, "Add tests");
+    assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_generate_message_with_ollama() -> anyhow::Result<()> {
+    use super::{generate_message, ChatBackend, Message, MessageRole, OAIConfig};
+
+    let (api_host, requests) = serve(|_| {
+        (
+            "application/json",
+            String::from(
+                r#"{"model":"llama3.2","message":{"role":"assistant","content":"Add tests"},"done":true}"#,
+            ),
+        )
+    })
+    .await?;
+    let prompt = vec![Message::new(MessageRole::User, String::from("diff"))];
+    let config = OAIConfig::new(api_host, "").backend(ChatBackend::Ollama);
+    let message = generate_message(&prompt, config, &Some(String::from("llama3.2")), &[]).await?;
+    assert_eq!(message, "Add tests");
+    assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_message_cache() {
+    use super::{CachedMessage, MessageCache};
+
+    let cached = |message: &str| {
+        vec![CachedMessage {
+            message: message.to_owned(),
+            temperature: None,
+        }]
+    };
+    let key = MessageCache::key(&["diff", "config", "1"]);
+    // The key must not change between builds, or the cache is never reused
+    assert_eq!(
+        key,
+        "1a0886d089e72c74a229db6bbdb8a983000db7ae73c872225e371bf7dd25705d"
+    );
+    assert_ne!(key, MessageCache::key(&["diff", "config", "2"]));
+    assert_ne!(
+        MessageCache::key(&["ab", "c"]),
+        MessageCache::key(&["a", "bc"])
+    );
+
+    let mut cache = MessageCache::default();
+    assert!(cache.get(&key, 0).is_none());
+    cache.insert(key.clone(), cached("Add tests"), 1_000);
+    assert_eq!(cache.get(&key, 1_000), Some(cached("Add tests").as_slice()));
+    let week = 7 * 24 * 60 * 60;
+    assert!(cache.get(&key, 1_000 + week).is_some());
+    assert!(cache.get(&key, 1_001 + week).is_none());
+
+    // Inserting drops expired entries, then the oldest beyond the bound
+    for i in 0..100 {
+        cache.insert(i.to_string(), cached("Fix bug"), 2_000 + week + i);
+    }
+    assert!(cache.get(&key, 2_000 + week).is_none());
+    assert!(cache.get("0", 2_100 + week).is_some());
+    cache.insert(String::from("new"), cached("Fix bug"), 2_100 + week);
+    assert!(cache.get("0", 2_100 + week).is_none());
+    assert!(cache.get("1", 2_100 + week).is_some());
+    assert!(cache.get("new", 2_100 + week).is_some());
+}
+
+#[test]
+fn test_resolve_proxy() {
+    use super::openai::resolve_proxy;
+
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    };
+    let url = "https://api.openai.com/v1/chat/completions";
+
+    assert_eq!(resolve_proxy(url, None, env(&[])), None);
+    assert_eq!(
+        resolve_proxy(url, None, env(&[("HTTPS_PROXY", "http://proxy:8080")])).as_deref(),
+        Some("http://proxy:8080")
+    );
+    assert_eq!(
+        resolve_proxy(url, None, env(&[("https_proxy", "http://proxy:8080")])).as_deref(),
+        Some("http://proxy:8080")
+    );
+    assert_eq!(
+        resolve_proxy(url, None, env(&[("HTTP_PROXY", "http://proxy:8080")])),
+        None
+    );
+    assert_eq!(
+        resolve_proxy(
+            "http://localhost:11434",
+            None,
+            env(&[("HTTP_PROXY", "http://proxy:8080")])
+        )
+        .as_deref(),
+        Some("http://proxy:8080")
+    );
+    assert_eq!(
+        resolve_proxy(url, None, env(&[("ALL_PROXY", "socks5://proxy:1080")])).as_deref(),
+        Some("socks5://proxy:1080")
+    );
+    assert_eq!(
+        resolve_proxy(
+            url,
+            Some("http://config-proxy:3128"),
+            env(&[("HTTPS_PROXY", "http://proxy:8080")])
+        )
+        .as_deref(),
+        Some("http://config-proxy:3128")
+    );
+
+    let no_proxy = env(&[
+        ("HTTPS_PROXY", "http://proxy:8080"),
+        ("NO_PROXY", "localhost, .openai.com"),
+    ]);
+    assert_eq!(resolve_proxy(url, None, no_proxy), None);
+    assert_eq!(
+        resolve_proxy("https://localhost:8443", None, no_proxy),
+        None
+    );
+    assert!(resolve_proxy("https://api.anthropic.com", None, no_proxy).is_some());
+    assert_eq!(
+        resolve_proxy(
+            url,
+            Some("http://config-proxy:3128"),
+            env(&[("NO_PROXY", "*")])
+        ),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_generate_message_through_proxy() -> anyhow::Result<()> {
+    use super::{generate_message, Message, MessageRole, OAIConfig};
+
+    // The API host can't be reached directly, only through the proxy
+    let (proxy, requests) = serve_completions(vec!["Add tests"]).await?;
+    let prompt = vec![Message::new(MessageRole::User, String::from("diff"))];
+    let config = OAIConfig::new("http://api.invalid", "sk-test").proxy(Some(proxy));
+    let message = generate_message(&prompt, config, &None, &[]).await?;
+    assert_eq!(message, "Add tests");
+    assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_state_round_trip() -> anyhow::Result<()> {
+    use super::State;
+
+    let home = TempHome::new()?;
+    let state = State::load().await?;
+    assert_eq!(state.get_last_remote("/work/app"), None);
+
+    let mut state = State::default();
+    state.set_last_remote("/work/app", "origin");
+    state.set_last_remote("/work/lib", "upstream");
+    state.save().await?;
+    assert!(home.dir.path().join(".autocommit.state").exists());
+
+    let mut state = State::load().await?;
+    assert_eq!(state.get_last_remote("/work/app"), Some("origin"));
+    assert_eq!(state.get_last_remote("/work/lib"), Some("upstream"));
+    assert_eq!(state.get_last_remote("/work/other"), None);
+
+    // Each repository keeps its own remote
+    state.set_last_remote("/work/app", "fork");
+    assert_eq!(state.get_last_remote("/work/app"), Some("fork"));
+    assert_eq!(state.get_last_remote("/work/lib"), Some("upstream"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_current_dir_guard_changes_back() -> anyhow::Result<()> {
+    use super::CurrentDirGuard;
+    use crate::git::tests::TestRepo;
+
+    // Holds the lock on the working directory other tests change too
+    let _repo = TestRepo::new().await?;
+    let original = std::env::current_dir()?;
+    let subdir = original.join("sub");
+    std::fs::create_dir(&subdir)?;
+
+    let guard = CurrentDirGuard::change_to(&subdir)?;
+    assert_eq!(std::env::current_dir()?, subdir);
+    drop(guard);
+    assert_eq!(std::env::current_dir()?, original);
+
+    assert!(CurrentDirGuard::change_to(&original.join("missing")).is_err());
+    assert_eq!(std::env::current_dir()?, original);
+
+    Ok(())
+}